@@ -1,18 +1,26 @@
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
 use dashmap::DashMap;
+use log::warn;
 
 use crate::Result;
 use crate::error::DbError;
 use crate::executor::mapper::Mapper;
-use crate::executor::session::Session;
-use crate::udbc::DEFAULT_DB_NAME;
+use crate::executor::session::{Session, SessionConfig, SessionFactory};
+use crate::executor::splitting::SplittingSession;
 use crate::udbc::driver::Driver;
+use crate::udbc::{DEFAULT_DB_NAME, PoolStats};
 
 /// The global entry point for the `uorm` library.
 /// Use this singleton to register drivers, load mapper assets, and create sessions or mappers.
 pub static U: LazyLock<DriverManager> = LazyLock::new(DriverManager::new);
 
+/// Alternative name for [`U`]. It refers to the same instance, so drivers registered through
+/// either name are visible through both.
+#[deprecated(note = "use `uorm::U` (or `uorm::driver_manager::U`) instead")]
+pub static UORM: &LazyLock<DriverManager> = &U;
+
 /// A manager for database drivers and their associated connection pools.
 ///
 /// `DriverManager` acts as a registry where different database drivers (MySQL, SQLite, etc.)
@@ -21,6 +29,59 @@ pub static U: LazyLock<DriverManager> = LazyLock::new(DriverManager::new);
 pub struct DriverManager {
     /// A thread-safe map storing registered database drivers by their unique names.
     pools: DashMap<String, Arc<dyn Driver>>,
+    /// The database used by [`DriverManager::session`] and [`DriverManager::mapper`].
+    default_db: String,
+    /// Receives every driver's stats in [`DriverManager::collect_pool_stats`].
+    stats_collector: Option<PoolStatsCollector>,
+}
+
+/// Callback given each driver's name and pool statistics.
+pub type PoolStatsCollector = Arc<dyn Fn(&str, &PoolStats) + Send + Sync>;
+
+/// Builds a standalone [`DriverManager`].
+///
+/// Unlike [`U`], the built manager is an ordinary value: drivers registered with it are only
+/// visible through it and are released when it is dropped, which keeps tests isolated.
+/// Mapper statements are still loaded into the process-wide registry, so `assets` and
+/// `mapper_assets!` behave the same for every manager.
+///
+/// ```ignore
+/// let manager = DriverManager::builder().default_db("test").build();
+/// manager.register(SqliteDriver::new("sqlite:test.db").name("test").build()?)?;
+/// let session = manager.session().unwrap();
+/// ```
+#[derive(Default)]
+pub struct DriverManagerBuilder {
+    default_db: Option<String>,
+    stats_collector: Option<PoolStatsCollector>,
+}
+
+impl DriverManagerBuilder {
+    /// Sets the database used by `session()` and `mapper()` (defaults to `"default"`).
+    pub fn default_db(mut self, name: impl Into<String>) -> Self {
+        self.default_db = Some(name.into());
+        self
+    }
+
+    /// Sets a callback that [`DriverManager::collect_pool_stats`] calls for each driver,
+    /// e.g. to export pool metrics.
+    pub fn pool_stats_collector(
+        mut self,
+        collector: impl Fn(&str, &PoolStats) + Send + Sync + 'static,
+    ) -> Self {
+        self.stats_collector = Some(Arc::new(collector));
+        self
+    }
+
+    pub fn build(self) -> DriverManager {
+        DriverManager {
+            pools: DashMap::new(),
+            default_db: self
+                .default_db
+                .unwrap_or_else(|| DEFAULT_DB_NAME.to_string()),
+            stats_collector: self.stats_collector,
+        }
+    }
 }
 
 impl Default for DriverManager {
@@ -32,27 +93,42 @@ impl Default for DriverManager {
 impl DriverManager {
     /// Creates a new, empty `DriverManager`.
     pub fn new() -> Self {
-        Self {
-            pools: DashMap::new(),
-        }
+        Self::builder().build()
+    }
+
+    /// Starts building a standalone `DriverManager`; see [`DriverManagerBuilder`].
+    pub fn builder() -> DriverManagerBuilder {
+        DriverManagerBuilder::default()
     }
 
     /// Registers a database driver with the manager.
     ///
     /// The driver's name (retrieved via `driver.name()`) is used as the registration key.
     ///
+    /// When called inside a Tokio runtime, the driver's pool is warmed up in the background
+    /// (see [`Driver::warmup`]).
+    ///
     /// # Errors
     /// Returns an error if a driver with the same name (especially the default name)
     /// is already registered.
     pub fn register(&self, driver: impl Driver + 'static) -> Result<()> {
         let name = driver.name().to_string();
-        if name == DEFAULT_DB_NAME && self.pools.contains_key(&name) {
+        if name == self.default_db && self.pools.contains_key(&name) {
             return Err(DbError::DriverError(format!(
                 "Driver with name '{}' already registered",
                 name
             )));
         }
-        self.pools.insert(name, Arc::new(driver));
+        let driver: Arc<dyn Driver> = Arc::new(driver);
+        self.pools.insert(name.clone(), driver.clone());
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = driver.warmup().await {
+                    warn!("Failed to warm up driver '{}': {}", name, e);
+                }
+            });
+        }
         Ok(())
     }
 
@@ -62,18 +138,29 @@ impl DriverManager {
     ///
     /// # Arguments
     /// * `pattern` - A glob pattern (e.g., "resources/mappers/*.xml") to find mapper files.
+    ///
+    /// Unresolved `<include>` references are logged as warnings
+    /// (see [`crate::mapper_loader::validate`]).
     pub fn assets(&self, pattern: &str) -> Result<()> {
         crate::mapper_loader::load(pattern).map_err(|e| {
             DbError::MapperLoadError(format!("Failed to load mapper assets from pattern: {}", e))
-        })
+        })?;
+        for w in crate::mapper_loader::validate()? {
+            warn!(
+                "Unresolved include '{}' in {}.{}",
+                w.missing_ref, w.namespace, w.statement_id
+            );
+        }
+        Ok(())
     }
 
-    /// Creates a `Session` for the default database.
+    /// Creates a `Session` for the default database (`"default"` unless set with
+    /// [`DriverManagerBuilder::default_db`]).
     ///
     /// # Returns
     /// `Some(Session)` if the default driver is registered, otherwise `None`.
     pub fn session(&self) -> Option<Session> {
-        self.session_by_name(DEFAULT_DB_NAME)
+        self.session_by_name(&self.default_db)
     }
 
     /// Creates a `Session` for the specified database by name.
@@ -88,12 +175,38 @@ impl DriverManager {
             .map(|v| Session::new(v.value().clone()))
     }
 
-    /// Creates a `Mapper` for the default database.
+    /// Creates a [`SessionFactory`] that builds sessions for `db_name` with `config`.
+    ///
+    /// # Errors
+    /// Returns `DbError::DriverError` if no driver named `db_name` is registered.
+    pub fn session_factory(&self, db_name: &str, config: SessionConfig) -> Result<SessionFactory> {
+        let pool = self.pools.get(db_name).ok_or_else(|| {
+            DbError::DriverError(format!("Driver with name '{}' not registered", db_name))
+        })?;
+        Ok(SessionFactory::new(pool.value().clone(), config))
+    }
+
+    /// Creates a `SplittingSession` that writes to `primary_db` and reads from `replica_db`.
+    ///
+    /// # Returns
+    /// `Some(SplittingSession)` if both drivers are registered, otherwise `None`.
+    pub fn splitting_session(
+        &self,
+        primary_db: &str,
+        replica_db: &str,
+    ) -> Option<SplittingSession> {
+        let primary = self.session_by_name(primary_db)?;
+        let replica = self.session_by_name(replica_db)?;
+        Some(SplittingSession::new(primary, replica))
+    }
+
+    /// Creates a `Mapper` for the default database (`"default"` unless set with
+    /// [`DriverManagerBuilder::default_db`]).
     ///
     /// # Returns
     /// `Some(Mapper)` if the default driver is registered, otherwise `None`.
     pub fn mapper(&self) -> Option<Mapper> {
-        self.mapper_by_name(DEFAULT_DB_NAME)
+        self.mapper_by_name(&self.default_db)
     }
 
     /// Creates a `Mapper` for the specified database by name.
@@ -107,4 +220,24 @@ impl DriverManager {
             .get(db_name)
             .map(|v| Mapper::new(v.value().clone()))
     }
+
+    /// Collects pool statistics from every registered driver, keyed by driver name.
+    pub fn all_pool_stats(&self) -> HashMap<String, PoolStats> {
+        self.pools
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().pool_stats()))
+            .collect()
+    }
+
+    /// Like [`DriverManager::all_pool_stats`], and also passes each driver's stats to the
+    /// collector set with [`DriverManagerBuilder::pool_stats_collector`].
+    pub fn collect_pool_stats(&self) -> HashMap<String, PoolStats> {
+        let stats = self.all_pool_stats();
+        if let Some(collector) = &self.stats_collector {
+            for (name, stats) in &stats {
+                collector(name, stats);
+            }
+        }
+        stats
+    }
 }
@@ -18,16 +18,74 @@ pub enum DbError {
     DriverError(String),
     #[error("Template Engine Error: {0}")]
     TemplateEngineError(String),
+    /// A value in the render context didn't fit the template node using it, e.g. a
+    /// `<foreach>` over something that isn't a list.
+    #[error("Template Error in '{template_name}': {message}")]
+    TemplateError {
+        template_name: String,
+        message: String,
+        /// The tag or expression kind that failed, such as `foreach` or `${}`.
+        node_type: Option<String>,
+    },
     #[error("SQL Execution Error: {0}")]
     SqlExecutionError(String),
     #[error("Type Mismatch: {0}")]
     TypeMismatch(String),
     #[error("Missing Field: {0}")]
     MissingField(String),
+    #[error("Not Found: {0}")]
+    NotFound(String),
+    #[error("Too Many Rows: expected 1, got {0}")]
+    TooManyRows(usize),
+    #[error("Timeout: {0}")]
+    Timeout(String),
+    /// A failure the database reported as temporary (e.g. a deadlock); retrying may succeed.
+    #[error("Transient Error: {0}")]
+    Transient(String),
+    #[error("Optimistic Lock Conflict: {0}")]
+    OptimisticLockConflict(String),
+    /// One execution of a batch failed; `index` is its position in the argument list.
+    #[error("Batch Error: execution {index} failed: {source}")]
+    BatchError {
+        index: usize,
+        #[source]
+        source: Box<DbError>,
+    },
     #[error("Custom Error: {0}")]
     Custom(String),
 }
 
+/// Lower-cased message fragments of errors that usually go away on retry.
+const RETRYABLE_PATTERNS: &[&str] = &[
+    "connection refused",
+    "connection reset",
+    "connection closed",
+    "broken pipe",
+    "timed out",
+    "deadlock",
+    "lock wait timeout",
+    "database is locked",
+];
+
+impl DbError {
+    /// Whether retrying the failed operation may succeed.
+    ///
+    /// `Timeout` and `Transient` errors are retryable. Generic database and SQL execution
+    /// errors are retryable when their message looks like a connection drop, timeout or
+    /// deadlock. Configuration, mapping and type errors never are.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DbError::Timeout(_) | DbError::Transient(_) => true,
+            DbError::BatchError { source, .. } => source.is_retryable(),
+            DbError::DbError(msg) | DbError::SqlExecutionError(msg) => {
+                let msg = msg.to_ascii_lowercase();
+                RETRYABLE_PATTERNS.iter().any(|p| msg.contains(p))
+            }
+            _ => false,
+        }
+    }
+}
+
 // Aliases for compatibility
 pub type Error = DbError;
 pub type SerdeError = DbError;
@@ -43,3 +101,29 @@ impl serde::de::Error for DbError {
         DbError::SerializationError(msg.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(DbError::Timeout("acquire".to_string()).is_retryable());
+        assert!(DbError::Transient("deadlock".to_string()).is_retryable());
+        assert!(DbError::DbError("Connection refused (os error 111)".to_string()).is_retryable());
+        assert!(
+            DbError::DbError("Deadlock found when trying to get lock".to_string()).is_retryable()
+        );
+        assert!(!DbError::DbError("syntax error near FROM".to_string()).is_retryable());
+        assert!(!DbError::TypeMismatch("deadlock".to_string()).is_retryable());
+        assert!(!DbError::MissingField("id".to_string()).is_retryable());
+        assert!(!DbError::MapperLoadError("timed out".to_string()).is_retryable());
+        assert!(!DbError::DriverError("Invalid pool options".to_string()).is_retryable());
+        let batch = |source| DbError::BatchError {
+            index: 3,
+            source: Box::new(source),
+        };
+        assert!(batch(DbError::Transient("deadlock".to_string())).is_retryable());
+        assert!(!batch(DbError::MissingField("id".to_string())).is_retryable());
+    }
+}
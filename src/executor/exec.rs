@@ -1,34 +1,80 @@
 use crate::Result;
 use crate::error::DbError;
-use crate::tpl::engine;
-use crate::udbc::connection::Connection;
+use crate::tpl::engine::{self, Rendered};
+use crate::udbc::connection::{Connection, Rows};
 use crate::udbc::driver::Driver;
-use crate::udbc::value::{FromValue, ToValue, Value};
-use log::debug;
+use crate::udbc::value::{FromValue, REDACTED, ToValue, Value};
+use log::{Level, log, log_enabled};
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::time::Instant;
 
 /// Executes a SQL statement (INSERT, UPDATE, DELETE) on the given connection.
+///
+/// Like [`engine::render_template`], `${...}` is rendered as long as the value is a plain
+/// identifier, so `sql` must be trusted.
 pub async fn execute_conn<T: ToValue>(
     conn: &mut dyn Connection,
     driver: &dyn Driver,
     template_name: &str,
     sql: &str,
     args: &T,
+) -> Result<u64> {
+    execute_conn_logged(conn, driver, template_name, sql, args, Level::Debug, true).await
+}
+
+/// [`execute_conn`] that logs the statement at `level` instead of `Debug`, and renders
+/// `${...}` only if `allow_raw`.
+pub(crate) async fn execute_conn_logged<T: ToValue>(
+    conn: &mut dyn Connection,
+    driver: &dyn Driver,
+    template_name: &str,
+    sql: &str,
+    args: &T,
+    level: Level,
+    allow_raw: bool,
 ) -> Result<u64> {
     let start = Instant::now();
-    let (rendered_sql, params) = engine::render_template(template_name, sql, args, driver)?;
+    let logged = log_enabled!(level).then(|| args.to_log_value());
+    let Rendered {
+        sql: rendered_sql,
+        params,
+        sensitive,
+    } = engine::render_logged(
+        template_name,
+        sql,
+        args,
+        logged.as_ref(),
+        allow_raw,
+        driver,
+        &driver.parser_config(),
+    )?;
     let result = conn.execute(&rendered_sql, &params).await;
     let elapsed = start.elapsed().as_millis();
 
+    if !log_enabled!(level) {
+        return result;
+    }
+    let params = to_log_repr(&params, &sensitive);
+    #[cfg(feature = "log-safe-params")]
+    let params = safe_log_params(params);
     match &result {
-        Ok(affected) => debug!(
+        Ok(affected) => log!(
+            level,
             "Execute: sql=\n{}, params={:?}, elapsed={}ms, affected={}",
-            &rendered_sql, &params, elapsed, affected
+            &rendered_sql,
+            &params,
+            elapsed,
+            affected
         ),
-        Err(e) => debug!(
+        Err(e) => log!(
+            level,
             "Execute: sql=\n{}, params={:?}, elapsed={}ms, error={:?}",
-            &rendered_sql, &params, elapsed, e
+            &rendered_sql,
+            &params,
+            elapsed,
+            e
         ),
     }
 
@@ -36,6 +82,9 @@ pub async fn execute_conn<T: ToValue>(
 }
 
 /// Executes a SQL query on the given connection and returns raw rows.
+///
+/// Like [`engine::render_template`], `${...}` is rendered as long as the value is a plain
+/// identifier, so `sql` must be trusted.
 pub async fn query_conn<T: ToValue>(
     conn: &mut dyn Connection,
     driver: &dyn Driver,
@@ -43,26 +92,220 @@ pub async fn query_conn<T: ToValue>(
     sql: &str,
     args: &T,
 ) -> Result<Vec<HashMap<String, Value>>> {
+    query_conn_logged(conn, driver, template_name, sql, args, Level::Debug, true)
+        .await
+        .map(|rows| rows.rows)
+}
+
+/// [`query_conn`] with the log level and `${...}` handling of [`execute_conn_logged`], also
+/// returning the column order.
+pub(crate) async fn query_conn_logged<T: ToValue>(
+    conn: &mut dyn Connection,
+    driver: &dyn Driver,
+    template_name: &str,
+    sql: &str,
+    args: &T,
+    level: Level,
+    allow_raw: bool,
+) -> Result<Rows> {
     let start = Instant::now();
-    let (rendered_sql, params) = engine::render_template(template_name, sql, args, driver)?;
-    let result: Result<Vec<HashMap<String, Value>>> = conn.query(&rendered_sql, &params).await;
+    let logged = log_enabled!(level).then(|| args.to_log_value());
+    let Rendered {
+        sql: rendered_sql,
+        params,
+        sensitive,
+    } = engine::render_logged(
+        template_name,
+        sql,
+        args,
+        logged.as_ref(),
+        allow_raw,
+        driver,
+        &driver.parser_config(),
+    )?;
+    let result = conn.query_with_columns(&rendered_sql, &params).await;
     let elapsed = start.elapsed().as_millis();
 
-    match &result {
-        Ok(rows) => debug!(
+    log_query(
+        level,
+        &rendered_sql,
+        &params,
+        &sensitive,
+        elapsed,
+        result.as_ref().map(|rows| rows.rows.len()),
+    );
+    result
+}
+
+/// Executes a SQL query on the given connection and returns only its first row.
+///
+/// Uses [`Connection::query_one_with_columns`], so drivers can stop reading after the first
+/// row. `${...}` is handled as in [`query_conn`].
+pub async fn query_one_conn<T: ToValue>(
+    conn: &mut dyn Connection,
+    driver: &dyn Driver,
+    template_name: &str,
+    sql: &str,
+    args: &T,
+) -> Result<Option<HashMap<String, Value>>> {
+    query_one_conn_logged(conn, driver, template_name, sql, args, Level::Debug, true)
+        .await
+        .map(|rows| rows.rows.into_iter().next())
+}
+
+/// [`query_one_conn`] with the log level and `${...}` handling of [`execute_conn_logged`],
+/// also returning the column order.
+pub(crate) async fn query_one_conn_logged<T: ToValue>(
+    conn: &mut dyn Connection,
+    driver: &dyn Driver,
+    template_name: &str,
+    sql: &str,
+    args: &T,
+    level: Level,
+    allow_raw: bool,
+) -> Result<Rows> {
+    let start = Instant::now();
+    let logged = log_enabled!(level).then(|| args.to_log_value());
+    let Rendered {
+        sql: rendered_sql,
+        params,
+        sensitive,
+    } = engine::render_logged(
+        template_name,
+        sql,
+        args,
+        logged.as_ref(),
+        allow_raw,
+        driver,
+        &driver.parser_config(),
+    )?;
+    let result = conn.query_one_with_columns(&rendered_sql, &params).await;
+    let elapsed = start.elapsed().as_millis();
+
+    log_query(
+        level,
+        &rendered_sql,
+        &params,
+        &sensitive,
+        elapsed,
+        result.as_ref().map(|rows| rows.rows.len()),
+    );
+    result
+}
+
+fn log_query(
+    level: Level,
+    rendered_sql: &str,
+    params: &[(String, Value)],
+    sensitive: &[bool],
+    elapsed: u128,
+    rows: std::result::Result<usize, &DbError>,
+) {
+    if !log_enabled!(level) {
+        return;
+    }
+    let params = to_log_repr(params, sensitive);
+    #[cfg(feature = "log-safe-params")]
+    let params = safe_log_params(params);
+    match rows {
+        Ok(rows) => log!(
+            level,
             "Query: sql=\n{}, params={:?}, elapsed={}ms, rows={}",
-            &rendered_sql,
+            rendered_sql,
             &params,
             elapsed,
-            rows.len()
+            rows
         ),
-        Err(e) => debug!(
+        Err(e) => log!(
+            level,
             "Query: sql=\n{}, params={:?}, elapsed={}ms, error={:?}",
-            &rendered_sql, &params, elapsed, e
+            rendered_sql,
+            &params,
+            elapsed,
+            e
         ),
     }
+}
 
-    result
+/// Longest string parameter shown in logs with the `log-safe-params` feature.
+#[cfg(feature = "log-safe-params")]
+const LOG_MAX_STRING_LEN: usize = 64;
+
+/// Renders logged parameters with [`Value::display_safe`].
+#[cfg(feature = "log-safe-params")]
+fn safe_log_params(params: Vec<(String, Value)>) -> Vec<(String, String)> {
+    params
+        .into_iter()
+        .map(|(name, v)| (name, v.display_safe(LOG_MAX_STRING_LEN)))
+        .collect()
+}
+
+/// Returns the bound parameters as they may appear in logs: those the render marked as
+/// `sensitive` (bound from `#[param(sensitive)]` fields) are replaced with [`REDACTED`].
+pub(crate) fn to_log_repr(params: &[(String, Value)], sensitive: &[bool]) -> Vec<(String, Value)> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(i, (name, v))| {
+            if sensitive.get(i).copied().unwrap_or(false) {
+                (name.clone(), Value::Str(REDACTED.to_string()))
+            } else {
+                (name.clone(), v.clone())
+            }
+        })
+        .collect()
+}
+
+/// A stable 64-bit hash of `args`, used to correlate log lines without logging the values.
+///
+/// Map entries are hashed in key order, so equal arguments always hash the same.
+pub(crate) fn params_hash<T: ToValue>(args: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_value(&args.as_value(), &mut hasher);
+    hasher.finish()
+}
+
+fn hash_value<H: Hasher>(v: &Value, h: &mut H) {
+    std::mem::discriminant(v).hash(h);
+    match v {
+        Value::Null => {}
+        Value::Bool(b) => b.hash(h),
+        Value::Char(c) => c.hash(h),
+        Value::Str(s) | Value::Enum(s) => s.hash(h),
+        Value::I8(n) => n.hash(h),
+        Value::I16(n) => n.hash(h),
+        Value::I32(n) => n.hash(h),
+        Value::I64(n) => n.hash(h),
+        Value::I128(n) => n.hash(h),
+        Value::U8(n) => n.hash(h),
+        Value::U16(n) => n.hash(h),
+        Value::U32(n) => n.hash(h),
+        Value::U64(n) => n.hash(h),
+        Value::U128(n) => n.hash(h),
+        Value::F32(f) => f.to_bits().hash(h),
+        Value::F64(f) => f.to_bits().hash(h),
+        Value::Bytes(b) => b.hash(h),
+        Value::Date(d) => d.hash(h),
+        Value::Time(t) => t.hash(h),
+        Value::DateTime(dt) => dt.hash(h),
+        Value::DateTimeUtc(dt) => dt.hash(h),
+        Value::Decimal(d) => d.hash(h),
+        Value::List(items) => {
+            items.len().hash(h);
+            for item in items {
+                hash_value(item, h);
+            }
+        }
+        Value::Map(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            keys.len().hash(h);
+            for k in keys {
+                k.hash(h);
+                hash_value(&map[k], h);
+            }
+        }
+    }
 }
 
 /// Maps raw database rows to the target type `R`.
@@ -70,10 +313,177 @@ pub fn map_rows<R>(rows: Vec<HashMap<String, Value>>) -> Result<Vec<R>>
 where
     R: FromValue,
 {
+    map_ordered_rows(Rows {
+        columns: Vec::new(),
+        rows,
+    })
+}
+
+/// [`map_rows`] that falls back to mapping each row as the list of its values in column
+/// order, for types that don't take a map, such as tuples.
+pub(crate) fn map_ordered_rows<R>(rows: Rows) -> Result<Vec<R>>
+where
+    R: FromValue,
+{
+    let Rows { columns, rows } = rows;
+    // The first row decides how every row is mapped.
+    let mut positional = None;
     rows.into_iter()
-        .map(|r| {
-            R::from_value(Value::Map(r))
-                .map_err(|e| DbError::SerializationError(format!("Row mapping failed: {:?}", e)))
+        .map(|row| {
+            let mapped = match positional {
+                Some(true) => R::from_value(row_values(row, &columns)),
+                Some(false) => R::from_value(Value::Map(row)),
+                None => {
+                    let (mapped, by_position) = map_first_row(row, &columns);
+                    positional = Some(by_position);
+                    mapped
+                }
+            };
+            mapped.map_err(|e| DbError::SerializationError(format!("Row mapping failed: {:?}", e)))
         })
         .collect()
 }
+
+/// Maps a row as a map of its columns or, if `R` rejects that and the column order is
+/// known, as the list of its values. Also tells whether the list was used.
+pub(crate) fn map_first_row<R: FromValue>(
+    row: HashMap<String, Value>,
+    columns: &[String],
+) -> (Result<R>, bool) {
+    // Duplicate column names collapse in the map, so their order can't be recovered.
+    if columns.is_empty() || columns.len() != row.len() {
+        return (R::from_value(Value::Map(row)), false);
+    }
+    match R::from_value(Value::Map(row.clone())) {
+        Ok(v) => (Ok(v), false),
+        Err(map_err) => match R::from_value(row_values(row, columns)) {
+            Ok(v) => (Ok(v), true),
+            Err(_) => (Err(map_err), false),
+        },
+    }
+}
+
+/// Maps the first of `rows` with [`map_single_row`], or returns `None` if there are none.
+pub(crate) fn map_optional_row<R: FromValue>(rows: Rows) -> Result<Option<R>> {
+    let Rows { columns, rows } = rows;
+    rows.into_iter()
+        .next()
+        .map(|row| map_single_row(row, &columns))
+        .transpose()
+}
+
+/// Maps a single row to `R`: as a map of its columns, as the list of its values in column
+/// order (e.g. a tuple), or, for a single-column row, as its only value (e.g. an `i64`).
+pub(crate) fn map_single_row<R: FromValue>(
+    row: HashMap<String, Value>,
+    columns: &[String],
+) -> Result<R> {
+    if row.len() != 1 {
+        return map_first_row(row, columns).0;
+    }
+    if let (Ok(v), _) = map_first_row(row.clone(), columns) {
+        return Ok(v);
+    }
+    let (_, only_val) = row.into_iter().next().unwrap();
+    R::from_value(only_val)
+}
+
+/// The values of a row as a `Value::List`, in `columns` order.
+fn row_values(mut row: HashMap<String, Value>, columns: &[String]) -> Value {
+    Value::List(
+        columns
+            .iter()
+            .map(|c| row.remove(c).unwrap_or(Value::Null))
+            .collect(),
+    )
+}
+
+/// Maps exactly one row to `R`.
+///
+/// Returns `DbError::NotFound(not_found_msg)` for an empty result and
+/// `DbError::TooManyRows(n)` when more than one row was returned.
+pub fn map_one_required<R>(rows: Vec<HashMap<String, Value>>, not_found_msg: &str) -> Result<R>
+where
+    R: FromValue,
+{
+    match rows.len() {
+        0 => Err(DbError::NotFound(not_found_msg.to_string())),
+        1 => {
+            let row = rows.into_iter().next().unwrap();
+            R::from_value(Value::Map(row))
+        }
+        n => Err(DbError::TooManyRows(n)),
+    }
+}
+
+/// Builds a map from two columns of each row; a repeated key keeps the last row's value.
+///
+/// Returns `DbError::MissingField` if a row lacks `key_col` or `val_col`.
+pub fn map_key_value<K, V>(
+    rows: Vec<HashMap<String, Value>>,
+    key_col: &str,
+    val_col: &str,
+) -> Result<HashMap<K, V>>
+where
+    K: FromValue + Eq + Hash,
+    V: FromValue,
+{
+    let mut map = HashMap::with_capacity(rows.len());
+    for mut row in rows {
+        let key = row
+            .remove(key_col)
+            .ok_or_else(|| DbError::MissingField(key_col.to_string()))?;
+        let val = row
+            .remove(val_col)
+            .ok_or_else(|| DbError::MissingField(val_col.to_string()))?;
+        map.insert(K::from_value(key)?, V::from_value(val)?);
+    }
+    Ok(map)
+}
+
+/// Collects one column from every row.
+///
+/// Returns `DbError::MissingField` if a row lacks `column`.
+pub fn map_column<T: FromValue>(rows: Vec<HashMap<String, Value>>, column: &str) -> Result<Vec<T>> {
+    rows.into_iter()
+        .map(|mut row| {
+            let v = row
+                .remove(column)
+                .ok_or_else(|| DbError::MissingField(column.to_string()))?;
+            T::from_value(v)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_log_repr_masks_sensitive_params() {
+        let params = vec![
+            ("name".to_string(), Value::Str("hunter2".to_string())),
+            ("password".to_string(), Value::Str("hunter2".to_string())),
+        ];
+        assert_eq!(
+            to_log_repr(&params, &[false, true]),
+            vec![
+                ("name".to_string(), Value::Str("hunter2".to_string())),
+                ("password".to_string(), Value::Str(REDACTED.to_string())),
+            ]
+        );
+
+        // Parameters rendered without log tracking are logged as-is.
+        assert_eq!(to_log_repr(&params, &[]), params);
+    }
+
+    #[test]
+    fn test_params_hash_is_order_independent() {
+        let a = HashMap::from([("a".to_string(), 1i64), ("b".to_string(), 2i64)]);
+        let b = HashMap::from([("b".to_string(), 2i64), ("a".to_string(), 1i64)]);
+        assert_eq!(params_hash(&a), params_hash(&b));
+
+        let c = HashMap::from([("a".to_string(), 1i64), ("b".to_string(), 3i64)]);
+        assert_ne!(params_hash(&a), params_hash(&c));
+    }
+}
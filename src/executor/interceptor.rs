@@ -0,0 +1,159 @@
+use crate::Result;
+use crate::executor::session::{Session, inline_template_name};
+use crate::udbc::value::{FromValue, ToValue, Value};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Hooks invoked around every query and execute call of a [`SessionWithInterceptor`].
+///
+/// Useful for cross-cutting concerns such as auditing, authorization or metrics.
+#[async_trait]
+pub trait QueryInterceptor: Send + Sync {
+    /// Called before the statement runs. Returning `Err` aborts the call with that error.
+    async fn before_query(&self, _sql_id: &str, _args: &Value) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after the statement succeeded.
+    ///
+    /// `rows` is the number of returned rows for queries and affected rows for executes.
+    async fn after_query(&self, _sql_id: &str, _rows: usize, _elapsed_ms: u64) {}
+}
+
+/// A `Session` that runs a chain of [`QueryInterceptor`]s around each call.
+///
+/// `before_query` hooks run in registration order and stop at the first error;
+/// `after_query` hooks run in registration order once the statement succeeded.
+pub struct SessionWithInterceptor {
+    session: Session,
+    interceptors: Vec<Arc<dyn QueryInterceptor>>,
+}
+
+impl SessionWithInterceptor {
+    pub fn new(session: Session, interceptors: Vec<Arc<dyn QueryInterceptor>>) -> Self {
+        Self {
+            session,
+            interceptors,
+        }
+    }
+
+    /// Appends another interceptor to the chain.
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn QueryInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// The wrapped session.
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub async fn begin(&self) -> Result<()> {
+        self.session.begin().await
+    }
+
+    pub async fn commit(&self) -> Result<()> {
+        self.session.commit().await
+    }
+
+    pub async fn rollback(&self) -> Result<()> {
+        self.session.rollback().await
+    }
+
+    pub fn is_transaction_active(&self) -> bool {
+        self.session.is_transaction_active()
+    }
+
+    pub async fn execute<T>(&self, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        let template_name = inline_template_name(sql);
+        self.execute_named(&template_name, sql, args).await
+    }
+
+    pub async fn execute_named<T>(&self, template_name: &str, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        self.before(template_name, &args.as_value()).await?;
+
+        let start = Instant::now();
+        let affected = self.session.execute_named(template_name, sql, args).await?;
+        self.after(template_name, affected as usize, start).await;
+        Ok(affected)
+    }
+
+    pub async fn query<R, T>(&self, sql: &str, args: &T) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let rows = self.query_raw(sql, args).await?;
+        crate::executor::exec::map_rows(rows)
+    }
+
+    pub async fn query_raw<T>(&self, sql: &str, args: &T) -> Result<Vec<HashMap<String, Value>>>
+    where
+        T: ToValue,
+    {
+        let template_name = inline_template_name(sql);
+        self.query_raw_named(&template_name, sql, args).await
+    }
+
+    pub async fn query_raw_named<T>(
+        &self,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<Vec<HashMap<String, Value>>>
+    where
+        T: ToValue,
+    {
+        self.before(template_name, &args.as_value()).await?;
+
+        let start = Instant::now();
+        let rows = self
+            .session
+            .query_raw_named(template_name, sql, args)
+            .await?;
+        self.after(template_name, rows.len(), start).await;
+        Ok(rows)
+    }
+
+    pub async fn execute_with_insert_id<T>(&self, sql: &str, args: &T) -> Result<(u64, u64)>
+    where
+        T: ToValue,
+    {
+        let template_name = inline_template_name(sql);
+        self.before(&template_name, &args.as_value()).await?;
+
+        let start = Instant::now();
+        let result = self
+            .session
+            .execute_named_with_insert_id(&template_name, sql, args)
+            .await?;
+        self.after(&template_name, result.0 as usize, start).await;
+        Ok(result)
+    }
+
+    pub async fn last_insert_id(&self) -> Result<u64> {
+        self.session.last_insert_id().await
+    }
+
+    async fn before(&self, sql_id: &str, args: &Value) -> Result<()> {
+        for interceptor in &self.interceptors {
+            interceptor.before_query(sql_id, args).await?;
+        }
+        Ok(())
+    }
+
+    async fn after(&self, sql_id: &str, rows: usize, start: Instant) {
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        for interceptor in &self.interceptors {
+            interceptor.after_query(sql_id, rows, elapsed_ms).await;
+        }
+    }
+}
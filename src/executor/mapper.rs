@@ -1,33 +1,68 @@
 use crate::Result;
 use crate::error::DbError;
-use crate::executor::session::Session;
+use crate::executor::exec::{
+    map_column, map_first_row, map_key_value, map_one_required, map_optional_row, map_ordered_rows,
+    map_single_row, params_hash,
+};
+use crate::executor::session::{Session, TaskLocalTransaction};
 use crate::mapper_loader::{SqlStatement, StatementType, find_statement};
+use crate::tpl::engine;
+use crate::udbc::connection::Rows;
 use crate::udbc::driver::Driver;
 use crate::udbc::value::{FromValue, ToValue, Value};
+use log::{Level, log, log_enabled};
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Mapper client encapsulating connection pool and SQL template execution.
 ///
 /// Acts as a higher-level abstraction over `Session`, handling SQL ID lookup
 /// and result mapping based on statement type.
+#[derive(Clone)]
 pub struct Mapper {
     pub pool: Arc<dyn Driver>,
+    log_level: Level,
 }
 
 impl Mapper {
     pub fn new(pool: Arc<dyn Driver>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            log_level: Level::Trace,
+        }
+    }
+
+    /// Sets the level of the per-call summary logged by [`Mapper::execute`] (default `trace`).
+    ///
+    /// The summary carries `sql_id`, `elapsed_ms`, `rows` and a `params_hash` that correlates
+    /// calls with the same arguments without exposing their values.
+    pub fn with_log_level(mut self, level: Level) -> Self {
+        self.log_level = level;
+        self
     }
 
     /// Creates a new ephemeral session for this mapper.
     /// Note: Sessions are cheap to create (Arc clone).
+    /// Statements are checked for `${...}` against their `allowRaw` when they are
+    /// registered, so the session renders whatever it is given.
     fn session(&self) -> Session {
-        Session::new(self.pool.clone())
+        Session::new(self.pool.clone()).allow_raw(true)
     }
 
-    fn get_statement(&self, sql_id: &str) -> Result<Arc<SqlStatement>> {
-        find_statement(sql_id, self.pool.r#type())
-            .ok_or_else(|| DbError::TemplateEngineError(format!("SQL ID not found: {}", sql_id)))
+    /// Looks up `sql_id` for this mapper's database type and checks that it has SQL content,
+    /// which [`SqlStatement::sql`] then returns.
+    fn statement_sql(&self, sql_id: &str) -> Result<Arc<SqlStatement>> {
+        let stmt = find_statement(sql_id, self.pool.r#type())
+            .ok_or_else(|| DbError::TemplateEngineError(format!("SQL ID not found: {}", sql_id)))?;
+        if stmt.content.is_none() {
+            return Err(DbError::TemplateEngineError(format!(
+                "SQL content empty for {}",
+                sql_id
+            )));
+        }
+        Ok(stmt)
     }
 
     /// Executes a mapped SQL statement by ID.
@@ -37,102 +72,600 @@ impl Mapper {
     ///   - For `Select`, `R` is typically `Vec<T>`.
     ///   - For `Insert`/`Update`/`Delete`, `R` is typically `u64` (affected rows) or `i64`.
     /// * `T`: Argument type. Must be serializable (passed to the template engine).
+    ///
+    /// For a `Select`, an `R` that is not a collection (e.g. `User`, `Option<User>`, `i64`) is
+    /// built from the first row, or from its only column; if there are no rows, `Option` yields
+    /// `None` and other types fail with `DbError::NotFound`.
+    ///
+    /// Statements declared with `returning="true"` are always run as queries, and their rows
+    /// are mapped the same way as a `Select`.
     pub async fn execute<R, T>(&self, sql_id: &str, args: &T) -> Result<R>
     where
         T: ToValue,
         R: FromValue,
     {
-        let stmt = self.get_statement(sql_id)?;
-        let sql = stmt.as_ref().content.as_deref().ok_or_else(|| {
-            DbError::TemplateEngineError(format!("SQL content empty for {}", sql_id))
-        })?;
+        let start = Instant::now();
+        let result = self.execute_counted(sql_id, args).await;
+        if log_enabled!(self.log_level) {
+            let elapsed_ms = start.elapsed().as_millis();
+            let params_hash = params_hash(args);
+            match &result {
+                Ok((_, rows)) => log!(
+                    self.log_level,
+                    "Mapper: sql_id={}, elapsed_ms={}, rows={}, params_hash={:016x}",
+                    sql_id,
+                    elapsed_ms,
+                    rows,
+                    params_hash
+                ),
+                Err(e) => log!(
+                    self.log_level,
+                    "Mapper: sql_id={}, elapsed_ms={}, params_hash={:016x}, error={}",
+                    sql_id,
+                    elapsed_ms,
+                    params_hash,
+                    e
+                ),
+            }
+        }
+        result.map(|(value, _)| value)
+    }
+
+    /// Runs [`Mapper::execute`] once per entry of `args_list`, in order, and collects the
+    /// results.
+    ///
+    /// Inside a transaction every execution uses the transaction's connection, so the batch
+    /// commits or rolls back as a whole. Execution stops at the first failure, which is
+    /// returned as `DbError::BatchError` carrying the index of the failing entry.
+    pub async fn execute_many<R, T>(&self, sql_id: &str, args_list: Vec<T>) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let mut results = Vec::with_capacity(args_list.len());
+        for (index, args) in args_list.iter().enumerate() {
+            let result = self
+                .execute(sql_id, args)
+                .await
+                .map_err(|e| DbError::BatchError {
+                    index,
+                    source: Box::new(e),
+                })?;
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// [`Mapper::execute`] with parameters that were assembled at runtime, e.g. a
+    /// `Value::Map` built by hand or taken from a previous query. The value is rendered as-is,
+    /// without being copied through [`ToValue::to_value`].
+    pub async fn execute_value<R>(&self, sql_id: &str, args: Value) -> Result<R>
+    where
+        R: FromValue,
+    {
+        self.execute(sql_id, &args).await
+    }
+
+    /// Runs [`Mapper::execute`] and also returns the number of rows affected or returned.
+    async fn execute_counted<R, T>(&self, sql_id: &str, args: &T) -> Result<(R, u64)>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        if stmt.returning || stmt.r#type == StatementType::Select {
+            let rows = self.session().query_rows_named(sql_id, sql, args).await?;
+            let count = rows.rows.len() as u64;
+            return Ok((rows_to_result(sql_id, rows)?, count));
+        }
 
         match stmt.r#type {
+            StatementType::Insert if stmt.return_key => {
+                let (affected, id) = self
+                    .session()
+                    .execute_named_with_insert_id(sql_id, sql, args)
+                    .await?;
+                Ok((R::from_value(Value::U64(id))?, affected))
+            }
+            _ => {
+                let affected = self.session().execute_named(sql_id, sql, args).await?;
+                Ok((R::from_value(Value::U64(affected))?, affected))
+            }
+        }
+    }
+
+    /// Executes a mapped data-modifying statement and returns its generated key together with
+    /// the number of affected rows.
+    ///
+    /// For an `Insert` the key is read with `last_insert_id` on the same connection, whether
+    /// or not the statement declares `useGeneratedKeys`. Other statements yield
+    /// `R::from_value(Value::I64(0))` as the key. Statements that return rows (`Select` or
+    /// `returning`) are mapped like [`Mapper::execute`], paired with the number of rows.
+    pub async fn execute_and_count<R, T>(&self, sql_id: &str, args: &T) -> Result<(R, u64)>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        if stmt.returning || stmt.r#type == StatementType::Select {
+            let rows = self.session().query_rows_named(sql_id, sql, args).await?;
+            let count = rows.rows.len() as u64;
+            return Ok((rows_to_result(sql_id, rows)?, count));
+        }
+
+        if stmt.r#type == StatementType::Insert {
+            let (affected, id) = self
+                .session()
+                .execute_named_with_insert_id(sql_id, sql, args)
+                .await?;
+            return Ok((R::from_value(Value::U64(id))?, affected));
+        }
+
+        let affected = self.session().execute_named(sql_id, sql, args).await?;
+        Ok((R::from_value(Value::I64(0))?, affected))
+    }
+
+    /// Executes plain SQL with positional parameters, skipping the template engine.
+    ///
+    /// Intended for SQL produced by query builders: placeholders (`?`, `$1`, ...) are passed
+    /// through untouched and `params` are bound in order. The result shape follows
+    /// [`StatementType::infer`]: queries are mapped like a `Select` in [`Mapper::execute`],
+    /// everything else returns the affected row count.
+    pub async fn execute_raw<R>(&self, sql: &str, params: Vec<Value>) -> Result<R>
+    where
+        R: FromValue,
+    {
+        let params: Vec<(String, Value)> = params
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| ((i + 1).to_string(), v))
+            .collect();
+
+        let session = self.session();
+        match StatementType::infer(sql) {
             StatementType::Select => {
-                let rows: Vec<std::collections::HashMap<String, Value>> =
-                    self.session().query_raw_named(sql_id, sql, args).await?;
-
-                match rows.len() {
-                    0 => {
-                        let list_value = Value::List(Vec::new());
-                        if let Ok(v) = R::from_value(list_value) {
-                            return Ok(v);
-                        }
-                        if let Ok(v) = R::from_value(Value::Null) {
-                            return Ok(v);
-                        }
-                        Err(DbError::DbError(format!("No rows returned for {}", sql_id)))
-                    }
-                    1 => {
-                        let row = rows.into_iter().next().unwrap();
-
-                        let list_value = Value::List(vec![Value::Map(row.clone())]);
-                        match R::from_value(list_value) {
-                            Ok(v) => Ok(v),
-                            Err(list_err) => {
-                                let map_value = Value::Map(row.clone());
-                                if let Ok(v) = R::from_value(map_value) {
-                                    return Ok(v);
-                                }
-
-                                if row.len() == 1 {
-                                    let (_, only_val) = row.into_iter().next().unwrap();
-                                    match R::from_value(only_val) {
-                                        Ok(v) => return Ok(v),
-                                        Err(e) => return Err(e),
-                                    }
-                                }
-
-                                Err(list_err)
-                            }
-                        }
-                    }
-                    _ => {
-                        let value = Value::List(rows.into_iter().map(Value::Map).collect());
-                        Ok(R::from_value(value)?)
-                    }
-                }
+                let rows = session.query_prepared(sql, &params).await?;
+                rows_to_result(sql, rows)
             }
-            StatementType::Insert => {
-                let session = self.session();
-
-                let val = if stmt.return_key {
-                    let is_active = session.is_transaction_active();
-                    if is_active {
-                        let _ = session.execute_named(sql_id, sql, args).await?;
-                        let id = session.last_insert_id().await?;
-                        Value::U64(id)
-                    } else {
-                        // Use transaction to ensure same connection for insert and last_insert_id
-                        session.begin().await?;
-                        let result = async {
-                            let _ = session.execute_named(sql_id, sql, args).await?;
-                            session.last_insert_id().await
-                        }
-                        .await;
-
-                        match result {
-                            Ok(id) => {
-                                session.commit().await?;
-                                Value::U64(id)
-                            }
-                            Err(e) => {
-                                session.rollback().await?;
-                                return Err(e);
-                            }
-                        }
-                    }
-                } else {
-                    let affected = session.execute_named(sql_id, sql, args).await?;
-                    Value::U64(affected)
-                };
-
-                Ok(R::from_value(val)?)
+            _ => {
+                let affected = session.execute_prepared(sql, &params).await?;
+                R::from_value(Value::U64(affected))
             }
-            StatementType::Update | StatementType::Delete | StatementType::Sql => {
-                let affected = self.session().execute_named(sql_id, sql, args).await?;
-                Ok(R::from_value(Value::U64(affected))?)
+        }
+    }
+
+    /// Renders a mapped statement without executing it.
+    ///
+    /// Returns the SQL and bound parameters exactly as [`Mapper::execute`] would send them,
+    /// which is handy for diagnostics and tests.
+    pub fn render<T>(&self, sql_id: &str, args: &T) -> Result<(String, Vec<(String, Value)>)>
+    where
+        T: ToValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+        engine::render_only(sql_id, sql, args, self.pool.as_ref())
+    }
+
+    /// Executes a mapped `Insert` statement and returns the auto-generated key.
+    ///
+    /// The key is read with `last_insert_id` on the same connection as the insert, whether
+    /// or not the statement declares `useGeneratedKeys`.
+    pub async fn insert_and_get_id<T>(&self, sql_id: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        if stmt.r#type != StatementType::Insert {
+            return Err(DbError::TemplateEngineError(
+                "insert_and_get_id requires an Insert statement type".to_string(),
+            ));
+        }
+        let sql = stmt.sql();
+
+        self.insert_returning_id(sql_id, sql, args).await
+    }
+
+    /// Runs an insert and reads `last_insert_id` on the same connection: the active
+    /// transaction's, or one acquired for the call.
+    async fn insert_returning_id<T: ToValue>(
+        &self,
+        sql_id: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<u64> {
+        let (_, id) = self
+            .session()
+            .execute_named_with_insert_id(sql_id, sql, args)
+            .await?;
+        Ok(id)
+    }
+
+    /// Executes a mapped data-modifying statement and requires it to affect at least
+    /// `min_affected` rows.
+    ///
+    /// Useful for optimistic locking (`UPDATE ... WHERE id = #{id} AND version = #{version}`),
+    /// where zero affected rows means the row is missing or the version is stale.
+    ///
+    /// # Errors
+    /// `DbError::NotFound` if fewer than `min_affected` rows were affected.
+    pub async fn update_and_require<T>(
+        &self,
+        sql_id: &str,
+        args: &T,
+        min_affected: u64,
+    ) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        let affected: u64 = self.execute(sql_id, args).await?;
+        if affected < min_affected {
+            return Err(DbError::NotFound(format!(
+                "Update affected {} rows",
+                affected
+            )));
+        }
+        Ok(affected)
+    }
+
+    /// Executes a mapped `Update` with an optimistic-lock check on `version_field`.
+    ///
+    /// The statement must compare the current version itself, e.g.
+    /// `UPDATE ... SET version = version + 1 WHERE id = #{id} AND version = #{version}`; it runs
+    /// unchanged and a result of zero affected rows is reported as a conflict.
+    ///
+    /// # Errors
+    /// - `DbError::TemplateEngineError` if the statement is not an `Update` or does not bind
+    ///   `#{<version_field>}`.
+    /// - `DbError::MissingField` if `args` has no `version_field`.
+    /// - `DbError::OptimisticLockConflict` if no rows were affected.
+    pub async fn update_with_version<T>(
+        &self,
+        sql_id: &str,
+        args: &T,
+        version_field: &str,
+    ) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        if stmt.r#type != StatementType::Update {
+            return Err(DbError::TemplateEngineError(
+                "optimistic locking requires an Update statement type".to_string(),
+            ));
+        }
+        let sql = stmt.sql();
+        if !engine::binds_var(sql, version_field) {
+            return Err(DbError::TemplateEngineError(format!(
+                "optimistic locking requires statement '{}' to compare #{{{}}}",
+                sql_id, version_field
+            )));
+        }
+
+        let value = args.as_value();
+        let Value::Map(fields) = value.as_ref() else {
+            return Err(DbError::MissingField(version_field.to_string()));
+        };
+        let Some(version) = fields.get(version_field) else {
+            return Err(DbError::MissingField(version_field.to_string()));
+        };
+
+        let affected = self.session().execute_named(sql_id, sql, args).await?;
+        if affected == 0 {
+            let entity = match fields.get("id") {
+                Some(id) => format!("{} id={}", sql_id, scalar_label(id)),
+                None => sql_id.to_string(),
+            };
+            return Err(DbError::OptimisticLockConflict(format!(
+                "{} {}={}",
+                entity,
+                version_field,
+                scalar_label(version)
+            )));
+        }
+        Ok(affected)
+    }
+
+    /// Executes a mapped data-modifying statement that must affect exactly one row.
+    ///
+    /// Outside a transaction the statement runs in its own transaction, which is rolled back
+    /// if more than one row was affected.
+    ///
+    /// # Errors
+    /// - `DbError::NotFound` if no rows were affected.
+    /// - `DbError::TooManyRows(n)` if `n > 1` rows were affected.
+    pub async fn update_one<T>(&self, sql_id: &str, args: &T) -> Result<()>
+    where
+        T: ToValue,
+    {
+        let session = self.session();
+        if session.is_transaction_active() {
+            return require_one(self.update_and_require(sql_id, args, 1).await?);
+        }
+
+        // The transaction lives only for this call, so it gets its own scope.
+        TaskLocalTransaction::scope(async {
+            session.begin().await?;
+            let result = match self.update_and_require(sql_id, args, 1).await {
+                Ok(affected) => require_one(affected),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => session.commit().await,
+                Err(e) => {
+                    session.rollback().await?;
+                    Err(e)
+                }
             }
+        })
+        .await
+    }
+
+    /// Executes a mapped statement and returns the rows it produces.
+    ///
+    /// Unlike [`Mapper::execute`], the statement is always run as a query regardless of its
+    /// type, so `INSERT ... RETURNING` / `UPDATE ... RETURNING` results can be captured.
+    pub async fn execute_returning<R, T>(&self, sql_id: &str, args: &T) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        let rows = self.session().query_rows_named(sql_id, sql, args).await?;
+        map_ordered_rows(rows)
+    }
+
+    /// Executes a mapped query and returns every row as `R`.
+    ///
+    /// A thin wrapper over [`Mapper::execute`] that fixes the return type to `Vec<R>`, so
+    /// callers don't need to annotate it.
+    pub async fn list<R, T>(&self, sql_id: &str, args: &T) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        self.execute::<Vec<R>, T>(sql_id, args).await
+    }
+
+    /// Executes a mapped query and returns the first row as `R`, or `None` if there are no rows.
+    ///
+    /// Only the first row is read. `R` is mapped like the single-row results of
+    /// [`Mapper::execute`]: a struct, a tuple of the columns in select-list order, or the only
+    /// column as a scalar.
+    pub async fn get_one<R, T>(&self, sql_id: &str, args: &T) -> Result<Option<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        let rows = self
+            .session()
+            .query_one_rows_named(sql_id, sql, args)
+            .await?;
+        map_optional_row(rows)
+    }
+
+    /// Shorthand for [`Mapper::get_one`].
+    pub async fn get<R, T>(&self, sql_id: &str, args: &T) -> Result<Option<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        self.get_one(sql_id, args).await
+    }
+
+    /// Executes a mapped query and collects two of its columns into a `HashMap`.
+    ///
+    /// See [`Session::query_map`]; a repeated key keeps the last row's value.
+    pub async fn query_map<K, V, T>(
+        &self,
+        sql_id: &str,
+        args: &T,
+        key_col: &str,
+        val_col: &str,
+    ) -> Result<HashMap<K, V>>
+    where
+        K: FromValue + Eq + Hash,
+        V: FromValue,
+        T: ToValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        let rows = self.session().query_raw_named(sql_id, sql, args).await?;
+        map_key_value(rows, key_col, val_col)
+    }
+
+    /// Executes a mapped query and collects the values of `column` from every row.
+    ///
+    /// See [`Session::query_column`].
+    pub async fn query_column<T, A>(&self, sql_id: &str, args: &A, column: &str) -> Result<Vec<T>>
+    where
+        T: FromValue,
+        A: ToValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        let rows = self.session().query_raw_named(sql_id, sql, args).await?;
+        map_column(rows, column)
+    }
+
+    /// Executes a mapped query that must return exactly one row and maps it to `R`.
+    ///
+    /// # Errors
+    /// - `DbError::NotFound(not_found_msg)` if no rows are returned.
+    /// - `DbError::TooManyRows(n)` if `n > 1` rows are returned.
+    pub async fn get_required<R, T>(&self, sql_id: &str, args: &T, not_found_msg: &str) -> Result<R>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        let rows = self.session().query_raw_named(sql_id, sql, args).await?;
+        map_one_required(rows, not_found_msg)
+    }
+
+    /// Executes a mapped `SELECT COUNT(...)`-style query and returns the count.
+    ///
+    /// The count is the first column of the first row, in select-list order; an empty result
+    /// set or a `NULL` value counts as `0`. The value must be numeric.
+    pub async fn count<T>(&self, sql_id: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        let stmt = self.statement_sql(sql_id)?;
+        let sql = stmt.sql();
+
+        let Rows { columns, rows } = self.session().query_rows_named(sql_id, sql, args).await?;
+        let Some(mut row) = rows.into_iter().next() else {
+            return Ok(0);
+        };
+        let value = match columns.first() {
+            Some(first) => row.remove(first),
+            // Without the column order only a single-column row has a well-defined first value.
+            None if row.len() == 1 => row.into_values().next(),
+            None => {
+                return Err(DbError::DbError(format!(
+                    "Count query {} returned {} columns in unknown order",
+                    sql_id,
+                    row.len()
+                )));
+            }
+        };
+        match value.unwrap_or(Value::Null) {
+            Value::Null => Ok(0),
+            v => u64::from_value(v),
+        }
+    }
+}
+
+fn require_one(affected: u64) -> Result<()> {
+    match affected {
+        1 => Ok(()),
+        n => Err(DbError::TooManyRows(n as usize)),
+    }
+}
+
+/// Converts the rows returned by a query into `R`.
+///
+/// `R` may be a collection (`Vec<T>`), a single row (`T` / `Option<T>`, or a tuple of its
+/// columns in select-list order), or a scalar taken from a single-column row.
+fn rows_to_result<R: FromValue>(sql_id: &str, rows: Rows) -> Result<R> {
+    let Rows { columns, mut rows } = rows;
+    if rows.is_empty() {
+        let list_value = Value::List(Vec::new());
+        if let Ok(v) = R::from_value(list_value) {
+            return Ok(v);
+        }
+        if let Ok(v) = R::from_value(Value::Null) {
+            return Ok(v);
         }
+        return Err(DbError::NotFound(format!(
+            "No rows returned for {}",
+            sql_id
+        )));
+    }
+
+    // Collections (`Vec<T>`, sets, ...) take an empty list but not `Null`: they get every row
+    // and need no single-row fallback. `Option<T>` and `Value` take both, so they try the
+    // list first and keep a copy of the first row in case it fails.
+    let takes_list = R::from_value(Value::List(Vec::new())).is_ok();
+    if takes_list && R::from_value(Value::Null).is_err() {
+        return R::from_value(Value::List(rows.into_iter().map(Value::Map).collect()));
+    }
+    let mut list_err = None;
+    if takes_list {
+        let first = rows[0].clone();
+        match R::from_value(Value::List(rows.into_iter().map(Value::Map).collect())) {
+            Ok(v) => return Ok(v),
+            Err(e) => list_err = Some(e),
+        }
+        rows = vec![first];
+    }
+
+    // `R` is not a collection: map the first row, or its only column for scalar results.
+    let first = rows.swap_remove(0);
+    if first.len() != 1 {
+        return map_first_row(first, &columns)
+            .0
+            .map_err(|e| list_err.unwrap_or(e));
+    }
+    map_single_row(first, &columns)
+}
+
+/// Formats an id or version value for error messages.
+fn scalar_label(value: &Value) -> String {
+    match value {
+        Value::Str(v) => v.clone(),
+        other => i64::from_value(other.clone())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|_| format!("{:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(columns: &[&str], rows: &[&[Value]]) -> Rows {
+        Rows {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .map(|c| c.to_string())
+                        .zip(row.iter().cloned())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_rows_to_result_shapes() {
+        let two = || {
+            rows(
+                &["id", "name"],
+                &[
+                    &[Value::I64(1), Value::Str("a".to_string())],
+                    &[Value::I64(2), Value::Str("b".to_string())],
+                ],
+            )
+        };
+        let all: Vec<HashMap<String, Value>> = rows_to_result("t", two()).unwrap();
+        assert_eq!(all.len(), 2);
+        let first: (i64, String) = rows_to_result("t", two()).unwrap();
+        assert_eq!(first, (1, "a".to_string()));
+        let first: Option<HashMap<String, Value>> = rows_to_result("t", two()).unwrap();
+        assert_eq!(first.unwrap()["id"], Value::I64(1));
+
+        let count = || rows(&["n"], &[&[Value::I64(3)]]);
+        assert_eq!(rows_to_result::<i64>("t", count()).unwrap(), 3);
+        assert_eq!(
+            rows_to_result::<Option<i64>>("t", count()).unwrap(),
+            Some(3)
+        );
+        assert!(
+            rows_to_result::<Vec<i64>>("t", rows(&["n"], &[]))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            rows_to_result::<i64>("t", rows(&["n"], &[])),
+            Err(DbError::NotFound(_))
+        ));
     }
 }
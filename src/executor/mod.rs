@@ -1,4 +1,6 @@
 pub mod exec;
+pub mod interceptor;
 pub mod mapper;
 pub mod session;
-mod transaction;
+pub mod splitting;
+pub mod transaction;
@@ -1,24 +1,112 @@
 use crate::Result;
+use crate::driver_manager::DriverManager;
 use crate::error::DbError;
-use crate::executor::exec::{execute_conn, map_rows, query_conn};
-use crate::executor::transaction::TransactionContext;
-use crate::udbc::connection::Connection;
+use crate::executor::exec::{
+    execute_conn_logged, map_column, map_key_value, map_one_required, map_optional_row,
+    map_ordered_rows, query_conn_logged, query_one_conn_logged,
+};
+use crate::executor::interceptor::{QueryInterceptor, SessionWithInterceptor};
+use crate::executor::splitting::SplittingSession;
+use crate::executor::transaction::{Transaction, TransactionContext};
+use crate::udbc::connection::{Connection, Rows};
 use crate::udbc::driver::Driver;
 use crate::udbc::value::{FromValue, ToValue, Value};
+use log::Level;
+use serde::Serialize;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio::task;
 
-type TransactionContextMap = HashMap<String, Arc<Mutex<TransactionContext>>>;
+/// Active transactions by driver instance and session identity (see [`Session::tx_key`]).
+type TransactionContextMap = HashMap<(usize, usize), Arc<Mutex<TransactionContext>>>;
 
 thread_local! {
     static TX_CONTEXT: RefCell<TransactionContextMap> = RefCell::new(HashMap::new());
 }
 
-fn inline_template_name(sql: &str) -> String {
+tokio::task_local! {
+    static TASK_TX_CONTEXT: RefCell<TransactionContextMap>;
+}
+
+/// Runs `f` on the transaction map of the current [`TaskLocalTransaction::scope`], else, outside
+/// any tokio task (e.g. in the future passed to `block_on`, which never moves between threads),
+/// on the thread-local map.
+///
+/// Returns `None` in a tokio task outside a scope: such a task can move between worker threads,
+/// and state kept for it elsewhere would outlive the task if it were aborted.
+fn with_tx_map<R>(f: impl FnOnce(&mut TransactionContextMap) -> R) -> Option<R> {
+    let mut f = Some(f);
+    if let Ok(result) = TASK_TX_CONTEXT.try_with(|map| (f.take().unwrap())(&mut map.borrow_mut())) {
+        return Some(result);
+    }
+    if task::try_id().is_some() {
+        return None;
+    }
+    Some(TX_CONTEXT.with(|map| (f.take().unwrap())(&mut map.borrow_mut())))
+}
+
+/// Task-local transaction state for [`Session::begin`] / `commit` / `rollback`.
+///
+/// Inside a tokio task, transactions are tracked by the task's [`TaskLocalTransaction::scope`],
+/// so they survive the task moving to another worker between `.await`s and are dropped (and
+/// rolled back) with the task if it is aborted. Only outside any task (the future given to
+/// `block_on`) are they tracked per thread. A scope also lets other tasks share the
+/// transactions visible where it was created; `#[transaction]` functions always run in a scope.
+pub struct TaskLocalTransaction;
+
+impl TaskLocalTransaction {
+    /// Runs `fut` with task-local transaction state.
+    ///
+    /// The scope starts with the transactions visible to the caller, so it joins an active
+    /// transaction instead of hiding it. Wrapping the future passed to `tokio::spawn` or
+    /// `tokio::task::spawn_local` lets the spawned task run statements on the caller's
+    /// transaction:
+    ///
+    /// ```ignore
+    /// session.begin().await?;
+    /// tokio::task::spawn_local(TaskLocalTransaction::scope(async move {
+    ///     session.execute("UPDATE ...", &args).await
+    /// }))
+    /// .await??;
+    /// session.commit().await?;
+    /// ```
+    ///
+    /// A transaction must be committed or rolled back by the task that began it, after the
+    /// tasks sharing it have finished.
+    pub fn scope<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+        // Captured now rather than on first poll, which may happen in the spawned task.
+        let inherited = with_tx_map(|map| map.clone()).unwrap_or_default();
+        TASK_TX_CONTEXT.scope(RefCell::new(inherited), fut)
+    }
+}
+
+/// A connection borrowed by a [`Session`] call: the active transaction's, held locked for
+/// the call, or one acquired from the pool for it.
+enum SessionConnection {
+    Transaction(OwnedMutexGuard<TransactionContext>),
+    Pooled(Box<dyn Connection>),
+}
+
+impl SessionConnection {
+    fn get(&mut self) -> Result<&mut dyn Connection> {
+        match self {
+            SessionConnection::Transaction(ctx) => match ctx.connection_mut() {
+                Some(conn) => Ok(conn.as_mut()),
+                None => Err(DbError::DbError(
+                    "Transaction connection closed".to_string(),
+                )),
+            },
+            SessionConnection::Pooled(conn) => Ok(conn.as_mut()),
+        }
+    }
+}
+
+pub(crate) fn inline_template_name(sql: &str) -> String {
     let mut hasher = DefaultHasher::new();
     sql.hash(&mut hasher);
     format!("__inline__:{:x}", hasher.finish())
@@ -27,8 +115,109 @@ fn inline_template_name(sql: &str) -> String {
 /// Database session wrapper managing connection pools and transaction state.
 ///
 /// Provides a unified interface for executing queries whether inside a transaction or not.
+///
+/// Sessions created with [`Session::new`] (including those handed out by
+/// [`DriverManager`](crate::driver_manager::DriverManager) and [`SessionFactory`]) share the
+/// transaction begun for their driver in the current task, which is how [`Mapper`] calls join
+/// it. A clone gets its own identity instead: cloning copies the pool handle and settings but
+/// not transaction state, so a clone neither sees nor joins the original's transaction.
+///
+/// [`Mapper`]: crate::executor::mapper::Mapper
 pub struct Session {
     pool: Arc<dyn Driver>,
+    query_timeout: Option<Duration>,
+    log_level: Level,
+    allow_raw: bool,
+    /// Set for clones, so their transactions are kept apart from the driver's shared one.
+    identity: Option<Arc<()>>,
+}
+
+impl Clone for Session {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            query_timeout: self.query_timeout,
+            log_level: self.log_level,
+            allow_raw: self.allow_raw,
+            identity: Some(Arc::new(())),
+        }
+    }
+}
+
+/// Settings shared by every [`Session`] created from a [`SessionFactory`].
+#[derive(Clone)]
+pub struct SessionConfig {
+    /// Upper bound for a single statement, including waiting for a connection.
+    /// Exceeding it fails the call with [`DbError::Timeout`] and, inside a transaction, aborts
+    /// the transaction. `None` means no limit.
+    pub query_timeout_ms: Option<u64>,
+    /// Level at which executed statements are logged. Defaults to `Debug`.
+    pub log_level: Level,
+    /// Interceptor applied by [`SessionFactory::create_intercepted`].
+    pub interceptor: Option<Arc<dyn QueryInterceptor>>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            query_timeout_ms: None,
+            log_level: Level::Debug,
+            interceptor: None,
+        }
+    }
+}
+
+/// Creates [`Session`]s for one driver with a fixed [`SessionConfig`].
+///
+/// Obtained from [`DriverManager::session_factory`](crate::driver_manager::DriverManager::session_factory);
+/// cheap to clone and to keep around, e.g. in application state.
+#[derive(Clone)]
+pub struct SessionFactory {
+    pool: Arc<dyn Driver>,
+    config: SessionConfig,
+}
+
+impl SessionFactory {
+    pub fn new(pool: Arc<dyn Driver>, config: SessionConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Creates a session with the factory's timeout and log level.
+    ///
+    /// The configured interceptor is not applied; use [`SessionFactory::create_intercepted`].
+    pub fn create(&self) -> Session {
+        Session {
+            pool: self.pool.clone(),
+            query_timeout: self.config.query_timeout_ms.map(Duration::from_millis),
+            log_level: self.config.log_level,
+            allow_raw: false,
+            identity: None,
+        }
+    }
+
+    /// Creates a session wrapped with the configured interceptor, if any.
+    pub fn create_intercepted(&self) -> SessionWithInterceptor {
+        let interceptors = self.config.interceptor.iter().cloned().collect();
+        SessionWithInterceptor::new(self.create(), interceptors)
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+}
+
+/// Outcome of [`Session::insert`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct InsertResult {
+    pub affected_rows: u64,
+    pub last_insert_id: u64,
+}
+
+impl InsertResult {
+    /// The generated key, i.e. [`InsertResult::last_insert_id`].
+    pub fn id(&self) -> u64 {
+        self.last_insert_id
+    }
 }
 
 pub trait TransactionResult: Sized {
@@ -51,41 +240,149 @@ where
 
 impl Session {
     pub fn new(pool: Arc<dyn Driver>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            query_timeout: None,
+            log_level: Level::Debug,
+            allow_raw: false,
+            identity: None,
+        }
+    }
+
+    /// Allows `${...}` raw interpolation in the SQL given to this session (e.g. to
+    /// [`Session::execute`]) and to the transactions it begins with
+    /// [`Session::begin_transaction`].
+    ///
+    /// Off by default, so such SQL fails to render: raw values are written into the SQL text,
+    /// so only enable it for templates whose raw values never come from user input. Mapper
+    /// statements are governed by their own `allowRaw` attribute instead.
+    pub fn allow_raw(mut self, allow: bool) -> Self {
+        self.allow_raw = allow;
+        self
+    }
+
+    /// Runs `fut` under the session's query timeout, if one is configured.
+    ///
+    /// A statement cut short on a transaction's connection leaves that connection in an
+    /// unknown state, so the transaction is aborted: it is rolled back, later statements and
+    /// [`Session::commit`] fail, and [`Session::rollback`] clears it.
+    async fn timed<R>(&self, fut: impl Future<Output = Result<R>>) -> Result<R> {
+        let Some(limit) = self.query_timeout else {
+            return fut.await;
+        };
+        match tokio::time::timeout(limit, fut).await {
+            Ok(result) => result,
+            Err(_) => {
+                if let Some(tx) = self.active_tx() {
+                    tx.lock().await.abort();
+                }
+                Err(DbError::Timeout(format!(
+                    "query on '{}' exceeded {}ms",
+                    self.pool.name(),
+                    limit.as_millis()
+                )))
+            }
+        }
+    }
+
+    /// The active transaction for this session's driver, if any.
+    fn active_tx(&self) -> Option<Arc<Mutex<TransactionContext>>> {
+        with_tx_map(|map| map.get(&self.tx_key()).cloned()).flatten()
+    }
+
+    /// Transactions are keyed by driver instance rather than name, so drivers that share a
+    /// name in different [`DriverManager`](crate::driver_manager::DriverManager)s never share
+    /// a transaction, and by the session's identity, so clones don't share one either.
+    fn tx_key(&self) -> (usize, usize) {
+        let driver = Arc::as_ptr(&self.pool) as *const () as usize;
+        let identity = self
+            .identity
+            .as_ref()
+            .map_or(0, |id| Arc::as_ptr(id) as usize);
+        (driver, identity)
+    }
+
+    /// The active transaction's connection, or one acquired from the pool when there is no
+    /// transaction.
+    async fn connection(&self) -> Result<SessionConnection> {
+        match self.active_tx() {
+            Some(tx) => Ok(SessionConnection::Transaction(tx.lock_owned().await)),
+            None => Ok(SessionConnection::Pooled(self.pool.acquire().await?)),
+        }
+    }
+
+    /// Wraps this session so that `interceptor` runs around every query and execute call.
+    ///
+    /// Further interceptors can be chained with [`SessionWithInterceptor::with_interceptor`].
+    pub fn with_interceptor(
+        self,
+        interceptor: Arc<dyn QueryInterceptor>,
+    ) -> SessionWithInterceptor {
+        SessionWithInterceptor::new(self, vec![interceptor])
+    }
+
+    /// Creates a [`SplittingSession`] over two drivers registered in `manager` (e.g. the
+    /// global `U`); shorthand for [`DriverManager::splitting_session`].
+    ///
+    /// Writes and transactions go to `primary_db`; reads go to `replica_db`.
+    ///
+    /// # Returns
+    /// `Some(SplittingSession)` if both drivers are registered, otherwise `None`.
+    pub fn with_splitting(
+        manager: &DriverManager,
+        primary_db: &str,
+        replica_db: &str,
+    ) -> Option<SplittingSession> {
+        manager.splitting_session(primary_db, replica_db)
     }
 
     /// Begins a new transaction for the current database connection.
     ///
-    /// The transaction state is stored in a thread-local map (`TX_CONTEXT`) using the driver's name as the key.
-    /// This ensures that nested or subsequent calls within the same thread can access the active transaction.
+    /// The transaction state is keyed by the driver instance and stored per task inside
+    /// [`TaskLocalTransaction::scope`], otherwise, outside any tokio task, in a thread-local
+    /// map (`TX_CONTEXT`).
+    /// This ensures that nested or subsequent calls can access the active transaction.
     ///
     /// # Errors
-    /// Returns `Error` if a transaction has already been started for this driver in the current thread.
+    /// Returns `Error` if a transaction has already been started for this driver in the current
+    /// task, or if called in a tokio task outside [`TaskLocalTransaction::scope`].
     pub async fn begin(&self) -> Result<()> {
-        let key = self.pool.name().to_string();
-        let existed = TX_CONTEXT.with(|tx| tx.borrow().contains_key(&key));
+        let key = self.tx_key();
+        let Some(existed) = with_tx_map(|tx| tx.contains_key(&key)) else {
+            return Err(DbError::DbError(format!(
+                "Transaction for '{}' begun in a tokio task outside TaskLocalTransaction::scope",
+                self.pool.name()
+            )));
+        };
         if existed {
             return Err(DbError::DbError(format!(
                 "Transaction already started for '{}'",
-                key
+                self.pool.name()
             )));
         }
 
         let ctx = TransactionContext::begin(self.pool.clone()).await?;
-        TX_CONTEXT.with(|tx| {
-            tx.borrow_mut().insert(key, Arc::new(Mutex::new(ctx)));
+        with_tx_map(|tx| {
+            tx.insert(key, Arc::new(Mutex::new(ctx)));
         });
         Ok(())
     }
 
+    /// Begins a transaction that owns its connection instead of using task-local state.
+    ///
+    /// All statements must be run through the returned [`Transaction`]; they are not visible to
+    /// `Session::execute`/`Session::query` on this session.
+    pub async fn begin_transaction(&self) -> Result<Transaction> {
+        Transaction::begin(self.pool.clone(), self.allow_raw).await
+    }
+
     /// Commits the active transaction for the current database connection.
     ///
     /// If no transaction is active, this method does nothing and returns `Ok(())`.
-    /// Upon completion, the transaction context is removed from the thread-local storage.
+    /// Upon completion, the transaction context is removed from the task-local storage.
     pub async fn commit(&self) -> Result<()> {
-        let key = self.pool.name().to_string();
-        let tx = TX_CONTEXT.with(|map| map.borrow().get(&key).cloned());
-        let Some(tx) = tx else {
+        let key = self.tx_key();
+        let Some(tx) = self.active_tx() else {
             return Ok(());
         };
 
@@ -94,9 +391,9 @@ impl Session {
             ctx.commit().await?;
         }
 
-        // Clean up the transaction context from thread-local storage.
-        TX_CONTEXT.with(|map| {
-            map.borrow_mut().remove(&key);
+        // Clean up the transaction context from task-local storage.
+        with_tx_map(|map| {
+            map.remove(&key);
         });
         Ok(())
     }
@@ -104,11 +401,10 @@ impl Session {
     /// Rolls back the active transaction for the current database connection.
     ///
     /// If no transaction is active, this method does nothing and returns `Ok(())`.
-    /// Upon completion, the transaction context is removed from the thread-local storage.
+    /// Upon completion, the transaction context is removed from the task-local storage.
     pub async fn rollback(&self) -> Result<()> {
-        let key = self.pool.name().to_string();
-        let tx = TX_CONTEXT.with(|map| map.borrow().get(&key).cloned());
-        let Some(tx) = tx else {
+        let key = self.tx_key();
+        let Some(tx) = self.active_tx() else {
             return Ok(());
         };
 
@@ -117,16 +413,15 @@ impl Session {
             ctx.rollback().await?;
         }
 
-        // Clean up the transaction context from thread-local storage.
-        TX_CONTEXT.with(|map| {
-            map.borrow_mut().remove(&key);
+        // Clean up the transaction context from task-local storage.
+        with_tx_map(|map| {
+            map.remove(&key);
         });
         Ok(())
     }
 
     pub fn is_transaction_active(&self) -> bool {
-        let key = self.pool.name().to_string();
-        TX_CONTEXT.with(|tx| tx.borrow().contains_key(&key))
+        self.active_tx().is_some()
     }
 
     /// Executes a SQL statement (e.g., INSERT, UPDATE, DELETE) that modifies data.
@@ -149,27 +444,107 @@ impl Session {
         self.execute_named(&template_name, sql, args).await
     }
 
+    /// [`Session::execute`] counterpart of [`Session::query_raw_value`].
+    pub async fn execute_value(&self, sql: &str, args: Value) -> Result<u64> {
+        self.execute(sql, &args).await
+    }
+
     pub async fn execute_named<T>(&self, template_name: &str, sql: &str, args: &T) -> Result<u64>
     where
         T: ToValue,
     {
-        let key = self.pool.name();
-        // Check if there's an active transaction for this driver.
-        if let Some(tx) = TX_CONTEXT.with(|map| map.borrow().get(key).cloned()) {
-            let mut ctx = tx.lock().await;
-            if let Some(conn) = ctx.connection_mut() {
-                return execute_conn(conn.as_mut(), self.pool.as_ref(), template_name, sql, args)
-                    .await;
-            } else {
-                return Err(DbError::DbError(
-                    "Transaction connection closed".to_string(),
-                ));
-            }
-        }
+        self.timed(async {
+            let mut conn = self.connection().await?;
+            self.execute_on(conn.get()?, template_name, sql, args).await
+        })
+        .await
+    }
+
+    async fn execute_on<T: ToValue>(
+        &self,
+        conn: &mut dyn Connection,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<u64> {
+        execute_conn_logged(
+            conn,
+            self.pool.as_ref(),
+            template_name,
+            sql,
+            args,
+            self.log_level,
+            self.allow_raw,
+        )
+        .await
+    }
+
+    async fn query_on<T: ToValue>(
+        &self,
+        conn: &mut dyn Connection,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<Rows> {
+        query_conn_logged(
+            conn,
+            self.pool.as_ref(),
+            template_name,
+            sql,
+            args,
+            self.log_level,
+            self.allow_raw,
+        )
+        .await
+    }
+
+    async fn query_one_on<T: ToValue>(
+        &self,
+        conn: &mut dyn Connection,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<Rows> {
+        query_one_conn_logged(
+            conn,
+            self.pool.as_ref(),
+            template_name,
+            sql,
+            args,
+            self.log_level,
+            self.allow_raw,
+        )
+        .await
+    }
+
+    /// Executes an INSERT and returns both the affected rows and the generated key.
+    ///
+    /// Both values come from the same connection; see [`Session::execute_with_insert_id`].
+    pub async fn insert<T>(&self, sql: &str, args: &T) -> Result<InsertResult>
+    where
+        T: ToValue,
+    {
+        let (affected_rows, last_insert_id) = self.execute_with_insert_id(sql, args).await?;
+        Ok(InsertResult {
+            affected_rows,
+            last_insert_id,
+        })
+    }
 
-        // No active transaction, render template and execute on a new connection.
-        let mut conn: Box<dyn Connection> = self.pool.acquire().await?;
-        execute_conn(conn.as_mut(), self.pool.as_ref(), template_name, sql, args).await
+    /// Executes an UPDATE and returns the number of affected rows.
+    pub async fn update<T>(&self, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        self.execute(sql, args).await
+    }
+
+    /// Executes a DELETE and returns the number of affected rows.
+    pub async fn delete<T>(&self, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        self.execute(sql, args).await
     }
 
     /// Executes a SQL query and maps the resulting rows to a collection of type `R`.
@@ -181,12 +556,83 @@ impl Session {
     /// # Returns
     /// A `Vec<R>` containing the deserialized results.
     pub async fn query<R, T>(&self, sql: &str, args: &T) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let template_name = inline_template_name(sql);
+        let rows = self.query_rows_named(&template_name, sql, args).await?;
+        map_ordered_rows(rows)
+    }
+
+    /// Executes a SQL query and maps the first row to `R`, or `None` if there are no rows.
+    ///
+    /// Only the first row is read from the database; see [`Connection::query_one`]. As with
+    /// [`Session::query`], `R` may be a tuple of the columns in select-list order, and a
+    /// single-column row can be read as a scalar such as `i64`.
+    pub async fn get_one<R, T>(&self, sql: &str, args: &T) -> Result<Option<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let template_name = inline_template_name(sql);
+        let rows = self.query_one_rows_named(&template_name, sql, args).await?;
+        map_optional_row(rows)
+    }
+
+    /// Executes a SQL query that must return exactly one row and maps it to `R`.
+    ///
+    /// # Errors
+    /// - `DbError::NotFound(not_found_msg)` if no rows are returned.
+    /// - `DbError::TooManyRows(n)` if `n > 1` rows are returned.
+    pub async fn query_one_required<R, T>(
+        &self,
+        sql: &str,
+        args: &T,
+        not_found_msg: &str,
+    ) -> Result<R>
     where
         T: ToValue,
         R: FromValue,
     {
         let rows = self.query_raw(sql, args).await?;
-        map_rows(rows)
+        map_one_required(rows, not_found_msg)
+    }
+
+    /// Executes a SQL query and collects two of its columns into a `HashMap`.
+    ///
+    /// Each row contributes `key_col -> val_col`; if a key appears more than once, the last
+    /// row wins.
+    ///
+    /// # Errors
+    /// `DbError::MissingField` if either column is absent from a row.
+    pub async fn query_map<K, V, T>(
+        &self,
+        sql: &str,
+        args: &T,
+        key_col: &str,
+        val_col: &str,
+    ) -> Result<HashMap<K, V>>
+    where
+        K: FromValue + Eq + Hash,
+        V: FromValue,
+        T: ToValue,
+    {
+        let rows = self.query_raw(sql, args).await?;
+        map_key_value(rows, key_col, val_col)
+    }
+
+    /// Executes a SQL query and collects the values of `column` from every row.
+    ///
+    /// # Errors
+    /// `DbError::MissingField` if the column is absent from a row.
+    pub async fn query_column<T, A>(&self, sql: &str, args: &A, column: &str) -> Result<Vec<T>>
+    where
+        T: FromValue,
+        A: ToValue,
+    {
+        let rows = self.query_raw(sql, args).await?;
+        map_column(rows, column)
     }
 
     /// Executes a SQL query and returns the results as a list of raw HashMaps.
@@ -200,6 +646,17 @@ impl Session {
         self.query_raw_named(&template_name, sql, args).await
     }
 
+    /// [`Session::query_raw`] with parameters that were assembled at runtime, e.g. a
+    /// `Value::Map` built by hand or taken from a previous query. The value is rendered as-is,
+    /// without being copied through [`ToValue::to_value`].
+    pub async fn query_raw_value(
+        &self,
+        sql: &str,
+        args: Value,
+    ) -> Result<Vec<HashMap<String, Value>>> {
+        self.query_raw(sql, &args).await
+    }
+
     pub async fn query_raw_named<T>(
         &self,
         template_name: &str,
@@ -209,38 +666,118 @@ impl Session {
     where
         T: ToValue,
     {
-        let key = self.pool.name();
-        if let Some(tx) = TX_CONTEXT.with(|map| map.borrow().get(key).cloned()) {
-            let mut ctx = tx.lock().await;
-            if let Some(conn) = ctx.connection_mut() {
-                return query_conn(conn.as_mut(), self.pool.as_ref(), template_name, sql, args)
-                    .await;
-            } else {
-                return Err(DbError::DbError(
-                    "Transaction connection closed".to_string(),
-                ));
-            }
-        }
+        Ok(self.query_rows_named(template_name, sql, args).await?.rows)
+    }
+
+    /// [`Session::query_raw_named`] that also returns the column order.
+    pub(crate) async fn query_rows_named<T>(
+        &self,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<Rows>
+    where
+        T: ToValue,
+    {
+        self.timed(async {
+            let mut conn = self.connection().await?;
+            self.query_on(conn.get()?, template_name, sql, args).await
+        })
+        .await
+    }
+
+    /// [`Session::query_rows_named`] that reads only the first row.
+    pub(crate) async fn query_one_rows_named<T>(
+        &self,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<Rows>
+    where
+        T: ToValue,
+    {
+        self.timed(async {
+            let mut conn = self.connection().await?;
+            self.query_one_on(conn.get()?, template_name, sql, args)
+                .await
+        })
+        .await
+    }
+
+    /// Runs already-rendered SQL with positional parameters, bypassing the template engine.
+    ///
+    /// Uses the active transaction's connection if there is one.
+    pub(crate) async fn execute_prepared(
+        &self,
+        sql: &str,
+        params: &[(String, Value)],
+    ) -> Result<u64> {
+        self.timed(async {
+            let mut conn = self.connection().await?;
+            conn.get()?.execute(sql, params).await
+        })
+        .await
+    }
+
+    /// Query counterpart of [`Session::execute_prepared`].
+    pub(crate) async fn query_prepared(
+        &self,
+        sql: &str,
+        params: &[(String, Value)],
+    ) -> Result<Rows> {
+        self.timed(async {
+            let mut conn = self.connection().await?;
+            conn.get()?.query_with_columns(sql, params).await
+        })
+        .await
+    }
 
-        let mut conn: Box<dyn Connection> = self.pool.acquire().await?;
-        query_conn(conn.as_mut(), self.pool.as_ref(), template_name, sql, args).await
+    /// Executes an INSERT and returns `(affected_rows, last_insert_id)`.
+    ///
+    /// Both values are read from the same connection: the active transaction's connection if
+    /// there is one, otherwise a single connection acquired for this call.
+    pub async fn execute_with_insert_id<T>(&self, sql: &str, args: &T) -> Result<(u64, u64)>
+    where
+        T: ToValue,
+    {
+        let template_name = inline_template_name(sql);
+        self.execute_named_with_insert_id(&template_name, sql, args)
+            .await
+    }
+
+    pub async fn execute_named_with_insert_id<T>(
+        &self,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<(u64, u64)>
+    where
+        T: ToValue,
+    {
+        self.timed(async {
+            let mut conn = self.connection().await?;
+            let conn = conn.get()?;
+            let affected = self.execute_on(conn, template_name, sql, args).await?;
+            Ok((affected, conn.last_insert_id().await?))
+        })
+        .await
     }
 
     /// Retrieves the ID of the last inserted row.
+    ///
+    /// The value is tracked per connection. Inside a transaction started with
+    /// [`Session::begin`] it is read from the transaction's connection, which also ran the
+    /// insert. Outside a transaction it is read from a freshly acquired pooled connection,
+    /// which need not be the one the insert used, so the result is unreliable there; use
+    /// [`Session::execute_with_insert_id`] or [`Session::insert`] instead.
     pub async fn last_insert_id(&self) -> Result<u64> {
-        let key = self.pool.name().to_string();
-        if let Some(tx) = TX_CONTEXT.with(|map| map.borrow().get(&key).cloned()) {
-            let mut ctx = tx.lock().await;
-            if let Some(conn) = ctx.connection_mut() {
-                return conn.last_insert_id().await;
-            } else {
-                return Err(DbError::DbError(
-                    "Transaction connection closed".to_string(),
-                ));
-            }
+        if !self.is_transaction_active() {
+            log::warn!(
+                "last_insert_id on '{}' outside a transaction may not return the id of your insert; use execute_with_insert_id",
+                self.pool.name()
+            );
         }
-
-        let mut conn: Box<dyn Connection> = self.pool.acquire().await?;
-        conn.last_insert_id().await
+        let mut conn = self.connection().await?;
+        conn.get()?.last_insert_id().await
     }
 }
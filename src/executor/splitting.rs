@@ -0,0 +1,114 @@
+use crate::Result;
+use crate::executor::session::Session;
+use crate::udbc::value::{FromValue, ToValue, Value};
+use std::collections::HashMap;
+
+/// Session that routes writes to a primary database and reads to a replica.
+///
+/// Transactions always run on the primary; while one is active, reads are routed to the
+/// primary as well so they observe the transaction's own writes.
+pub struct SplittingSession {
+    primary: Session,
+    replica: Session,
+}
+
+impl SplittingSession {
+    pub fn new(primary: Session, replica: Session) -> Self {
+        Self { primary, replica }
+    }
+
+    /// The session used for writes and transactions.
+    pub fn primary(&self) -> &Session {
+        &self.primary
+    }
+
+    /// The session used for reads outside of transactions.
+    pub fn replica(&self) -> &Session {
+        &self.replica
+    }
+
+    fn read_session(&self) -> &Session {
+        if self.primary.is_transaction_active() {
+            &self.primary
+        } else {
+            &self.replica
+        }
+    }
+
+    /// Begins a transaction on the primary.
+    pub async fn begin(&self) -> Result<()> {
+        self.primary.begin().await
+    }
+
+    /// Commits the active transaction on the primary.
+    pub async fn commit(&self) -> Result<()> {
+        self.primary.commit().await
+    }
+
+    /// Rolls back the active transaction on the primary.
+    pub async fn rollback(&self) -> Result<()> {
+        self.primary.rollback().await
+    }
+
+    pub fn is_transaction_active(&self) -> bool {
+        self.primary.is_transaction_active()
+    }
+
+    /// Executes a data-modifying statement on the primary.
+    pub async fn execute<T>(&self, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        self.primary.execute(sql, args).await
+    }
+
+    pub async fn execute_named<T>(&self, template_name: &str, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        self.primary.execute_named(template_name, sql, args).await
+    }
+
+    /// Executes a query on the replica (or the primary inside a transaction).
+    pub async fn query<R, T>(&self, sql: &str, args: &T) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        self.read_session().query(sql, args).await
+    }
+
+    pub async fn query_raw<T>(&self, sql: &str, args: &T) -> Result<Vec<HashMap<String, Value>>>
+    where
+        T: ToValue,
+    {
+        self.read_session().query_raw(sql, args).await
+    }
+
+    pub async fn query_raw_named<T>(
+        &self,
+        template_name: &str,
+        sql: &str,
+        args: &T,
+    ) -> Result<Vec<HashMap<String, Value>>>
+    where
+        T: ToValue,
+    {
+        self.read_session()
+            .query_raw_named(template_name, sql, args)
+            .await
+    }
+
+    /// Executes an INSERT on the primary and returns `(affected_rows, last_insert_id)`.
+    pub async fn execute_with_insert_id<T>(&self, sql: &str, args: &T) -> Result<(u64, u64)>
+    where
+        T: ToValue,
+    {
+        self.primary.execute_with_insert_id(sql, args).await
+    }
+
+    /// Retrieves the ID of the last inserted row from the primary.
+    pub async fn last_insert_id(&self) -> Result<u64> {
+        self.primary.last_insert_id().await
+    }
+}
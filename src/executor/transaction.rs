@@ -1,6 +1,15 @@
 use crate::Result;
-use crate::udbc::connection::Connection;
+use crate::error::DbError;
+use crate::executor::exec::{
+    execute_conn_logged, map_optional_row, map_ordered_rows, query_conn_logged,
+    query_one_conn_logged,
+};
+use crate::executor::session::inline_template_name;
+use crate::udbc::connection::{Connection, Rows};
 use crate::udbc::driver::Driver;
+use crate::udbc::value::{FromValue, ToValue, Value};
+use log::Level;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub(crate) struct TransactionContext {
@@ -19,9 +28,12 @@ impl TransactionContext {
     }
 
     pub async fn commit(&mut self) -> Result<()> {
-        if let Some(conn) = self.conn.as_mut() {
-            conn.commit().await?;
-        }
+        let Some(conn) = self.conn.as_mut() else {
+            return Err(DbError::DbError(
+                "Transaction connection closed".to_string(),
+            ));
+        };
+        conn.commit().await?;
         self.committed = true;
         Ok(())
     }
@@ -37,6 +49,17 @@ impl TransactionContext {
         }
         r
     }
+
+    /// Gives up the connection after a statement on it was cancelled, rolling it back in the
+    /// background. Later statements and `commit` fail; `rollback` still succeeds.
+    pub fn abort(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            tokio::spawn(async move {
+                let _ = conn.rollback().await;
+            });
+        }
+    }
+
     pub fn connection_mut(&mut self) -> Option<&mut Box<dyn Connection>> {
         self.conn.as_mut()
     }
@@ -53,3 +76,204 @@ impl Drop for TransactionContext {
         }
     }
 }
+
+/// An explicitly owned database transaction.
+///
+/// Unlike [`Session::begin`](crate::executor::session::Session::begin), which keeps the
+/// transaction in task-local state, a `Transaction` owns its connection, so it can be moved
+/// across tasks and awaits freely. Finish it with [`Transaction::commit`] or
+/// [`Transaction::rollback`]; if it is dropped without either, a rollback is spawned.
+pub struct Transaction {
+    pool: Arc<dyn Driver>,
+    conn: Option<Box<dyn Connection>>,
+    /// Inherited from [`Session::allow_raw`](crate::executor::session::Session::allow_raw).
+    allow_raw: bool,
+}
+
+impl Transaction {
+    pub(crate) async fn begin(pool: Arc<dyn Driver>, allow_raw: bool) -> Result<Self> {
+        let mut conn: Box<dyn Connection> = pool.acquire().await?;
+        conn.begin().await?;
+        Ok(Self {
+            pool,
+            conn: Some(conn),
+            allow_raw,
+        })
+    }
+
+    fn conn(&mut self) -> Result<&mut Box<dyn Connection>> {
+        self.conn
+            .as_mut()
+            .ok_or_else(|| DbError::DbError("Transaction connection closed".to_string()))
+    }
+
+    /// Executes a SQL template inside the transaction and returns the number of affected rows.
+    pub async fn execute<T>(&mut self, sql: &str, args: &T) -> Result<u64>
+    where
+        T: ToValue,
+    {
+        let template_name = inline_template_name(sql);
+        let (pool, allow_raw) = (self.pool.clone(), self.allow_raw);
+        let conn = self.conn()?;
+        execute_conn_logged(
+            conn.as_mut(),
+            pool.as_ref(),
+            &template_name,
+            sql,
+            args,
+            Level::Debug,
+            allow_raw,
+        )
+        .await
+    }
+
+    /// Executes a SQL query inside the transaction and returns the raw rows.
+    pub async fn query_raw<T>(&mut self, sql: &str, args: &T) -> Result<Vec<HashMap<String, Value>>>
+    where
+        T: ToValue,
+    {
+        self.query_rows(sql, args).await.map(|rows| rows.rows)
+    }
+
+    /// [`Transaction::query_raw`] that also returns the column order.
+    async fn query_rows<T>(&mut self, sql: &str, args: &T) -> Result<Rows>
+    where
+        T: ToValue,
+    {
+        let template_name = inline_template_name(sql);
+        let (pool, allow_raw) = (self.pool.clone(), self.allow_raw);
+        let conn = self.conn()?;
+        query_conn_logged(
+            conn.as_mut(),
+            pool.as_ref(),
+            &template_name,
+            sql,
+            args,
+            Level::Debug,
+            allow_raw,
+        )
+        .await
+    }
+
+    /// Executes a SQL query inside the transaction and maps every row to `R`.
+    pub async fn query<R, T>(&mut self, sql: &str, args: &T) -> Result<Vec<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let rows = self.query_rows(sql, args).await?;
+        map_ordered_rows(rows)
+    }
+
+    /// Executes a SQL query and maps the first row to `R`, or `None` if there are no rows.
+    ///
+    /// See [`Session::get_one`](crate::executor::session::Session::get_one) for the accepted
+    /// shapes of `R`.
+    pub async fn get_one<R, T>(&mut self, sql: &str, args: &T) -> Result<Option<R>>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let template_name = inline_template_name(sql);
+        let (pool, allow_raw) = (self.pool.clone(), self.allow_raw);
+        let conn = self.conn()?;
+        let rows = query_one_conn_logged(
+            conn.as_mut(),
+            pool.as_ref(),
+            &template_name,
+            sql,
+            args,
+            Level::Debug,
+            allow_raw,
+        )
+        .await?;
+        map_optional_row(rows)
+    }
+
+    /// Executes a query returning a single column and converts the first value to `R`.
+    ///
+    /// If no rows are returned, `R` is built from `Value::Null`, so `Option<R>` yields `None`.
+    pub async fn query_scalar<R, T>(&mut self, sql: &str, args: &T) -> Result<R>
+    where
+        T: ToValue,
+        R: FromValue,
+    {
+        let rows = self.query_raw(sql, args).await?;
+        let Some(row) = rows.into_iter().next() else {
+            return R::from_value(Value::Null);
+        };
+        if row.len() != 1 {
+            return Err(DbError::DbError(format!(
+                "Scalar query must return exactly one column, got {}",
+                row.len()
+            )));
+        }
+        let (_, value) = row.into_iter().next().unwrap();
+        R::from_value(value)
+    }
+
+    /// Retrieves the ID of the last row inserted on this transaction's connection.
+    pub async fn last_insert_id(&mut self) -> Result<u64> {
+        self.conn()?.last_insert_id().await
+    }
+
+    /// Creates a savepoint named `name` inside the transaction.
+    pub async fn savepoint(&mut self, name: &str) -> Result<()> {
+        let sql = format!("SAVEPOINT {}", savepoint_name(name)?);
+        self.conn()?.execute(&sql, &[]).await?;
+        Ok(())
+    }
+
+    /// Rolls back to a savepoint created with [`Transaction::savepoint`], keeping the
+    /// transaction open.
+    pub async fn rollback_to_savepoint(&mut self, name: &str) -> Result<()> {
+        let sql = format!("ROLLBACK TO SAVEPOINT {}", savepoint_name(name)?);
+        self.conn()?.execute(&sql, &[]).await?;
+        Ok(())
+    }
+
+    /// Commits the transaction.
+    pub async fn commit(mut self) -> Result<()> {
+        let mut conn = self
+            .conn
+            .take()
+            .ok_or_else(|| DbError::DbError("Transaction connection closed".to_string()))?;
+        conn.commit().await
+    }
+
+    /// Rolls back the transaction.
+    pub async fn rollback(mut self) -> Result<()> {
+        let mut conn = self
+            .conn
+            .take()
+            .ok_or_else(|| DbError::DbError("Transaction connection closed".to_string()))?;
+        conn.rollback().await
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            tokio::spawn(async move {
+                let _ = conn.rollback().await;
+            });
+        }
+    }
+}
+
+/// Savepoint names are interpolated into SQL, so only plain identifiers are accepted.
+fn savepoint_name(name: &str) -> Result<&str> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        Err(DbError::QueryBuildError(format!(
+            "Invalid savepoint name: {:?}",
+            name
+        )))
+    }
+}
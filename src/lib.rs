@@ -4,18 +4,32 @@ pub mod executor;
 #[doc(hidden)]
 pub mod mapper_loader;
 mod page;
-pub(crate) mod tpl;
+pub mod query_builder;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod tpl;
 pub mod udbc;
 
 use crate::error::DbError;
 #[doc(hidden)]
 pub use ctor;
+pub use driver_manager::U;
+#[allow(deprecated)]
+pub use driver_manager::UORM;
 pub use executor::session::TransactionResult;
+pub use query_builder::CriteriaBuilder;
+pub use tpl::parser::ParserConfig;
 pub use udbc::value::{FromValue, ToValue, Value};
 pub use uorm_macros::Param;
 pub use uorm_macros::mapper_assets;
 pub use uorm_macros::param;
 pub use uorm_macros::sql;
+pub use uorm_macros::sql_delete;
+pub use uorm_macros::sql_get;
+pub use uorm_macros::sql_insert;
+pub use uorm_macros::sql_list;
+pub use uorm_macros::sql_namespace;
+pub use uorm_macros::sql_update;
 pub use uorm_macros::transaction;
 
 pub type Result<T> = std::result::Result<T, DbError>;
@@ -1,6 +1,7 @@
 use crate::Result;
 use crate::error::DbError;
-use crate::tpl::cache;
+use crate::tpl::engine::default_config;
+use crate::tpl::{cache, engine};
 use dashmap::DashMap;
 use glob::glob;
 use quick_xml::events::{BytesStart, Event};
@@ -9,6 +10,9 @@ use std::fs;
 use std::path::Path;
 use std::sync::{Arc, OnceLock};
 
+#[cfg(feature = "toml-mappers")]
+mod toml_format;
+
 /// SQL statement type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StatementType {
@@ -30,6 +34,26 @@ impl StatementType {
             _ => None,
         }
     }
+
+    /// Infers the statement type from the first keyword of a plain SQL string.
+    ///
+    /// `WITH`, `VALUES`, `SHOW` and `EXPLAIN` are treated as `Select`; unknown keywords
+    /// map to `Sql`.
+    pub fn infer(sql: &str) -> Self {
+        let keyword = sql
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match keyword.as_str() {
+            "select" | "with" | "values" | "show" | "explain" => StatementType::Select,
+            "insert" | "replace" => StatementType::Insert,
+            "update" => StatementType::Update,
+            "delete" => StatementType::Delete,
+            _ => StatementType::Sql,
+        }
+    }
 }
 
 /// A SQL statement definition (runtime representation).
@@ -40,11 +64,46 @@ pub struct SqlStatement {
     /// Statement type (SELECT, INSERT, etc.).
     pub r#type: StatementType,
     /// Database type (mysql, sqlite, postgres, etc.). Optional.
+    ///
+    /// May list several comma-separated types (`"mysql,sqlite"`); the statement then serves
+    /// each of them. See [`SqlStatement::database_types`].
     pub database_type: Option<String>,
     /// SQL template content (may contain dynamic XML tags).
     pub content: Option<String>,
     /// Whether to return the generated key.
     pub return_key: bool,
+    /// Whether the statement returns rows (e.g. PostgreSQL `RETURNING`).
+    pub returning: bool,
+    /// Whether `${...}` raw interpolation is allowed (`allowRaw="true"`).
+    pub allow_raw: bool,
+}
+
+impl SqlStatement {
+    /// The database types this statement is registered for; empty for the default variant.
+    pub fn database_types(&self) -> impl Iterator<Item = &str> {
+        split_database_types(self.database_type.as_deref())
+    }
+
+    /// The SQL template, or `""` for a statement without content.
+    pub(crate) fn sql(&self) -> &str {
+        self.content.as_deref().unwrap_or_default()
+    }
+}
+
+/// Splits a `databaseType` attribute on commas, skipping blank entries.
+fn split_database_types(database_type: Option<&str>) -> impl Iterator<Item = &str> {
+    database_type
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Normalizes a `databaseType` attribute: `" mysql, sqlite "` becomes `"mysql,sqlite"` and
+/// a blank value becomes `None`.
+fn normalize_database_type(database_type: Option<String>) -> Option<String> {
+    let types: Vec<&str> = split_database_types(database_type.as_deref()).collect();
+    (!types.is_empty()).then(|| types.join(","))
 }
 
 /// Statement repository.
@@ -74,6 +133,41 @@ pub fn load(pattern: &str) -> Result<()> {
     Ok(())
 }
 
+/// Recursively load every `.xml` file under `dir`.
+///
+/// Symlinked directories are not followed.
+///
+/// All files are attempted even if some fail; the failures are then reported together in a
+/// single `MapperLoadError`. Files that loaded successfully stay registered either way.
+///
+/// # Returns
+/// The number of files loaded.
+pub fn load_directory(dir: &Path) -> Result<usize> {
+    let mut files = Vec::new();
+    collect_xml_files(dir, &mut files)?;
+    files.sort();
+
+    let mut loaded = 0;
+    let mut errors = Vec::new();
+    for path in &files {
+        match load_file(path) {
+            Ok(()) => loaded += 1,
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(loaded);
+    }
+    let details: Vec<String> = errors.iter().map(|e| format!("  - {}", e)).collect();
+    Err(DbError::MapperLoadError(format!(
+        "{} 个 Mapper 文件加载失败 (目录: {}):\n{}",
+        errors.len(),
+        dir.display(),
+        details.join("\n")
+    )))
+}
+
 /// Load embedded mapper assets (typically compiled into the binary).
 pub fn load_assets(assets: Vec<(&str, &str)>) -> Result<()> {
     for (source, content) in assets {
@@ -82,6 +176,79 @@ pub fn load_assets(assets: Vec<(&str, &str)>) -> Result<()> {
     Ok(())
 }
 
+/// Register the statements of a TOML mapper definition.
+///
+/// The namespace comes from `[namespace] name = "..."` and each `[statements.<id>]` table
+/// defines one statement with `type` and `sql`, plus the optional `id`, `database_type`,
+/// `use_generated_keys`, `returning` and `allow_raw` keys. `sql` is the same template
+/// language as the body of an XML statement. `source` only labels error messages.
+#[cfg(feature = "toml-mappers")]
+pub fn load_toml_str(source: &str, content: &str) -> Result<()> {
+    let (namespace, items) = toml_format::parse_toml(content, source)?;
+    register_parsed(&namespace, items, source)?;
+    Ok(())
+}
+
+/// Largest uncompressed ZIP entry [`load_zip`] accepts; mapper files are far smaller.
+#[cfg(feature = "zip-mappers")]
+const MAX_ZIP_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Load every `.xml` entry of a ZIP archive, e.g. mapper files shipped next to the binary.
+///
+/// Stored and deflated entries are supported. Entries that are not valid UTF-8 or
+/// decompress to more than 64 MiB are rejected, as are corrupt archives.
+///
+/// # Returns
+/// The number of statements (including `<sql>` fragments) loaded.
+#[cfg(feature = "zip-mappers")]
+pub fn load_zip(reader: impl std::io::Read + std::io::Seek) -> Result<usize> {
+    use std::io::Read;
+
+    let zip_err =
+        |e: zip::result::ZipError| DbError::MapperLoadError(format!("ZIP 读取失败: {}", e));
+    let mut archive = zip::ZipArchive::new(reader).map_err(zip_err)?;
+    let mut loaded = 0;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(zip_err)?;
+        let name = entry.name().map_err(zip_err)?.into_owned();
+        if entry.is_dir() || !name.ends_with(".xml") {
+            continue;
+        }
+        let too_large = || {
+            DbError::MapperLoadError(format!(
+                "Mapper 文件过大: {} (上限 {} 字节)",
+                name, MAX_ZIP_ENTRY_SIZE
+            ))
+        };
+        if entry.size() > MAX_ZIP_ENTRY_SIZE {
+            return Err(too_large());
+        }
+        // The declared size can't be trusted, so decompression itself is capped too.
+        let mut bytes = Vec::new();
+        entry
+            .take(MAX_ZIP_ENTRY_SIZE + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|e| DbError::MapperLoadError(format!("ZIP 读取失败: {} - {}", name, e)))?;
+        if bytes.len() as u64 > MAX_ZIP_ENTRY_SIZE {
+            return Err(too_large());
+        }
+        let xml_content = String::from_utf8(bytes).map_err(|e| {
+            DbError::MapperLoadError(format!("Mapper 文件不是有效的 UTF-8: {} - {}", name, e))
+        })?;
+        loaded += parse_and_register(&xml_content, &name)?;
+    }
+    Ok(loaded)
+}
+
+/// Open `path` and load it with [`load_zip`].
+#[cfg(feature = "zip-mappers")]
+pub fn load_zip_file(path: &Path) -> Result<usize> {
+    let file = fs::File::open(path).map_err(|e| {
+        DbError::MapperLoadError(format!("无法打开 ZIP 文件: {} - {}", path.display(), e))
+    })?;
+    load_zip(std::io::BufReader::new(file))
+}
+
 /// Find a SQL statement definition by SQL id.
 ///
 /// # Parameters
@@ -96,8 +263,8 @@ pub fn find_statement(full_id: &str, db_type: &str) -> Option<Arc<SqlStatement>>
     // Prefer an entry that matches `database_type`; fall back to the default (`None`) entry.
     let mut fallback = None;
     for stmt in statements.value().iter() {
-        match stmt.database_type.as_deref() {
-            Some(t) if t == db_type => return Some(stmt.clone()),
+        match stmt.database_type {
+            Some(_) if stmt.database_types().any(|t| t == db_type) => return Some(stmt.clone()),
             None => fallback = Some(stmt.clone()),
             _ => {}
         }
@@ -106,6 +273,94 @@ pub fn find_statement(full_id: &str, db_type: &str) -> Option<Arc<SqlStatement>>
     fallback
 }
 
+/// Atomically replace all statements of a namespace with the ones in `xml_content`.
+///
+/// The XML is fully parsed and validated before anything is touched; on error the
+/// currently loaded namespace stays intact. Other namespaces are never affected.
+pub fn reload_namespace(namespace: &str, xml_content: &str) -> Result<()> {
+    let source = format!("reload:{}", namespace);
+    let (parsed_namespace, items) = parse_xml(xml_content, &source)?;
+    if parsed_namespace != namespace {
+        return Err(DbError::MapperLoadError(format!(
+            "命名空间不匹配: 期望 '{}', 实际 '{}' (Source: '{}')",
+            namespace, parsed_namespace, source
+        )));
+    }
+
+    let ns_map = DashMap::new();
+    let templates = collect_statements(&ns_map, namespace, items, &source)?;
+
+    // Validation passed: swap the template cache and the namespace entry.
+    cache::remove_namespace(namespace);
+    for (full_id, content) in &templates {
+        cache::get_ast(full_id, content, &default_config());
+    }
+    STATEMENTS
+        .get_or_init(DashMap::new)
+        .insert(namespace.to_string(), ns_map);
+    Ok(())
+}
+
+/// An `<include>` reference that doesn't resolve to any loaded template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    /// Namespace of the statement containing the include.
+    pub namespace: String,
+    /// Id of the statement containing the include.
+    pub statement_id: String,
+    /// The unresolved `refid`.
+    pub missing_ref: String,
+}
+
+/// Check every `<include refid="...">` in the loaded statements.
+///
+/// Unresolved references are reported as warnings instead of an error, so the caller can
+/// decide whether they are fatal.
+pub fn validate() -> Result<Vec<ValidationWarning>> {
+    let Some(store) = STATEMENTS.get() else {
+        return Ok(Vec::new());
+    };
+
+    let mut templates = Vec::new();
+    for ns in store.iter() {
+        for stmts in ns.value().iter() {
+            for stmt in stmts.value() {
+                if let Some(content) = &stmt.content {
+                    templates.push((ns.key().clone(), stmts.key().clone(), content.clone()));
+                }
+            }
+        }
+    }
+
+    // Make sure every template is cached before resolving includes against the cache.
+    for (namespace, id, content) in &templates {
+        cache::get_ast(&format!("{}.{}", namespace, id), content, &default_config());
+    }
+
+    let mut warnings = Vec::new();
+    for (namespace, id, content) in templates {
+        let full_id = format!("{}.{}", namespace, id);
+        for missing_ref in engine::missing_includes(&full_id, &content) {
+            let warning = ValidationWarning {
+                namespace: namespace.clone(),
+                statement_id: id.clone(),
+                missing_ref,
+            };
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+    warnings.sort_by(|a, b| {
+        (&a.namespace, &a.statement_id, &a.missing_ref).cmp(&(
+            &b.namespace,
+            &b.statement_id,
+            &b.missing_ref,
+        ))
+    });
+    Ok(warnings)
+}
+
 /// Clear all loaded statements (mainly to reset state in tests).
 pub fn clear() {
     if let Some(store) = STATEMENTS.get() {
@@ -113,6 +368,76 @@ pub fn clear() {
     }
 }
 
+/// Insert or replace a single statement without going through XML.
+///
+/// Any existing definitions of `namespace.id` (including database-specific variants) are
+/// replaced by one default entry, and the template cache is refreshed. Handy for overriding
+/// a statement in tests or registering generated SQL at startup.
+///
+/// # Errors
+/// `DbError::MapperLoadError` if `sql_content` uses `${...}` raw interpolation, which is only
+/// allowed for XML statements declared with `allowRaw="true"`.
+pub fn register_statement(
+    namespace: &str,
+    id: &str,
+    stmt_type: StatementType,
+    sql_content: &str,
+) -> Result<()> {
+    let content = sql_content.trim().to_string();
+    if engine::has_raw_vars(&content) {
+        return Err(DbError::MapperLoadError(format!(
+            "SQL '{}.{}' 使用了 ${{...}} 原样插值, 仅允许在设置 allowRaw=\"true\" 的 XML 语句中使用",
+            namespace, id
+        )));
+    }
+
+    let full_id = format!("{}.{}", namespace, id);
+    cache::TEMPLATE_CACHE.remove(&full_id);
+    cache::get_ast(&full_id, &content, &default_config());
+
+    let statement = SqlStatement {
+        r#type: stmt_type,
+        database_type: None,
+        content: Some(content),
+        return_key: false,
+        returning: false,
+        allow_raw: false,
+    };
+    STATEMENTS
+        .get_or_init(DashMap::new)
+        .entry(namespace.to_string())
+        .or_default()
+        .insert(id.to_string(), vec![Arc::new(statement)]);
+    Ok(())
+}
+
+/// Remove a single statement (all database variants) and its cached template.
+///
+/// # Returns
+/// `true` if the statement existed.
+pub fn remove_statement(namespace: &str, id: &str) -> bool {
+    cache::TEMPLATE_CACHE.remove(&format!("{}.{}", namespace, id));
+    STATEMENTS
+        .get()
+        .and_then(|store| store.get(namespace).and_then(|ns_map| ns_map.remove(id)))
+        .is_some()
+}
+
+/// Remove one namespace's statements and their cached templates.
+///
+/// Other namespaces are untouched, so a namespace can be reloaded with
+/// `clear_namespace("user")` followed by `load_assets(...)`.
+///
+/// # Returns
+/// The number of statement ids removed (0 if the namespace wasn't loaded).
+pub fn clear_namespace(namespace: &str) -> usize {
+    cache::remove_namespace(namespace);
+    STATEMENTS
+        .get()
+        .and_then(|store| store.remove(namespace))
+        .map_or(0, |(_, ns_map)| ns_map.len())
+}
+
 // --- Internal implementation ---
 
 fn load_file(path: &Path) -> Result<()> {
@@ -123,42 +448,98 @@ fn load_file(path: &Path) -> Result<()> {
             e
         ))
     })?;
-    parse_and_register(&xml_content, &path.display().to_string())
+    parse_and_register(&xml_content, &path.display().to_string())?;
+    Ok(())
 }
 
-fn parse_and_register(xml_content: &str, source: &str) -> Result<()> {
+fn collect_xml_files(dir: &Path, out: &mut Vec<std::path::PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        DbError::MapperLoadError(format!("无法读取目录: {} - {}", dir.display(), e))
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            DbError::MapperLoadError(format!("无法读取目录: {} - {}", dir.display(), e))
+        })?;
+        let path = entry.path();
+        // `file_type` does not follow symlinks, so a link back to a parent can't loop;
+        // symlinked files are still loaded.
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir {
+            collect_xml_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "xml") && path.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parses one mapper file and registers its statements, returning how many were added.
+fn parse_and_register(xml_content: &str, source: &str) -> Result<usize> {
     let (namespace, items) = parse_xml(xml_content, source)?;
+    register_parsed(&namespace, items, source)
+}
+
+fn register_parsed(namespace: &str, items: Vec<ParsedItem>, source: &str) -> Result<usize> {
+    let count = items.len();
 
     let store = STATEMENTS.get_or_init(DashMap::new);
-    let ns_map = store.entry(namespace.clone()).or_default();
+    let ns_map = store.entry(namespace.to_string()).or_default();
+
+    let templates = collect_statements(&ns_map, namespace, items, source)?;
+
+    // Register in template cache for <include> tags.
+    for (full_id, content) in &templates {
+        cache::get_ast(full_id, content, &default_config());
+    }
+    Ok(count)
+}
+
+/// Add parsed statements to `ns_map`, rejecting duplicates.
+///
+/// Returns the `(full_id, content)` pairs that should be registered in the template cache.
+fn collect_statements(
+    ns_map: &DashMap<String, Vec<Arc<SqlStatement>>>,
+    namespace: &str,
+    items: Vec<ParsedItem>,
+    source: &str,
+) -> Result<Vec<(String, String)>> {
+    let mut templates = Vec::new();
 
     for mut statement in items {
         if let Some(content) = &mut statement.content {
             *content = content.trim().to_string();
         }
 
-        // Register in template cache for <include> tags.
-        if let Some(content) = &statement.content {
-            let full_id = format!("{}.{}", namespace, statement.id);
-            cache::get_ast(&full_id, content);
-        }
-
         let mut statements = ns_map.entry(statement.id.clone()).or_default();
 
-        // Reject duplicate definitions.
-        if statements
-            .iter()
-            .any(|s| s.database_type == statement.database_type)
-        {
+        // Reject duplicate definitions, including overlaps such as `mysql` vs `mysql,sqlite`.
+        let duplicate = statements.iter().any(|s| match &statement.database_type {
+            None => s.database_type.is_none(),
+            Some(_) => s
+                .database_types()
+                .any(|t| split_database_types(statement.database_type.as_deref()).any(|n| n == t)),
+        });
+        if duplicate {
             return Err(DbError::MapperLoadError(format!(
                 "重复的 SQL ID 定义: '{}' (Database: '{:?}', Source: '{}')",
                 statement.id, statement.database_type, source
             )));
         }
 
+        if let Some(content) = &statement.content {
+            // `${...}` bypasses parameter binding, so it must be enabled explicitly.
+            if !statement.allow_raw && engine::has_raw_vars(content) {
+                return Err(DbError::MapperLoadError(format!(
+                    "SQL '{}' 使用了 ${{...}} 原样插值, 需要设置 allowRaw=\"true\" (Source: '{}')",
+                    statement.id, source
+                )));
+            }
+            templates.push((format!("{}.{}", namespace, statement.id), content.clone()));
+        }
+
         statements.push(Arc::new(statement.into_sql_statement()));
     }
-    Ok(())
+    Ok(templates)
 }
 
 struct ParsedItem {
@@ -166,6 +547,8 @@ struct ParsedItem {
     id: String,
     database_type: Option<String>,
     return_key: bool,
+    returning: bool,
+    allow_raw: bool,
     content: Option<String>,
 }
 
@@ -176,6 +559,8 @@ impl ParsedItem {
             database_type: self.database_type,
             content: self.content,
             return_key: self.return_key,
+            returning: self.returning,
+            allow_raw: self.allow_raw,
         }
     }
 }
@@ -204,8 +589,10 @@ fn parse_xml(xml: &str, source: &str) -> Result<(String, Vec<ParsedItem>)> {
                         DbError::MapperLoadError(format!("SQL 语句缺少 id 属性: {}", source))
                     })?;
 
-                    let database_type = get_attribute(e, "databaseType");
+                    let database_type = normalize_database_type(get_attribute(e, "databaseType"));
                     let return_key = parse_bool(get_attribute(e, "returnKey").as_deref());
+                    let returning = parse_bool(get_attribute(e, "returning").as_deref());
+                    let allow_raw = parse_bool(get_attribute(e, "allowRaw").as_deref());
 
                     // Use the end of the start tag as the content start position.
                     let start_pos = reader.buffer_position() as usize;
@@ -243,6 +630,8 @@ fn parse_xml(xml: &str, source: &str) -> Result<(String, Vec<ParsedItem>)> {
                         id,
                         database_type,
                         return_key,
+                        returning,
+                        allow_raw,
                         content,
                     });
                 }
@@ -266,6 +655,7 @@ fn parse_xml(xml: &str, source: &str) -> Result<(String, Vec<ParsedItem>)> {
 }
 
 // Helper: read until the matching end tag is found, and return the position right after it.
+#[allow(clippy::collapsible_match)]
 fn read_until_end_tag(
     reader: &mut Reader<&[u8]>,
     target_tag: &str,
@@ -313,3 +703,370 @@ fn parse_bool(s: Option<&str>) -> bool {
         "true" | "1" | "yes" | "on"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_statement_type() {
+        assert_eq!(
+            StatementType::infer("  select * from t"),
+            StatementType::Select
+        );
+        assert_eq!(
+            StatementType::infer("WITH x AS (SELECT 1) SELECT * FROM x"),
+            StatementType::Select
+        );
+        assert_eq!(
+            StatementType::infer("(SELECT 1) UNION (SELECT 2)"),
+            StatementType::Select
+        );
+        assert_eq!(
+            StatementType::infer("INSERT INTO t VALUES (?)"),
+            StatementType::Insert
+        );
+        assert_eq!(
+            StatementType::infer("update t set a = ?"),
+            StatementType::Update
+        );
+        assert_eq!(StatementType::infer("DELETE FROM t"), StatementType::Delete);
+        assert_eq!(
+            StatementType::infer("CREATE TABLE t (id INT)"),
+            StatementType::Sql
+        );
+    }
+
+    const V1: &str = r#"<mapper namespace="reload_test">
+        <select id="get">SELECT 1</select>
+        <select id="old_only">SELECT 'old'</select>
+    </mapper>"#;
+
+    const V2: &str = r#"<mapper namespace="reload_test">
+        <select id="get">SELECT 2</select>
+    </mapper>"#;
+
+    #[test]
+    fn test_reload_namespace() {
+        load_assets(vec![("reload_test.xml", V1)]).unwrap();
+        let stmt = find_statement("reload_test.get", "sqlite").unwrap();
+        assert_eq!(stmt.content.as_deref(), Some("SELECT 1"));
+
+        reload_namespace("reload_test", V2).unwrap();
+        let stmt = find_statement("reload_test.get", "sqlite").unwrap();
+        assert_eq!(stmt.content.as_deref(), Some("SELECT 2"));
+        assert!(find_statement("reload_test.old_only", "sqlite").is_none());
+
+        // Invalid statements, duplicates and namespace mismatches leave the namespace untouched.
+        let missing_id = r#"<mapper namespace="reload_test"><select>SELECT 3</select></mapper>"#;
+        assert!(reload_namespace("reload_test", missing_id).is_err());
+        let dup = r#"<mapper namespace="reload_test">
+            <select id="get">SELECT 3</select>
+            <select id="get">SELECT 4</select>
+        </mapper>"#;
+        assert!(reload_namespace("reload_test", dup).is_err());
+        assert!(reload_namespace("other", V2).is_err());
+
+        let stmt = find_statement("reload_test.get", "sqlite").unwrap();
+        assert_eq!(stmt.content.as_deref(), Some("SELECT 2"));
+    }
+
+    #[test]
+    fn test_register_and_remove_statement() {
+        let xml = r#"<mapper namespace="override_test">
+            <select id="get">SELECT 1</select>
+            <select id="get" databaseType="sqlite">SELECT 1 /* sqlite */</select>
+        </mapper>"#;
+        load_assets(vec![("override_test.xml", xml)]).unwrap();
+
+        register_statement("override_test", "get", StatementType::Select, " SELECT 2 ").unwrap();
+        let stmt = find_statement("override_test.get", "sqlite").unwrap();
+        assert_eq!(stmt.content.as_deref(), Some("SELECT 2"));
+        assert_eq!(stmt.r#type, StatementType::Select);
+
+        register_statement(
+            "generated",
+            "touch",
+            StatementType::Update,
+            "UPDATE t SET a = 1",
+        )
+        .unwrap();
+        assert!(find_statement("generated.touch", "mysql").is_some());
+        assert!(cache::TEMPLATE_CACHE.contains_key("generated.touch"));
+
+        assert!(
+            register_statement("generated", "raw", StatementType::Select, "SELECT ${col}").is_err()
+        );
+        assert!(find_statement("generated.raw", "sqlite").is_none());
+
+        assert!(remove_statement("generated", "touch"));
+        assert!(!remove_statement("generated", "touch"));
+        assert!(find_statement("generated.touch", "sqlite").is_none());
+        assert!(!cache::TEMPLATE_CACHE.contains_key("generated.touch"));
+    }
+
+    #[test]
+    fn test_multiple_database_types() {
+        let xml = r#"<mapper namespace="multi_db_test">
+            <select id="get">SELECT 1</select>
+            <select id="get" databaseType="mysql, sqlite">SELECT 2</select>
+        </mapper>"#;
+        load_assets(vec![("multi_db_test.xml", xml)]).unwrap();
+
+        let mysql = find_statement("multi_db_test.get", "mysql").unwrap();
+        let sqlite = find_statement("multi_db_test.get", "sqlite").unwrap();
+        assert!(Arc::ptr_eq(&mysql, &sqlite));
+        assert_eq!(mysql.database_type.as_deref(), Some("mysql,sqlite"));
+        let fallback = find_statement("multi_db_test.get", "postgres").unwrap();
+        assert_eq!(fallback.content.as_deref(), Some("SELECT 1"));
+
+        let overlap = r#"<mapper namespace="multi_db_dup">
+            <select id="get" databaseType="mysql">SELECT 1</select>
+            <select id="get" databaseType="mysql,sqlite">SELECT 2</select>
+        </mapper>"#;
+        let err = load_assets(vec![("multi_db_dup.xml", overlap)]).unwrap_err();
+        assert!(err.to_string().contains("重复的 SQL ID 定义"));
+    }
+
+    #[test]
+    fn test_clear_namespace() {
+        let keep = r#"<mapper namespace="clear_keep"><select id="get">SELECT 1</select></mapper>"#;
+        let drop = r#"<mapper namespace="clear_drop">
+            <select id="a">SELECT 1</select>
+            <select id="b">SELECT 2</select>
+        </mapper>"#;
+        load_assets(vec![("clear_keep.xml", keep), ("clear_drop.xml", drop)]).unwrap();
+        assert!(cache::TEMPLATE_CACHE.contains_key("clear_drop.a"));
+
+        assert_eq!(clear_namespace("clear_drop"), 2);
+        assert!(find_statement("clear_drop.a", "sqlite").is_none());
+        assert!(!cache::TEMPLATE_CACHE.contains_key("clear_drop.a"));
+        assert!(find_statement("clear_keep.get", "sqlite").is_some());
+        assert_eq!(clear_namespace("clear_drop"), 0);
+
+        // The namespace can be loaded again once cleared.
+        load_assets(vec![("clear_drop.xml", drop)]).unwrap();
+        assert!(find_statement("clear_drop.b", "sqlite").is_some());
+    }
+
+    #[test]
+    fn test_validate_reports_missing_includes() {
+        let xml = r#"<mapper namespace="validate_test">
+            <sql id="cols">id, name</sql>
+            <select id="ok">SELECT <include refid="cols"/> FROM t</select>
+            <select id="broken">
+                SELECT <include refid="cols"/> FROM t
+                <if test="x != null"><include refid="other_ns.where"/></if>
+            </select>
+        </mapper>"#;
+        load_assets(vec![("validate_test.xml", xml)]).unwrap();
+
+        let warnings: Vec<_> = validate()
+            .unwrap()
+            .into_iter()
+            .filter(|w| w.namespace == "validate_test")
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![ValidationWarning {
+                namespace: "validate_test".to_string(),
+                statement_id: "broken".to_string(),
+                missing_ref: "other_ns.where".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_raw_interpolation_requires_allow_raw() {
+        let denied = r#"<mapper namespace="raw_denied">
+            <select id="list">SELECT * FROM ${table}</select>
+        </mapper>"#;
+        assert!(load_assets(vec![("raw_denied.xml", denied)]).is_err());
+
+        let allowed = r#"<mapper namespace="raw_allowed">
+            <select id="list" allowRaw="true">SELECT * FROM ${table}</select>
+        </mapper>"#;
+        load_assets(vec![("raw_allowed.xml", allowed)]).unwrap();
+        let stmt = find_statement("raw_allowed.list", "sqlite").unwrap();
+        assert!(stmt.allow_raw);
+    }
+
+    #[test]
+    fn test_load_directory() {
+        let dir = std::env::temp_dir().join(format!("uorm_load_dir_{}", std::process::id()));
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            dir.join("a.xml"),
+            r#"<mapper namespace="load_dir_a"><select id="get">SELECT 1</select></mapper>"#,
+        )
+        .unwrap();
+        fs::write(
+            nested.join("b.xml"),
+            r#"<mapper namespace="load_dir_b"><select id="get">SELECT 2</select></mapper>"#,
+        )
+        .unwrap();
+        fs::write(nested.join("notes.txt"), "not a mapper").unwrap();
+
+        assert_eq!(load_directory(&dir).unwrap(), 2);
+        assert!(find_statement("load_dir_b.get", "sqlite").is_some());
+
+        // Every broken file is reported, and the good ones still load.
+        fs::write(dir.join("bad1.xml"), "<mapper>").unwrap();
+        fs::write(nested.join("bad2.xml"), "<mapper>").unwrap();
+        let err = load_directory(&dir).unwrap_err().to_string();
+        assert!(
+            err.contains("bad1.xml") && err.contains("bad2.xml"),
+            "{}",
+            err
+        );
+        assert!(find_statement("load_dir_a.get", "sqlite").is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_directory_ignores_symlink_loops() {
+        let dir = std::env::temp_dir().join(format!("uorm_load_loop_{}", std::process::id()));
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        std::os::unix::fs::symlink(&dir, nested.join("loop")).unwrap();
+        fs::write(
+            nested.join("c.xml"),
+            r#"<mapper namespace="load_loop_c"><select id="get">SELECT 3</select></mapper>"#,
+        )
+        .unwrap();
+
+        assert_eq!(load_directory(&dir).unwrap(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Builds an archive with one deflated entry per file (the name list order is kept).
+    #[cfg(feature = "zip-mappers")]
+    fn build_zip(files: &[(&str, &str)]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[cfg(feature = "zip-mappers")]
+    #[test]
+    fn test_load_zip() {
+        let archive = build_zip(&[
+            (
+                "mappers/a.xml",
+                r#"<mapper namespace="zip_a"><sql id="cols">id</sql><select id="get">SELECT <include refid="cols"/> FROM t</select></mapper>"#,
+            ),
+            ("mappers/README.txt", "not a mapper"),
+            (
+                "mappers/b.xml",
+                r#"<mapper namespace="zip_b"><delete id="clear">DELETE FROM t</delete></mapper>"#,
+            ),
+        ]);
+        assert_eq!(load_zip(std::io::Cursor::new(&archive)).unwrap(), 3);
+        assert!(find_statement("zip_a.get", "sqlite").is_some());
+        assert!(find_statement("zip_b.clear", "sqlite").is_some());
+
+        let mut corrupt = archive.clone();
+        corrupt[40] ^= 0xff;
+        assert!(matches!(
+            load_zip(std::io::Cursor::new(&corrupt)),
+            Err(DbError::MapperLoadError(_))
+        ));
+        assert!(load_zip(std::io::Cursor::new(b"not a zip".to_vec())).is_err());
+    }
+
+    #[cfg(feature = "zip-mappers")]
+    #[test]
+    fn test_load_zip_rejects_malformed_archives() {
+        let load = |bytes: &[u8]| load_zip(std::io::Cursor::new(bytes.to_vec()));
+        let archive = build_zip(&[(
+            "mappers/c.xml",
+            r#"<mapper namespace="zip_c"><select id="get">SELECT 1</select></mapper>"#,
+        )]);
+
+        // Shorter than an end-of-central-directory record, or cut inside one.
+        assert!(load(b"").is_err());
+        assert!(load(b"PK").is_err());
+        assert!(load(&0x0605_4b50u32.to_le_bytes()).is_err());
+        assert!(load(&archive[..archive.len() - 4]).is_err());
+
+        // The central directory record points past its own data.
+        let dir_offset = u32::from_le_bytes(archive[archive.len() - 6..][..4].try_into().unwrap());
+        let size_at = dir_offset as usize + 24;
+        let set_size = |size: u32| {
+            let mut patched = archive.clone();
+            patched[size_at..size_at + 4].copy_from_slice(&size.to_le_bytes());
+            patched
+        };
+        let mut truncated_dir = archive.clone();
+        truncated_dir[dir_offset as usize + 28] = 0xff;
+        assert!(load(&truncated_dir).is_err());
+
+        // Declared sizes are checked instead of trusted: too large, or smaller than the data.
+        assert!(load(&set_size(u32::MAX - 1)).is_err());
+        assert!(load(&set_size(4)).is_err());
+        assert!(find_statement("zip_c.get", "sqlite").is_none());
+    }
+
+    #[cfg(feature = "toml-mappers")]
+    #[test]
+    fn test_load_toml_str() {
+        let content = r#"
+[namespace]
+name = "toml_users"
+
+[statements.cols]
+type = "sql"
+sql = "id, name"
+
+[statements.list_all]
+type = "select"
+sql = """
+SELECT <include refid="cols"/> FROM users
+<if test="min_age != null">WHERE age >= #{min_age}</if>
+"""
+
+[statements.list_all_mysql]
+id = "list_all"
+type = "select"
+database_type = "mysql"
+sql = "SELECT * FROM users LIMIT 100"
+
+[statements.insert]
+type = "insert"
+use_generated_keys = true
+sql = "INSERT INTO users (name) VALUES (#{name})"
+"#;
+        load_toml_str("users.toml", content).unwrap();
+
+        let insert = find_statement("toml_users.insert", "sqlite").unwrap();
+        assert_eq!(insert.r#type, StatementType::Insert);
+        assert!(insert.return_key);
+        let mysql = find_statement("toml_users.list_all", "mysql").unwrap();
+        assert_eq!(
+            mysql.content.as_deref(),
+            Some("SELECT * FROM users LIMIT 100")
+        );
+        let default = find_statement("toml_users.list_all", "sqlite").unwrap();
+        assert!(default.content.as_deref().unwrap().contains("<include"));
+
+        let err = load_toml_str(
+            "bad.toml",
+            "namespace = \"toml_bad\"\n[statements.x]\ntype = \"select\"\nsqll = \"SELECT 1\"\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("sqll"), "{}", err);
+        assert!(load_toml_str("missing.toml", "[statements.x]\ntype = \"select\"").is_err());
+    }
+}
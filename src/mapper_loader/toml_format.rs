@@ -0,0 +1,110 @@
+//! TOML mapper format:
+//!
+//! ```toml
+//! [namespace]
+//! name = "users"
+//!
+//! [statements.list_all]
+//! type = "select"
+//! sql = "SELECT * FROM users"
+//!
+//! [statements.list_all_mysql]
+//! id = "list_all"            # defaults to the table key
+//! type = "select"
+//! database_type = "mysql"
+//! sql = "SELECT * FROM users LIMIT 100"
+//! ```
+
+use super::{ParsedItem, StatementType, normalize_database_type};
+use crate::Result;
+use crate::error::DbError;
+use toml_edit::{DocumentMut, Item, TableLike};
+
+pub(super) fn parse_toml(content: &str, source: &str) -> Result<(String, Vec<ParsedItem>)> {
+    let doc: DocumentMut = content.parse().map_err(|e| {
+        DbError::MapperLoadError(format!("TOML 解析错误: {} (Source: {})", e, source))
+    })?;
+
+    let namespace = doc
+        .get("namespace")
+        .and_then(|ns| match ns.as_table_like() {
+            Some(table) => table.get("name").and_then(Item::as_str),
+            None => ns.as_str(),
+        })
+        .ok_or_else(|| DbError::MapperLoadError(format!("Mapper 文件缺少 namespace: {}", source)))?
+        .to_string();
+
+    let mut items = Vec::new();
+    match doc.get("statements") {
+        None => {}
+        Some(Item::ArrayOfTables(tables)) => {
+            for table in tables.iter() {
+                items.push(parse_statement(None, table, source)?);
+            }
+        }
+        Some(item) => {
+            let tables = item.as_table_like().ok_or_else(|| {
+                DbError::MapperLoadError(format!("statements 必须是表: {}", source))
+            })?;
+            for (key, item) in tables.iter() {
+                let table = item.as_table_like().ok_or_else(|| {
+                    DbError::MapperLoadError(format!("statements.{} 必须是表: {}", key, source))
+                })?;
+                items.push(parse_statement(Some(key), table, source)?);
+            }
+        }
+    }
+    Ok((namespace, items))
+}
+
+fn parse_statement(key: Option<&str>, table: &dyn TableLike, source: &str) -> Result<ParsedItem> {
+    let label = key.unwrap_or("[[statements]]");
+    let err = |msg: String| DbError::MapperLoadError(format!("{} (Source: {})", msg, source));
+
+    let mut id = key.map(str::to_string);
+    let mut stmt_type = None;
+    let mut database_type = None;
+    let mut return_key = false;
+    let mut returning = false;
+    let mut allow_raw = false;
+    let mut content = None;
+
+    for (field, value) in table.iter() {
+        let as_str = || {
+            value
+                .as_str()
+                .ok_or_else(|| err(format!("SQL '{}' 的 {} 必须是字符串", label, field)))
+        };
+        let as_bool = || {
+            value
+                .as_bool()
+                .ok_or_else(|| err(format!("SQL '{}' 的 {} 必须是布尔值", label, field)))
+        };
+        match field {
+            "id" => id = Some(as_str()?.to_string()),
+            "type" => {
+                let t = as_str()?;
+                stmt_type = Some(
+                    StatementType::from_str(t)
+                        .ok_or_else(|| err(format!("SQL '{}' 的 type 无效: '{}'", label, t)))?,
+                );
+            }
+            "database_type" => database_type = Some(as_str()?.to_string()),
+            "use_generated_keys" | "return_key" => return_key = as_bool()?,
+            "returning" => returning = as_bool()?,
+            "allow_raw" => allow_raw = as_bool()?,
+            "sql" => content = Some(as_str()?.to_string()),
+            other => return Err(err(format!("SQL '{}' 含有未知字段: '{}'", label, other))),
+        }
+    }
+
+    Ok(ParsedItem {
+        r#type: stmt_type.ok_or_else(|| err(format!("SQL '{}' 缺少 type", label)))?,
+        id: id.ok_or_else(|| err("SQL 语句缺少 id".to_string()))?,
+        database_type: normalize_database_type(database_type),
+        return_key,
+        returning,
+        allow_raw,
+        content,
+    })
+}
@@ -0,0 +1,277 @@
+use crate::udbc::value::{ToValue, Value};
+use std::collections::HashMap;
+
+/// How a condition is joined to the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connector {
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+enum Criterion {
+    Compare {
+        column: String,
+        op: &'static str,
+        value: Value,
+    },
+    In {
+        column: String,
+        values: Vec<Value>,
+    },
+    Null {
+        column: String,
+        negated: bool,
+    },
+    Group(CriteriaBuilder),
+}
+
+/// Composes a `WHERE` clause from optional filters at runtime.
+///
+/// Values are bound as `#{param_N}` placeholders, so the output can be passed straight to
+/// [`Session::query`](crate::executor::session::Session::query):
+///
+/// ```ignore
+/// let (where_clause, params) = CriteriaBuilder::new()
+///     .eq("status", "active")
+///     .gt("age", 18)
+///     .or(CriteriaBuilder::new().is_null("deleted_at"))
+///     .build();
+/// let sql = format!("SELECT * FROM users {}", where_clause);
+/// let users: Vec<User> = session.query(&sql, &params).await?;
+/// ```
+///
+/// Column names are inserted verbatim and must not come from user input.
+#[derive(Debug, Clone, Default)]
+pub struct CriteriaBuilder {
+    criteria: Vec<(Connector, Criterion)>,
+}
+
+impl CriteriaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `column = value`
+    pub fn eq(self, column: impl Into<String>, value: impl ToValue) -> Self {
+        self.compare(column, "=", value)
+    }
+
+    /// `column <> value`
+    pub fn ne(self, column: impl Into<String>, value: impl ToValue) -> Self {
+        self.compare(column, "<>", value)
+    }
+
+    /// `column > value`
+    pub fn gt(self, column: impl Into<String>, value: impl ToValue) -> Self {
+        self.compare(column, ">", value)
+    }
+
+    /// `column < value`
+    pub fn lt(self, column: impl Into<String>, value: impl ToValue) -> Self {
+        self.compare(column, "<", value)
+    }
+
+    /// `column LIKE value`; wildcards are up to the caller.
+    pub fn like(self, column: impl Into<String>, value: impl ToValue) -> Self {
+        self.compare(column, "LIKE", value)
+    }
+
+    /// `column IN (...)`. An empty list matches no rows.
+    pub fn in_list<I, T>(self, column: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: ToValue,
+    {
+        let values = values.into_iter().map(|v| v.to_value()).collect();
+        self.push(
+            Connector::And,
+            Criterion::In {
+                column: column.into(),
+                values,
+            },
+        )
+    }
+
+    /// `column IS NULL`
+    pub fn is_null(self, column: impl Into<String>) -> Self {
+        self.push(
+            Connector::And,
+            Criterion::Null {
+                column: column.into(),
+                negated: false,
+            },
+        )
+    }
+
+    /// `column IS NOT NULL`
+    pub fn is_not_null(self, column: impl Into<String>) -> Self {
+        self.push(
+            Connector::And,
+            Criterion::Null {
+                column: column.into(),
+                negated: true,
+            },
+        )
+    }
+
+    /// Appends `AND (other)`.
+    pub fn and(self, other: CriteriaBuilder) -> Self {
+        self.group(Connector::And, other)
+    }
+
+    /// Combines everything so far with `other` as `(...) OR (other)`.
+    pub fn or(self, other: CriteriaBuilder) -> Self {
+        self.group(Connector::Or, other)
+    }
+
+    /// Whether no conditions have been added.
+    pub fn is_empty(&self) -> bool {
+        self.criteria.is_empty()
+    }
+
+    /// Renders the conditions as `WHERE ...` plus the values for its placeholders.
+    ///
+    /// Returns an empty string (and no parameters) when no conditions were added.
+    pub fn build(&self) -> (String, HashMap<String, Value>) {
+        let mut params = HashMap::new();
+        match self.render(&mut params) {
+            Some(clause) => (format!("WHERE {}", clause), params),
+            None => (String::new(), params),
+        }
+    }
+
+    fn compare(self, column: impl Into<String>, op: &'static str, value: impl ToValue) -> Self {
+        self.push(
+            Connector::And,
+            Criterion::Compare {
+                column: column.into(),
+                op,
+                value: value.to_value(),
+            },
+        )
+    }
+
+    fn group(self, connector: Connector, other: CriteriaBuilder) -> Self {
+        if other.is_empty() {
+            return self;
+        }
+        self.push(connector, Criterion::Group(other))
+    }
+
+    fn push(mut self, connector: Connector, criterion: Criterion) -> Self {
+        self.criteria.push((connector, criterion));
+        self
+    }
+
+    /// Renders the conditions without the `WHERE` keyword, or `None` if there are none.
+    fn render(&self, params: &mut HashMap<String, Value>) -> Option<String> {
+        let mut clause: Option<String> = None;
+        let mut ends_with_or = false;
+        for (connector, criterion) in &self.criteria {
+            let part = match criterion {
+                Criterion::Compare { column, op, value } => {
+                    format!("{} {} {}", column, op, bind(params, value.clone()))
+                }
+                Criterion::In { values, .. } if values.is_empty() => "1 = 0".to_string(),
+                Criterion::In { column, values } => {
+                    let placeholders: Vec<String> =
+                        values.iter().map(|v| bind(params, v.clone())).collect();
+                    format!("{} IN ({})", column, placeholders.join(", "))
+                }
+                Criterion::Null { column, negated } => {
+                    let not = if *negated { "NOT " } else { "" };
+                    format!("{} IS {}NULL", column, not)
+                }
+                Criterion::Group(inner) => match inner.render(params) {
+                    Some(rendered) if inner.criteria.len() > 1 => format!("({})", rendered),
+                    Some(rendered) => rendered,
+                    None => continue,
+                },
+            };
+            clause = Some(match (clause, connector) {
+                (None, _) => part,
+                // Keep left-to-right meaning: `a OR b AND c` becomes `(a OR b) AND c`.
+                (Some(prev), Connector::And) if ends_with_or => format!("({}) AND {}", prev, part),
+                (Some(prev), Connector::And) => format!("{} AND {}", prev, part),
+                (Some(prev), Connector::Or) => format!("{} OR {}", prev, part),
+            });
+            ends_with_or = *connector == Connector::Or;
+        }
+        clause
+    }
+}
+
+/// Stores `value` under the next `param_N` name and returns its placeholder.
+fn bind(params: &mut HashMap<String, Value>, value: Value) -> String {
+    let name = format!("param_{}", params.len() + 1);
+    let placeholder = format!("#{{{}}}", name);
+    params.insert(name, value);
+    placeholder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_conditions() {
+        let (sql, params) = CriteriaBuilder::new()
+            .eq("status", "active")
+            .gt("age", 18)
+            .like("name", "A%")
+            .in_list("role", ["admin", "editor"])
+            .is_not_null("email")
+            .build();
+        assert_eq!(
+            sql,
+            "WHERE status = #{param_1} AND age > #{param_2} AND name LIKE #{param_3} \
+             AND role IN (#{param_4}, #{param_5}) AND email IS NOT NULL"
+        );
+        assert_eq!(params.len(), 5);
+        assert_eq!(params["param_1"], Value::Str("active".to_string()));
+        assert_eq!(params["param_2"], Value::I32(18));
+        assert_eq!(params["param_5"], Value::Str("editor".to_string()));
+    }
+
+    #[test]
+    fn test_build_groups() {
+        let (sql, params) = CriteriaBuilder::new()
+            .ne("status", "deleted")
+            .and(
+                CriteriaBuilder::new()
+                    .lt("age", 10)
+                    .or(CriteriaBuilder::new().gt("age", 60)),
+            )
+            .build();
+        assert_eq!(
+            sql,
+            "WHERE status <> #{param_1} AND (age < #{param_2} OR age > #{param_3})"
+        );
+        assert_eq!(params["param_3"], Value::I32(60));
+
+        let (sql, _) = CriteriaBuilder::new()
+            .eq("a", 1)
+            .eq("b", 2)
+            .or(CriteriaBuilder::new().is_null("c"))
+            .eq("d", 3)
+            .build();
+        assert_eq!(
+            sql,
+            "WHERE (a = #{param_1} AND b = #{param_2} OR c IS NULL) AND d = #{param_3}"
+        );
+    }
+
+    #[test]
+    fn test_build_empty() {
+        let (sql, params) = CriteriaBuilder::new().and(CriteriaBuilder::new()).build();
+        assert_eq!(sql, "");
+        assert!(params.is_empty());
+
+        let (sql, params) = CriteriaBuilder::new()
+            .in_list("id", Vec::<i64>::new())
+            .build();
+        assert_eq!(sql, "WHERE 1 = 0");
+        assert!(params.is_empty());
+    }
+}
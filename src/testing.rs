@@ -0,0 +1,128 @@
+//! Helpers for checking what templates render to, without a database.
+//!
+//! ```ignore
+//! uorm::assert_sql_renders!(
+//!     "user.by_id",
+//!     "SELECT * FROM users WHERE id = #{id}",
+//!     args,
+//!     "SELECT * FROM users WHERE id = ?"
+//! );
+//! uorm::assert_params!("user.by_id", template, args, vec![("id", Value::I64(7))]);
+//! ```
+
+use crate::Result;
+use crate::error::DbError;
+use crate::udbc::connection::Connection;
+use crate::udbc::driver::Driver;
+use async_trait::async_trait;
+
+/// A [`Driver`] that renders `?` placeholders and never connects.
+///
+/// `acquire` always fails, so it only suits code paths that render SQL, such as
+/// [`render_template`](crate::tpl::engine::render_template).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockDriver;
+
+#[async_trait]
+impl Driver for MockDriver {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn r#type(&self) -> &str {
+        "mock"
+    }
+
+    fn placeholder(&self, _param_seq: usize, _param_name: &str) -> String {
+        "?".to_string()
+    }
+
+    async fn acquire(&self) -> Result<Box<dyn Connection>> {
+        Err(DbError::DriverError(
+            "MockDriver has no database connections".to_string(),
+        ))
+    }
+
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Asserts that a template renders to `expected_sql` with [`MockDriver`] (`?` placeholders).
+///
+/// The template is parsed on every call and not added to the template cache, so
+/// `$template_name` can't replace a cached mapper statement of the same name.
+///
+/// Panics if the template fails to render.
+#[macro_export]
+macro_rules! assert_sql_renders {
+    ($template_name:expr, $template:expr, $args:expr, $expected_sql:expr $(,)?) => {{
+        let (sql, _) = $crate::tpl::engine::render_uncached(
+            $template_name,
+            $template,
+            &$args,
+            &$crate::testing::MockDriver,
+            &$crate::ParserConfig::default(),
+        )
+        .expect("template failed to render");
+        assert_eq!(sql, $expected_sql);
+    }};
+}
+
+/// Asserts the parameters a template binds, in order, as `(name, Value)` pairs.
+///
+/// Panics if the template fails to render.
+#[macro_export]
+macro_rules! assert_params {
+    ($template_name:expr, $template:expr, $args:expr, $expected_params:expr $(,)?) => {{
+        let (_, params) = $crate::tpl::engine::render_uncached(
+            $template_name,
+            $template,
+            &$args,
+            &$crate::testing::MockDriver,
+            &$crate::ParserConfig::default(),
+        )
+        .expect("template failed to render");
+        let expected: Vec<(String, $crate::Value)> = $expected_params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        assert_eq!(params, expected);
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_assertions() {
+        let mut args = HashMap::new();
+        args.insert("name".to_string(), Value::Str("Alice".to_string()));
+        args.insert(
+            "ids".to_string(),
+            Value::List(vec![Value::I64(1), Value::I64(2)]),
+        );
+        let template = "SELECT * FROM users WHERE name = #{name}\
+            <foreach item=\"id\" collection=\"ids\" open=\" AND id IN (\" separator=\",\" close=\")\">#{id}</foreach>";
+
+        assert_sql_renders!(
+            "testing.users",
+            template,
+            args,
+            "SELECT * FROM users WHERE name = ? AND id IN (?,?)"
+        );
+        assert_params!(
+            "testing.users",
+            template,
+            args,
+            vec![
+                ("name", Value::Str("Alice".to_string())),
+                ("id", Value::I64(1)),
+                ("id", Value::I64(2)),
+            ]
+        );
+        assert!(!crate::tpl::cache::TEMPLATE_CACHE.contains_key("testing.users"));
+    }
+}
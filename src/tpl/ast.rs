@@ -17,21 +17,68 @@ pub enum Expr {
     Literal(Value),
     Var(String),
     Binary(Op, Box<Expr>, Box<Expr>),
+    /// `a + b + ...`: the parts joined as text.
+    Concat(Vec<Expr>),
+    /// `var in (a, b, ...)` / `var not in (a, b, ...)`
+    In {
+        var: Box<Expr>,
+        values: Vec<Expr>,
+        negated: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum AstNode {
     Text(String),
     Var(String),
+    /// `${name}`: the value is written into the SQL text verbatim, not bound as a parameter.
+    RawVar(String),
     Include {
         refid: String,
     },
+    /// `<bind name="..." value="..."/>`: evaluates `value` and makes it available as `name`
+    /// to the rest of the enclosing block.
+    Bind {
+        name: String,
+        value: Expr,
+    },
     If {
         test: Expr,
         body: Vec<AstNode>,
+        /// The nodes after `<else/>`, rendered when `test` is falsy.
+        else_body: Vec<AstNode>,
+    },
+    /// `<where>`: renders `WHERE <body>` with a leading `AND`/`OR` removed, or nothing when
+    /// the body renders empty.
+    Where {
+        body: Vec<AstNode>,
+    },
+    /// `<set>`: renders `SET <body>` with a trailing comma removed, or nothing when the body
+    /// renders empty.
+    Set {
+        body: Vec<AstNode>,
+    },
+    /// `<trim>`: the general form of `<where>` and `<set>`. The rendered body is trimmed, the
+    /// first matching `prefix_overrides` / `suffix_overrides` token is removed from its start /
+    /// end, and the result is wrapped in `prefix` and `suffix`. Renders nothing when empty.
+    Trim {
+        prefix: String,
+        suffix: String,
+        prefix_overrides: Vec<String>,
+        suffix_overrides: Vec<String>,
+        body: Vec<AstNode>,
+    },
+    /// `<choose>`: renders the body of the first `<when>` whose test is truthy, or the
+    /// `<otherwise>` body if none is.
+    Choose {
+        branches: Vec<(Expr, Vec<AstNode>)>,
+        otherwise: Vec<AstNode>,
     },
     Foreach {
         item: String,
+        /// Local holding the zero-based iteration index, or the key when iterating a map: the
+        /// `index` attribute, or `<item>_index` by default.
+        index: String,
         collection: String,
         open: String,
         separator: String,
@@ -1,5 +1,5 @@
 use crate::tpl::ast::AstNode;
-use crate::tpl::parser::parse_template;
+use crate::tpl::parser::{ParserConfig, parse_template_with_config};
 use dashmap::DashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -7,32 +7,73 @@ use std::sync::{Arc, LazyLock};
 
 #[derive(Clone)]
 pub struct CachedTemplate {
+    /// The AST parsed most recently, under any parser config.
     pub ast: Arc<Vec<AstNode>>,
-    pub content_hash: u64,
+    /// One entry per parser config the template has been parsed with, so alternating
+    /// configs don't evict each other.
+    parsed: Vec<ParsedTemplate>,
 }
 
-/// Cache for parsed template ASTs.
+#[derive(Clone)]
+struct ParsedTemplate {
+    config_hash: u64,
+    content_hash: u64,
+    ast: Arc<Vec<AstNode>>,
+}
+
+/// Cache for parsed template ASTs, keyed by template name and then by parser config.
 pub(crate) static TEMPLATE_CACHE: LazyLock<DashMap<String, CachedTemplate>> =
     LazyLock::new(DashMap::new);
 
-pub(crate) fn get_ast(template_name: &str, template_content: &str) -> Arc<Vec<AstNode>> {
-    let mut hasher = DefaultHasher::new();
-    template_content.hash(&mut hasher);
-    let new_hash = hasher.finish();
+/// Returns the AST for `template_name` under `config`, re-parsing when the content changed.
+pub(crate) fn get_ast(
+    template_name: &str,
+    template_content: &str,
+    config: &ParserConfig,
+) -> Arc<Vec<AstNode>> {
+    let config_hash = hash_of(config);
+    let content_hash = hash_of(template_content);
 
     if let Some(cached) = TEMPLATE_CACHE.get(template_name)
-        && cached.content_hash == new_hash
+        && let Some(parsed) = cached
+            .parsed
+            .iter()
+            .find(|p| p.config_hash == config_hash && p.content_hash == content_hash)
     {
-        return cached.ast.clone();
+        return parsed.ast.clone();
     }
 
-    let ast = Arc::new(parse_template(template_content));
-    TEMPLATE_CACHE.insert(
-        template_name.to_string(),
-        CachedTemplate {
+    let ast = Arc::new(parse_template_with_config(template_content, config));
+    let parsed = ParsedTemplate {
+        config_hash,
+        content_hash,
+        ast: ast.clone(),
+    };
+    TEMPLATE_CACHE
+        .entry(template_name.to_string())
+        .and_modify(|cached| {
+            // New content replaces the ASTs of the old content under every config.
+            cached
+                .parsed
+                .retain(|p| p.config_hash != config_hash && p.content_hash == content_hash);
+            cached.parsed.push(parsed.clone());
+            cached.ast = ast.clone();
+        })
+        .or_insert_with(|| CachedTemplate {
             ast: ast.clone(),
-            content_hash: new_hash,
-        },
-    );
+            parsed: vec![parsed],
+        });
     ast
 }
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Removes all cached templates that belong to `namespace`.
+pub(crate) fn remove_namespace(namespace: &str) {
+    let prefix = format!("{}.", namespace);
+    TEMPLATE_CACHE.retain(|name, _| !name.starts_with(&prefix));
+}
@@ -1,9 +1,47 @@
 use crate::Result;
+use crate::tpl::ast::AstNode;
+use crate::tpl::parser::{ParserConfig, parse_template, parse_template_with_config};
 use crate::tpl::render::RenderBuffer;
 use crate::tpl::render_context::Context;
 use crate::tpl::{cache, render};
 use crate::udbc::driver::Driver;
 use crate::udbc::value::{ToValue, Value};
+use std::sync::RwLock;
+
+/// Process-wide defaults for the template engine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TemplateEngineConfig {
+    /// Parser config used by [`parse_template`] and by drivers without an explicit one.
+    pub parser: ParserConfig,
+    /// Fail rendering when a value doesn't fit the node using it (e.g. `<foreach>` over a
+    /// string) instead of logging a warning and skipping the node.
+    pub strict_mode: bool,
+}
+
+static ENGINE_CONFIG: RwLock<TemplateEngineConfig> = RwLock::new(TemplateEngineConfig {
+    parser: ParserConfig {
+        allow_colon_params: false,
+    },
+    strict_mode: false,
+});
+
+/// Replaces the global template engine config.
+///
+/// Templates are cached per parser config, so a new default parser config takes effect on next
+/// use without discarding the ASTs parsed under the old one.
+pub fn configure(config: TemplateEngineConfig) {
+    *ENGINE_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// Returns the current global template engine config.
+pub fn config() -> TemplateEngineConfig {
+    *ENGINE_CONFIG.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The parser config used when none is given explicitly.
+pub(crate) fn default_config() -> ParserConfig {
+    config().parser
+}
 
 /// Renders a SQL template by substituting parameters and returning the generated SQL
 /// along with the bound parameter values.
@@ -17,26 +55,217 @@ pub fn render_template<T: ToValue>(
     template_content: &str,
     param: &T,
     driver: &dyn Driver,
+    config: &ParserConfig,
 ) -> Result<(String, Vec<(String, Value)>)> {
+    let rendered = render_logged(
+        template_name,
+        template_content,
+        param,
+        None,
+        true,
+        driver,
+        config,
+    )?;
+    Ok((rendered.sql, rendered.params))
+}
+
+/// A statement rendered by [`render_logged`].
+pub(crate) struct Rendered {
+    pub sql: String,
+    pub params: Vec<(String, Value)>,
+    /// For each of `params`, whether it must be masked in logs.
+    pub sensitive: Vec<bool>,
+}
+
+/// [`render_template`] that also tells, for each bound parameter, whether `logged` (the
+/// arguments' [`ToValue::to_log_value`]) masks the value it was bound from. Without
+/// `logged`, nothing is reported as sensitive. `${...}` fails the render unless `allow_raw`.
+pub(crate) fn render_logged<T: ToValue>(
+    template_name: &str,
+    template_content: &str,
+    param: &T,
+    logged: Option<&Value>,
+    allow_raw: bool,
+    driver: &dyn Driver,
+    config: &ParserConfig,
+) -> Result<Rendered> {
     // Retrieve the abstract syntax tree (AST) for the template, using a cache to avoid re-parsing.
-    let ast = cache::get_ast(template_name, template_content);
+    let ast = cache::get_ast(template_name, template_content, config);
+    render_ast(
+        template_name,
+        &ast,
+        template_content.len(),
+        param,
+        logged,
+        allow_raw,
+        driver,
+    )
+}
+
+/// [`render_template`] that parses `template_content` on every call instead of caching its
+/// AST under `template_name`, so the `testing` assertions leave the global cache untouched.
+/// `<include>`s still resolve against the cache.
+#[doc(hidden)]
+pub fn render_uncached<T: ToValue>(
+    template_name: &str,
+    template_content: &str,
+    param: &T,
+    driver: &dyn Driver,
+    config: &ParserConfig,
+) -> Result<(String, Vec<(String, Value)>)> {
+    let ast = parse_template_with_config(template_content, config);
+    let rendered = render_ast(
+        template_name,
+        &ast,
+        template_content.len(),
+        param,
+        None,
+        true,
+        driver,
+    )?;
+    Ok((rendered.sql, rendered.params))
+}
 
-    // Convert the provided parameters into a generic Value type for SQL execution.
-    let value = param.to_value();
+fn render_ast<T: ToValue>(
+    template_name: &str,
+    ast: &[AstNode],
+    capacity: usize,
+    param: &T,
+    logged: Option<&Value>,
+    allow_raw: bool,
+    driver: &dyn Driver,
+) -> Result<Rendered> {
+    // Convert the provided parameters into a generic Value type for SQL execution; a `Value`
+    // argument is used as-is.
+    let value = param.as_value();
 
     // Initialize the render buffer with estimated capacity to minimize reallocations.
     let mut buf = RenderBuffer {
-        sql: String::with_capacity(template_content.len()),
+        sql: String::with_capacity(capacity),
         params: Vec::with_capacity(10),
         driver,
         param_count: 0,
+        strict: self::config().strict_mode,
+        sensitive: Vec::with_capacity(10),
+        allow_raw,
     };
 
     // Set up the rendering context and execute the rendering process.
     let mut ctx = Context::new(&value);
-    render::render(template_name, &ast, &mut ctx, &mut buf);
+    if let Some(logged) = logged {
+        ctx = ctx.with_log_value(logged);
+    }
+    render::render(template_name, ast, &mut ctx, &mut buf)?;
+
+    Ok(Rendered {
+        sql: buf.sql,
+        params: buf.params,
+        sensitive: buf.sensitive,
+    })
+}
+
+/// Renders a template without executing it (dry run), using the driver's parser config.
+///
+/// Returns the same SQL and bound parameters that would be sent to the database.
+pub fn render_only<T: ToValue>(
+    template_name: &str,
+    template_content: &str,
+    param: &T,
+    driver: &dyn Driver,
+) -> Result<(String, Vec<(String, Value)>)> {
+    render_template(
+        template_name,
+        template_content,
+        param,
+        driver,
+        &driver.parser_config(),
+    )
+}
+
+/// Returns the `refid`s of `<include>` tags in a template that don't resolve to a cached template.
+pub(crate) fn missing_includes(template_name: &str, template_content: &str) -> Vec<String> {
+    let ast = cache::get_ast(template_name, template_content, &default_config());
+    let mut missing = Vec::new();
+    collect_missing_includes(template_name, &ast, &mut missing);
+    missing
+}
+
+fn collect_missing_includes(template_name: &str, nodes: &[AstNode], missing: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            AstNode::Include { refid } => {
+                let resolved = render::resolve_include_key(template_name, refid)
+                    .is_some_and(|key| cache::TEMPLATE_CACHE.contains_key(&key));
+                if !resolved {
+                    missing.push(refid.clone());
+                }
+            }
+            AstNode::If {
+                body, else_body, ..
+            } => {
+                collect_missing_includes(template_name, body, missing);
+                collect_missing_includes(template_name, else_body, missing);
+            }
+            AstNode::Where { body }
+            | AstNode::Set { body }
+            | AstNode::Trim { body, .. }
+            | AstNode::Foreach { body, .. } => {
+                collect_missing_includes(template_name, body, missing);
+            }
+            AstNode::Choose {
+                branches,
+                otherwise,
+            } => {
+                for (_, body) in branches {
+                    collect_missing_includes(template_name, body, missing);
+                }
+                collect_missing_includes(template_name, otherwise, missing);
+            }
+            AstNode::Text(_) | AstNode::Var(_) | AstNode::RawVar(_) | AstNode::Bind { .. } => {}
+        }
+    }
+}
+
+/// Returns whether a template uses `${...}` raw interpolation anywhere.
+pub(crate) fn has_raw_vars(template_content: &str) -> bool {
+    any_node(&parse_template(template_content), &|node| {
+        matches!(node, AstNode::RawVar(_))
+    })
+}
+
+/// Whether the template binds `#{name}` anywhere in its own body (includes are not followed).
+pub(crate) fn binds_var(template_content: &str, name: &str) -> bool {
+    any_node(
+        &parse_template(template_content),
+        &|node| matches!(node, AstNode::Var(var) if var.trim() == name),
+    )
+}
 
-    Ok((buf.sql, buf.params))
+fn any_node(nodes: &[AstNode], pred: &dyn Fn(&AstNode) -> bool) -> bool {
+    nodes.iter().any(|node| {
+        pred(node)
+            || match node {
+                AstNode::If {
+                    body, else_body, ..
+                } => any_node(body, pred) || any_node(else_body, pred),
+                AstNode::Where { body }
+                | AstNode::Set { body }
+                | AstNode::Trim { body, .. }
+                | AstNode::Foreach { body, .. } => any_node(body, pred),
+                AstNode::Choose {
+                    branches,
+                    otherwise,
+                } => {
+                    branches.iter().any(|(_, body)| any_node(body, pred))
+                        || any_node(otherwise, pred)
+                }
+                AstNode::Text(_)
+                | AstNode::Var(_)
+                | AstNode::RawVar(_)
+                | AstNode::Include { .. }
+                | AstNode::Bind { .. } => false,
+            }
+    })
 }
 
 // pub fn remove_template(template_name: &str) {
@@ -46,8 +275,11 @@ pub fn render_template<T: ToValue>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::DbError;
     use crate::tpl::cache;
     use crate::udbc::connection::Connection;
+    use crate::udbc::driver::PlaceholderStyle;
+    use crate::udbc::value::REDACTED;
     use async_trait::async_trait;
 
     struct TestDriver;
@@ -75,13 +307,130 @@ mod tests {
         }
     }
 
+    struct NamedAtDriver;
+
+    #[async_trait]
+    impl Driver for NamedAtDriver {
+        fn name(&self) -> &str {
+            "named"
+        }
+
+        fn r#type(&self) -> &str {
+            "named"
+        }
+
+        fn placeholder(&self, _param_seq: usize, _param_name: &str) -> String {
+            unreachable!("named drivers do not use positional placeholders")
+        }
+
+        fn placeholder_style(&self) -> PlaceholderStyle {
+            PlaceholderStyle::NamedAt
+        }
+
+        async fn acquire(&self) -> Result<Box<dyn Connection>> {
+            Err(crate::error::DbError::DbError("not supported".to_string()))
+        }
+
+        async fn close(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Login;
+
+    impl ToValue for Login {
+        fn to_value(&self) -> Value {
+            Value::Map(std::collections::HashMap::from([
+                ("name".to_string(), Value::Str("hunter2".to_string())),
+                ("password".to_string(), Value::Str("hunter2".to_string())),
+                (
+                    "pins".to_string(),
+                    Value::List(vec![Value::I64(1), Value::I64(2)]),
+                ),
+            ]))
+        }
+
+        fn to_log_value(&self) -> Value {
+            Value::Map(std::collections::HashMap::from([
+                ("name".to_string(), Value::Str("hunter2".to_string())),
+                ("password".to_string(), Value::Str(REDACTED.to_string())),
+                ("pins".to_string(), Value::Str(REDACTED.to_string())),
+            ]))
+        }
+    }
+
     #[test]
-    fn include_is_resolved_by_current_namespace_first() {
-        cache::TEMPLATE_CACHE.clear();
+    fn render_logged_marks_params_bound_from_sensitive_fields() {
+        let tpl = "SELECT * FROM users WHERE name = #{name} AND password = #{password}\
+            <bind name=\"salted\" value=\"'s:' + password\"/> AND salted = #{salted}\
+            <foreach item=\"pin\" collection=\"pins\" open=\" AND pin IN (\" separator=\",\" close=\")\">#{pin}</foreach>";
+        let logged = Login.to_log_value();
+        let rendered = render_logged(
+            "logged.test",
+            tpl,
+            &Login,
+            Some(&logged),
+            false,
+            &TestDriver,
+            &ParserConfig::default(),
+        )
+        .unwrap();
+        // `name` has the same value as `password` but isn't masked.
+        assert_eq!(rendered.params.len(), 5);
+        assert_eq!(rendered.sensitive, vec![false, true, true, true, true]);
+
+        let rendered = render_logged(
+            "logged.test",
+            tpl,
+            &Login,
+            None,
+            false,
+            &TestDriver,
+            &ParserConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(rendered.sensitive, vec![false; 5]);
+    }
+
+    #[test]
+    fn named_placeholders_bind_each_name_once() {
+        let mut args = std::collections::HashMap::new();
+        args.insert("id".to_string(), Value::I64(7));
+        args.insert(
+            "ids".to_string(),
+            Value::List(vec![Value::I64(1), Value::I64(2)]),
+        );
+
+        let (sql, params) = render_template(
+            "named.test",
+            "SELECT * FROM t WHERE id = #{id} OR parent = #{id} OR id IN \
+             <foreach item=\"x\" collection=\"ids\" open=\"(\" separator=\",\" close=\")\">#{x}</foreach>",
+            &args,
+            &NamedAtDriver,
+            &ParserConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE id = @id OR parent = @id OR id IN (@x,@x_2)"
+        );
+        assert_eq!(
+            params,
+            vec![
+                ("id".to_string(), Value::I64(7)),
+                ("x".to_string(), Value::I64(1)),
+                ("x_2".to_string(), Value::I64(2)),
+            ]
+        );
+    }
 
-        cache::get_ast("a.cols", "id, name");
-        cache::get_ast("b.cols", "id, email");
-        cache::get_ast("cols", "WRONG");
+    #[test]
+    fn include_is_resolved_by_current_namespace_first() {
+        // Not clearing the shared cache: other tests rely on their entries, and these are
+        // overwritten anyway.
+        cache::get_ast("a.cols", "id, name", &ParserConfig::default());
+        cache::get_ast("b.cols", "id, email", &ParserConfig::default());
+        cache::get_ast("cols", "WRONG", &ParserConfig::default());
 
         let driver = TestDriver;
         let (sql, _params) = render_template(
@@ -89,6 +438,7 @@ mod tests {
             "select <include refid=\"cols\"/> from t",
             &(),
             &driver,
+            &ParserConfig::default(),
         )
         .unwrap();
         assert!(sql.contains("id, name"));
@@ -99,6 +449,7 @@ mod tests {
             "select <include refid=\"cols\"/> from t",
             &(),
             &driver,
+            &ParserConfig::default(),
         )
         .unwrap();
         assert!(sql.contains("id, email"));
@@ -109,8 +460,455 @@ mod tests {
             "select <include refid=\"b.cols\"/> from t",
             &(),
             &driver,
+            &ParserConfig::default(),
         )
         .unwrap();
         assert!(sql.contains("id, email"));
     }
+
+    #[test]
+    fn raw_var_is_inlined_without_binding() {
+        let mut args = std::collections::HashMap::new();
+        args.insert("table".to_string(), Value::Str("users_01".to_string()));
+        args.insert("id".to_string(), Value::I64(7));
+
+        let driver = TestDriver;
+        let (sql, params) = render_template(
+            "raw.test",
+            "SELECT * FROM ${table} WHERE id = #{id}",
+            &args,
+            &driver,
+            &ParserConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(sql, "SELECT * FROM users_01 WHERE id = ?");
+        assert_eq!(params, vec![("id".to_string(), Value::I64(7))]);
+    }
+
+    #[test]
+    fn choose_renders_first_matching_branch() {
+        let tpl = "SELECT * FROM t WHERE <choose>\
+            <when test=\"id != null\">id = #{id}</when>\
+            <when test=\"name != null\">name = #{name}</when>\
+            <otherwise>1 = 0</otherwise>\
+            </choose>";
+        let driver = TestDriver;
+        let render_with = |args: &std::collections::HashMap<String, Value>| {
+            render_template("choose.test", tpl, args, &driver, &ParserConfig::default()).unwrap()
+        };
+
+        let mut args = std::collections::HashMap::new();
+        args.insert("id".to_string(), Value::I64(7));
+        args.insert("name".to_string(), Value::Str("a".to_string()));
+        let (sql, params) = render_with(&args);
+        assert_eq!(sql, "SELECT * FROM t WHERE id = ?");
+        assert_eq!(params, vec![("id".to_string(), Value::I64(7))]);
+
+        args.remove("id");
+        assert_eq!(render_with(&args).0, "SELECT * FROM t WHERE name = ?");
+
+        args.clear();
+        let (sql, params) = render_with(&args);
+        assert_eq!(sql, "SELECT * FROM t WHERE 1 = 0");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn where_drops_leading_connector_and_empty_clause() {
+        let tpl = "SELECT * FROM users <where>
+            <if test=\"name != null\">AND name = #{name}</if>
+            <if test=\"min_age != null\">and age >= #{min_age}</if>
+            <if test=\"status != null\">OR status = #{status}</if>
+        </where> ORDER BY id";
+        let driver = TestDriver;
+        let render_with = |args: &std::collections::HashMap<String, Value>| {
+            render_template("where.test", tpl, args, &driver, &ParserConfig::default()).unwrap()
+        };
+
+        let mut args = std::collections::HashMap::new();
+        let (sql, params) = render_with(&args);
+        assert_eq!(sql, "SELECT * FROM users  ORDER BY id");
+        assert!(params.is_empty());
+
+        args.insert("min_age".to_string(), Value::I64(18));
+        let (sql, params) = render_with(&args);
+        assert_eq!(sql, "SELECT * FROM users WHERE age >= ? ORDER BY id");
+        assert_eq!(params, vec![("min_age".to_string(), Value::I64(18))]);
+
+        args.insert("name".to_string(), Value::Str("a".to_string()));
+        args.insert("status".to_string(), Value::Str("active".to_string()));
+        let (sql, params) = render_with(&args);
+        assert!(
+            sql.starts_with("SELECT * FROM users WHERE name = ?"),
+            "{}",
+            sql
+        );
+        assert!(sql.ends_with("OR status = ? ORDER BY id"), "{}", sql);
+        assert_eq!(
+            params,
+            vec![
+                ("name".to_string(), Value::Str("a".to_string())),
+                ("min_age".to_string(), Value::I64(18)),
+                ("status".to_string(), Value::Str("active".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn set_drops_trailing_comma() {
+        let tpl = "UPDATE users <set>\
+            <if test=\"name != null\">name = #{name}, </if>\
+            <if test=\"age != null\">age = #{age},</if>\
+            </set> WHERE id = #{id}";
+        let driver = TestDriver;
+        let mut args = std::collections::HashMap::new();
+        args.insert("id".to_string(), Value::I64(7));
+        args.insert("name".to_string(), Value::Str("a".to_string()));
+        args.insert("age".to_string(), Value::I64(30));
+
+        let (sql, params) =
+            render_template("set.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "UPDATE users SET name = ?, age = ? WHERE id = ?");
+        assert_eq!(
+            params,
+            vec![
+                ("name".to_string(), Value::Str("a".to_string())),
+                ("age".to_string(), Value::I64(30)),
+                ("id".to_string(), Value::I64(7)),
+            ]
+        );
+
+        args.remove("age");
+        let (sql, _) =
+            render_template("set.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "UPDATE users SET name = ? WHERE id = ?");
+    }
+
+    #[test]
+    fn trim_strips_first_override_and_wraps() {
+        let tpl = "SELECT * FROM t WHERE deleted = 0 <trim prefix=\"AND (\" suffix=\")\" \
+            prefixOverrides=\"AND |OR \" suffixOverrides=\",\">\
+            <if test=\"a != null\">and or a = #{a}</if>\
+            <if test=\"b != null\"> OR b = #{b},</if>\
+            </trim>";
+        let driver = TestDriver;
+        let mut args = std::collections::HashMap::new();
+        args.insert("a".to_string(), Value::I64(1));
+        args.insert("b".to_string(), Value::I64(2));
+
+        let (sql, params) =
+            render_template("trim.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        // Only the first matching prefix is removed, whatever its case.
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE deleted = 0 AND ( or a = ? OR b = ? )"
+        );
+        assert_eq!(
+            params,
+            vec![
+                ("a".to_string(), Value::I64(1)),
+                ("b".to_string(), Value::I64(2)),
+            ]
+        );
+
+        args.remove("a");
+        let (sql, _) =
+            render_template("trim.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "SELECT * FROM t WHERE deleted = 0 AND ( b = ? )");
+
+        args.clear();
+        let (sql, _) =
+            render_template("trim.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "SELECT * FROM t WHERE deleted = 0 ");
+    }
+
+    #[test]
+    fn bind_computes_local_values() {
+        let tpl = "<bind name=\"pattern\" value=\"'%' + keyword + '%'\"/>\
+            SELECT * FROM users WHERE name LIKE #{pattern}\
+            <if test=\"tag != null\"><bind name=\"inner\" value=\"tag + '+'\"/> AND tag = #{inner}</if>\
+            <if test=\"inner != null\"> AND leaked = 1</if>";
+        let driver = TestDriver;
+        let mut args = std::collections::HashMap::new();
+        args.insert("keyword".to_string(), Value::Str("o".to_string()));
+        args.insert("tag".to_string(), Value::I64(3));
+
+        let (sql, params) =
+            render_template("bind.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        // `inner` is only visible inside the `<if>` that binds it.
+        assert_eq!(sql, "SELECT * FROM users WHERE name LIKE ? AND tag = ?");
+        assert_eq!(
+            params,
+            vec![
+                ("pattern".to_string(), Value::Str("%o%".to_string())),
+                ("inner".to_string(), Value::Str("3+".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn foreach_exposes_iteration_index() {
+        let user = |name: &str, tags: &[&str]| {
+            let mut m = std::collections::HashMap::new();
+            m.insert("name".to_string(), Value::Str(name.to_string()));
+            m.insert("tags".to_string(), tags.to_vec().to_value());
+            Value::Map(m)
+        };
+        let mut args = std::collections::HashMap::new();
+        args.insert(
+            "users".to_string(),
+            Value::List(vec![user("a", &["x", "y"]), user("b", &[])]),
+        );
+
+        // The inner loop shadows `i`; the outer `i` is visible again after it.
+        let tpl = "<foreach item=\"u\" index=\"i\" collection=\"users\" separator=\", \">\
+            (#{i}, #{u.name}<foreach item=\"t\" index=\"i\" collection=\"u.tags\" open=\" [\" close=\"]\">\
+            #{i}#{t}</foreach> #{i})</foreach>";
+        let driver = TestDriver;
+        let (sql, params) =
+            render_template("index.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "(?, ? [??,??] ?), (?, ? ?)");
+        let values: Vec<Value> = params.into_iter().map(|(_, v)| v).collect();
+        let s = |v: &str| Value::Str(v.to_string());
+        assert_eq!(
+            values,
+            vec![
+                Value::I64(0),
+                s("a"),
+                Value::I64(0),
+                s("x"),
+                Value::I64(1),
+                s("y"),
+                Value::I64(0),
+                Value::I64(1),
+                s("b"),
+                Value::I64(1),
+            ]
+        );
+
+        // Without `index`, the index is `<item>_index`.
+        let tpl = "<foreach item=\"u\" collection=\"users\">#{u_index}</foreach>";
+        let (_, params) = render_template(
+            "index.default",
+            tpl,
+            &args,
+            &driver,
+            &ParserConfig::default(),
+        )
+        .unwrap();
+        let values: Vec<Value> = params.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, vec![Value::I64(0), Value::I64(1)]);
+    }
+
+    #[test]
+    fn foreach_iterates_map_in_key_order() {
+        let mut columns = std::collections::HashMap::new();
+        columns.insert("name".to_string(), Value::Str("a".to_string()));
+        columns.insert("age".to_string(), Value::I64(30));
+        columns.insert("email".to_string(), Value::Null);
+        let mut args = std::collections::HashMap::new();
+        args.insert("columns".to_string(), Value::Map(columns));
+
+        let tpl = "INSERT INTO users (<foreach item=\"v\" index=\"col\" collection=\"columns\">\
+            ${col}</foreach>) VALUES (<foreach item=\"v\" collection=\"columns\">#{v}</foreach>)";
+        let driver = TestDriver;
+        let (sql, params) =
+            render_template("map.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "INSERT INTO users (age,email,name) VALUES (?,?,?)");
+        assert_eq!(
+            params,
+            vec![
+                ("v".to_string(), Value::I64(30)),
+                ("v".to_string(), Value::Null),
+                ("v".to_string(), Value::Str("a".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_var_rejects_non_identifier_values() {
+        let driver = TestDriver;
+        let tpl = "SELECT * FROM t ORDER BY ${column}";
+        let render_with = |column: &str| {
+            let mut args = std::collections::HashMap::new();
+            args.insert("column".to_string(), Value::Str(column.to_string()));
+            render_template("raw.check", tpl, &args, &driver, &ParserConfig::default())
+        };
+
+        assert_eq!(
+            render_with("u.created_at").unwrap().0,
+            "SELECT * FROM t ORDER BY u.created_at"
+        );
+        for bad in ["id; DROP TABLE t", "name DESC", "id--", ""] {
+            match render_with(bad) {
+                Err(DbError::TemplateEngineError(message)) => {
+                    assert!(message.contains("${column}"), "{}", message)
+                }
+                other => panic!(
+                    "expected TemplateEngineError for {:?}, got {:?}",
+                    bad, other
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn binds_var_finds_nested_placeholders() {
+        let sql = "UPDATE t SET a = 1 <where><if test=\"id != null\">id = #{id}</if> AND version = #{ version }</where>";
+        assert!(binds_var(sql, "version"));
+        assert!(binds_var(sql, "id"));
+        assert!(!binds_var("UPDATE t SET version = ${version}", "version"));
+    }
+
+    #[test]
+    fn raw_var_fails_without_a_text_value_or_opt_in() {
+        let driver = TestDriver;
+        let tpl = "SELECT * FROM t ORDER BY ${column}";
+        let mut args = std::collections::HashMap::new();
+        let render = |args: &std::collections::HashMap<String, Value>, allow_raw| {
+            render_logged(
+                "raw.value",
+                tpl,
+                args,
+                None,
+                allow_raw,
+                &driver,
+                &ParserConfig::default(),
+            )
+            .map(|rendered| rendered.sql)
+        };
+
+        assert!(matches!(
+            render(&args, true),
+            Err(DbError::TemplateEngineError(_))
+        ));
+        args.insert("column".to_string(), Value::List(vec![]));
+        assert!(matches!(
+            render(&args, true),
+            Err(DbError::TemplateEngineError(_))
+        ));
+        args.insert("column".to_string(), Value::Str("id".to_string()));
+        assert_eq!(render(&args, true).unwrap(), "SELECT * FROM t ORDER BY id");
+        assert!(matches!(
+            render(&args, false),
+            Err(DbError::TemplateEngineError(_))
+        ));
+    }
+
+    #[test]
+    fn if_else_renders_one_branch() {
+        let tpl = "SELECT * FROM t ORDER BY \
+            <if test=\"sort == 'name'\">name<else/>id</if>";
+        let driver = TestDriver;
+        let mut args = std::collections::HashMap::new();
+        let (sql, _) =
+            render_template("else.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "SELECT * FROM t ORDER BY id");
+
+        args.insert("sort".to_string(), Value::Str("name".to_string()));
+        let (sql, _) =
+            render_template("else.test", tpl, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(sql, "SELECT * FROM t ORDER BY name");
+    }
+
+    #[test]
+    fn colon_params_are_opt_in() {
+        let mut args = std::collections::HashMap::new();
+        args.insert("id".to_string(), Value::I64(7));
+
+        let driver = TestDriver;
+        let sql = "SELECT id::text FROM t WHERE id = :id";
+        let config = ParserConfig {
+            allow_colon_params: true,
+        };
+        let (rendered, params) = render_template("colon.on", sql, &args, &driver, &config).unwrap();
+        assert_eq!(rendered, "SELECT id::text FROM t WHERE id = ?");
+        assert_eq!(params, vec![("id".to_string(), Value::I64(7))]);
+
+        let (rendered, params) =
+            render_template("colon.off", sql, &args, &driver, &ParserConfig::default()).unwrap();
+        assert_eq!(rendered, sql);
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn parser_config_enables_colon_params() {
+        // Goes through an explicit config: `configure` would change the default for every
+        // test running in parallel.
+        let sql = "SELECT * FROM t WHERE id = :id";
+        let colon = ParserConfig {
+            allow_colon_params: true,
+        };
+        let nodes = crate::tpl::parser::parse_template_with_config(sql, &colon);
+        assert!(matches!(nodes.last(), Some(AstNode::Var(v)) if v == "id"));
+
+        let nodes = crate::tpl::parser::parse_template_with_config(sql, &ParserConfig::default());
+        assert!(matches!(nodes.last(), Some(AstNode::Text(t)) if t.ends_with(":id")));
+        assert_eq!(config(), TemplateEngineConfig::default());
+        assert_eq!(TestDriver.parser_config(), default_config());
+    }
+
+    #[test]
+    fn cache_keeps_one_ast_per_parser_config() {
+        let sql = "SELECT * FROM t WHERE id = :id";
+        let colon = ParserConfig {
+            allow_colon_params: true,
+        };
+        let plain = cache::get_ast("cache_cfg.by_id", sql, &ParserConfig::default());
+        let with_colon = cache::get_ast("cache_cfg.by_id", sql, &colon);
+        assert!(matches!(with_colon.last(), Some(AstNode::Var(v)) if v == "id"));
+
+        // Alternating configs reuse the cached ASTs instead of re-parsing.
+        let again = cache::get_ast("cache_cfg.by_id", sql, &ParserConfig::default());
+        assert!(std::sync::Arc::ptr_eq(&plain, &again));
+        assert!(std::sync::Arc::ptr_eq(
+            &with_colon,
+            &cache::get_ast("cache_cfg.by_id", sql, &colon)
+        ));
+
+        // Changed content drops the ASTs of the old content.
+        let changed = cache::get_ast("cache_cfg.by_id", "SELECT 1", &colon);
+        assert!(!std::sync::Arc::ptr_eq(&with_colon, &changed));
+        let plain_changed = cache::get_ast("cache_cfg.by_id", "SELECT 1", &ParserConfig::default());
+        assert!(matches!(plain_changed.as_slice(), [AstNode::Text(t)] if t == "SELECT 1"));
+    }
+
+    #[test]
+    fn strict_mode_rejects_mismatched_values() {
+        let mut args = std::collections::HashMap::new();
+        args.insert("ids".to_string(), Value::Str("1,2".to_string()));
+        let value = args.to_value();
+        let ast = parse_template(
+            "SELECT * FROM t WHERE 1 = 1<foreach item=\"id\" collection=\"ids\" open=\" AND id IN (\" separator=\",\" close=\")\">#{id}</foreach>",
+        );
+
+        let driver = TestDriver;
+        let render_with = |strict: bool| {
+            let mut buf = RenderBuffer {
+                sql: String::new(),
+                params: Vec::new(),
+                driver: &driver,
+                param_count: 0,
+                strict,
+                sensitive: Vec::new(),
+                allow_raw: true,
+            };
+            render::render("strict.test", &ast, &mut Context::new(&value), &mut buf)
+                .map(|_| buf.sql)
+        };
+
+        assert_eq!(render_with(false).unwrap(), "SELECT * FROM t WHERE 1 = 1");
+        match render_with(true) {
+            Err(DbError::TemplateError {
+                template_name,
+                message,
+                node_type,
+            }) => {
+                assert_eq!(template_name, "strict.test");
+                assert!(message.contains("'ids' is not a list"), "{}", message);
+                assert_eq!(node_type.as_deref(), Some("foreach"));
+            }
+            other => panic!("expected TemplateError, got {:?}", other),
+        }
+    }
 }
@@ -1,6 +1,6 @@
-mod ast;
+pub mod ast;
 pub(crate) mod cache;
-pub(crate) mod engine;
-mod parser;
+pub mod engine;
+pub mod parser;
 mod render;
 mod render_context;
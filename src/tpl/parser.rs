@@ -1,17 +1,51 @@
 use crate::tpl::ast::{AstNode, Expr, Op};
+use crate::tpl::engine::default_config;
 use crate::udbc::value::Value;
 use std::collections::HashMap;
 
+/// Optional template syntax, off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ParserConfig {
+    /// Treat `:name` as `#{name}`. A `:` next to another `:` (e.g. PostgreSQL `::int` casts)
+    /// or followed by a non-identifier character is left as text.
+    pub allow_colon_params: bool,
+}
+
 /// Represents a stack frame during template parsing to handle nested tags.
 ///
 /// When a start tag (like `<if>`) is encountered, a new `TagFrame` is pushed onto the stack.
 /// This allows the parser to keep track of the current tag's attributes and nesting level.
 enum TagFrame {
-    /// An `<if>` tag frame, storing the test expression.
-    If { test: Expr },
+    /// An `<if>` tag frame, storing the test expression and, once `<else/>` has been seen,
+    /// the nodes before it.
+    If {
+        test: Expr,
+        then_body: Option<Vec<AstNode>>,
+    },
+    /// A `<where>` tag frame.
+    Where,
+    /// A `<set>` tag frame.
+    Set,
+    /// A `<trim>` tag frame, storing its wrapping and override attributes.
+    Trim {
+        prefix: String,
+        suffix: String,
+        prefix_overrides: Vec<String>,
+        suffix_overrides: Vec<String>,
+    },
+    /// A `<choose>` tag frame, collecting the branches closed so far.
+    Choose {
+        branches: Vec<(Expr, Vec<AstNode>)>,
+        otherwise: Vec<AstNode>,
+    },
+    /// A `<when>` tag frame; only opened directly inside a `<choose>`.
+    When { test: Expr },
+    /// An `<otherwise>` tag frame; only opened directly inside a `<choose>`.
+    Otherwise,
     /// A `<foreach>` tag frame, storing the iteration details.
     Foreach {
         item: String,
+        index: String,
         collection: String,
         open: String,
         separator: String,
@@ -19,17 +53,40 @@ enum TagFrame {
     },
 }
 
+impl TagFrame {
+    /// The closing tag that ends this frame.
+    fn close_tag(&self) -> &'static str {
+        match self {
+            TagFrame::If { .. } => "</if>",
+            TagFrame::Where => "</where>",
+            TagFrame::Set => "</set>",
+            TagFrame::Trim { .. } => "</trim>",
+            TagFrame::Choose { .. } => "</choose>",
+            TagFrame::When { .. } => "</when>",
+            TagFrame::Otherwise => "</otherwise>",
+            TagFrame::Foreach { .. } => "</foreach>",
+        }
+    }
+}
+
 /// A hand-written recursive-descent style parser for the SQL template language.
 ///
 /// It supports:
 /// - Plain text (SQL)
 /// - Variable interpolation: `#{var}`
-/// - Conditional logic: `<if test="...">...</if>`
+/// - Conditional logic: `<if test="...">...</if>`, optionally split by `<else/>`
+/// - `WHERE` clauses: `<where>...</where>`
+/// - `SET` clauses: `<set>...</set>`
+/// - Custom clause wrappers: `<trim prefix="..." prefixOverrides="...">...</trim>`
+/// - Exclusive branches: `<choose><when test="...">...</when><otherwise>...</otherwise></choose>`
 /// - Iteration: `<foreach item="..." collection="..." ...>...</foreach>`
 /// - Template inclusion: `<include refid="..." />`
+/// - Local variables: `<bind name="..." value="'%' + keyword + '%'" />`
 ///
 /// The parser uses a stack-based approach to handle nested tags correctly.
 struct Parser<'a> {
+    /// Optional syntax enabled for this template.
+    config: ParserConfig,
     /// The original template string being parsed.
     template: &'a str,
     /// Current character position in the template.
@@ -43,8 +100,9 @@ struct Parser<'a> {
 
 impl<'a> Parser<'a> {
     /// Creates a new parser instance for the given template string.
-    fn new(template: &'a str) -> Self {
+    fn new(template: &'a str, config: &ParserConfig) -> Self {
         Self {
+            config: *config,
             template,
             pos: 0,
             nodes_stack: vec![Vec::new()], // Initialize with root level.
@@ -71,7 +129,8 @@ impl<'a> Parser<'a> {
         self.nodes_stack.pop().unwrap_or_default()
     }
 
-    /// Try to parse a tag: `<if>`, `</if>`, `<foreach>`, `</foreach>`, `<include>`.
+    /// Try to parse a tag: `<if>`, `<where>`, `<set>`, `<trim>`, `<choose>`, `<when>`, `<otherwise>`,
+    /// `<foreach>`, `<include>`, `<bind>`, `<else/>` or a closing tag.
     /// Returns true if a tag was successfully parsed and consumed.
     fn try_parse_tag(&mut self) -> bool {
         let remaining = &self.template[self.pos..];
@@ -82,12 +141,42 @@ impl<'a> Parser<'a> {
         if remaining.starts_with("<if ") {
             return self.handle_if_tag(remaining);
         }
+        if remaining.starts_with("<where>") {
+            return self.open_frame(TagFrame::Where, 7);
+        }
+        if remaining.starts_with("<set>") {
+            return self.open_frame(TagFrame::Set, 5);
+        }
+        if remaining.starts_with("<trim ") || remaining.starts_with("<trim>") {
+            return self.handle_trim_tag(remaining);
+        }
+        if remaining.starts_with("<choose>") {
+            return self.open_frame(
+                TagFrame::Choose {
+                    branches: Vec::new(),
+                    otherwise: Vec::new(),
+                },
+                8,
+            );
+        }
+        if remaining.starts_with("<when ") {
+            return self.handle_when_tag(remaining);
+        }
+        if remaining.starts_with("<otherwise>") {
+            return self.in_choose() && self.open_frame(TagFrame::Otherwise, 11);
+        }
         if remaining.starts_with("<foreach ") {
             return self.handle_foreach_tag(remaining);
         }
         if remaining.starts_with("<include") {
             return self.handle_include_tag(remaining);
         }
+        if remaining.starts_with("<bind ") {
+            return self.handle_bind_tag(remaining);
+        }
+        if remaining.starts_with("<else") {
+            return self.handle_else_tag(remaining);
+        }
 
         false
     }
@@ -100,7 +189,10 @@ impl<'a> Parser<'a> {
             if let Some(test_str) = attrs.get("test") {
                 let test = parse_expr(test_str);
                 self.nodes_stack.push(Vec::new());
-                self.tag_stack.push(TagFrame::If { test });
+                self.tag_stack.push(TagFrame::If {
+                    test,
+                    then_body: None,
+                });
                 self.pos += end_idx + 1;
                 return true;
             }
@@ -108,7 +200,89 @@ impl<'a> Parser<'a> {
         false
     }
 
-    /// Handle <foreach item="..." collection="...">
+    /// Handle `<else/>` directly inside an `<if>` with no `<else/>` yet: the nodes so far
+    /// become the `then` branch. Anywhere else the tag is left as text.
+    fn handle_else_tag(&mut self, remaining: &str) -> bool {
+        let len = if remaining.starts_with("<else/>") {
+            7
+        } else if remaining.starts_with("<else />") {
+            8
+        } else {
+            return false;
+        };
+        let Some(TagFrame::If { then_body, .. }) = self.tag_stack.last() else {
+            return false;
+        };
+        if then_body.is_some() {
+            return false;
+        }
+
+        let mut body = self.nodes_stack.pop().unwrap_or_default();
+        self.trim_text_nodes(&mut body);
+        if let Some(TagFrame::If { then_body, .. }) = self.tag_stack.last_mut() {
+            *then_body = Some(body);
+        }
+        self.nodes_stack.push(Vec::new());
+        self.pos += len;
+        true
+    }
+
+    /// Handle <trim prefix="..." suffix="..." prefixOverrides="..." suffixOverrides="...">.
+    /// All attributes are optional; overrides are `|`-separated tokens.
+    fn handle_trim_tag(&mut self, remaining: &str) -> bool {
+        if let Some(end_idx) = find_tag_end(remaining) {
+            let attrs = parse_attributes(&remaining[5..end_idx]); // Skip "<trim"
+            let attr = |name: &str| attrs.get(name).cloned().unwrap_or_default();
+            let overrides = |name: &str| {
+                attrs.get(name).map_or_else(Vec::new, |list| {
+                    list.split('|')
+                        .map(str::trim)
+                        .filter(|token| !token.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+            };
+            let frame = TagFrame::Trim {
+                prefix: attr("prefix"),
+                suffix: attr("suffix"),
+                prefix_overrides: overrides("prefixOverrides"),
+                suffix_overrides: overrides("suffixOverrides"),
+            };
+            return self.open_frame(frame, end_idx + 1);
+        }
+        false
+    }
+
+    /// Handle <when test="...">. Outside a `<choose>` the tag is left as text.
+    fn handle_when_tag(&mut self, remaining: &str) -> bool {
+        if !self.in_choose() {
+            return false;
+        }
+        if let Some(end_idx) = find_tag_end(remaining) {
+            let tag_content = &remaining[6..end_idx]; // Skip "<when "
+            let attrs = parse_attributes(tag_content);
+            if let Some(test_str) = attrs.get("test") {
+                let test = parse_expr(test_str);
+                return self.open_frame(TagFrame::When { test }, end_idx + 1);
+            }
+        }
+        false
+    }
+
+    /// Whether the innermost open tag is a `<choose>`.
+    fn in_choose(&self) -> bool {
+        matches!(self.tag_stack.last(), Some(TagFrame::Choose { .. }))
+    }
+
+    /// Push `frame` with a fresh body scope and consume its `len`-byte start tag.
+    fn open_frame(&mut self, frame: TagFrame, len: usize) -> bool {
+        self.nodes_stack.push(Vec::new());
+        self.tag_stack.push(frame);
+        self.pos += len;
+        true
+    }
+
+    /// Handle <foreach item="..." collection="..."> with an optional `index="..."`.
     fn handle_foreach_tag(&mut self, remaining: &str) -> bool {
         if let Some(end_idx) = find_tag_end(remaining) {
             let tag_content = &remaining[9..end_idx]; // Skip "<foreach "
@@ -117,10 +291,15 @@ impl<'a> Parser<'a> {
                 let open = attrs.get("open").map(|s| s.as_str()).unwrap_or("");
                 let separator = attrs.get("separator").map(|s| s.as_str()).unwrap_or(",");
                 let close = attrs.get("close").map(|s| s.as_str()).unwrap_or("");
+                let index = attrs
+                    .get("index")
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}_index", item));
 
                 self.nodes_stack.push(Vec::new());
                 self.tag_stack.push(TagFrame::Foreach {
                     item: item.to_string(),
+                    index,
                     collection: collection.to_string(),
                     open: open.to_string(),
                     separator: separator.to_string(),
@@ -149,43 +328,110 @@ impl<'a> Parser<'a> {
         false
     }
 
-    /// Handle closing tags `</if>` and `</foreach>`.
+    /// Handle <bind name="..." value="..." />
+    fn handle_bind_tag(&mut self, remaining: &str) -> bool {
+        if let Some(end_idx) = find_tag_end(remaining) {
+            let tag_content = &remaining[6..end_idx]; // Skip "<bind "
+            let attrs = parse_attributes(tag_content);
+            if let (Some(name), Some(value)) = (attrs.get("name"), attrs.get("value")) {
+                self.append_node(AstNode::Bind {
+                    name: name.to_string(),
+                    value: parse_concat(value),
+                });
+                self.pos += end_idx + 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Handle the closing tag of the innermost open tag. Any other closing tag is left as text.
     fn handle_close_tag(&mut self, remaining: &str) -> bool {
-        if remaining.starts_with("</if>")
-            && let Some(TagFrame::If { .. }) = self.tag_stack.last()
-            && let Some(TagFrame::If { test }) = self.tag_stack.pop()
-        {
-            let mut body = self.nodes_stack.pop().unwrap_or_default();
-            self.trim_text_nodes(&mut body);
+        let Some(close) = self.tag_stack.last().map(TagFrame::close_tag) else {
+            return false;
+        };
+        if !remaining.starts_with(close) {
+            return false;
+        }
+        if let Some(frame) = self.tag_stack.pop() {
+            self.close_frame(frame);
+        }
+        self.pos += close.len();
+        true
+    }
 
-            self.append_node(AstNode::If { test, body });
-            self.pos += 5;
-            return true;
-        } else if remaining.starts_with("</foreach>")
-            && let Some(TagFrame::Foreach { .. }) = self.tag_stack.last()
-            && let Some(TagFrame::Foreach {
+    /// Finish a popped frame: take its body and attach the resulting node to the enclosing
+    /// scope, or for `<when>`/`<otherwise>` to the enclosing `<choose>` frame.
+    fn close_frame(&mut self, frame: TagFrame) {
+        let mut body = self.nodes_stack.pop().unwrap_or_default();
+        self.trim_text_nodes(&mut body);
+
+        let node = match frame {
+            TagFrame::If {
+                test,
+                then_body: Some(then_body),
+            } => AstNode::If {
+                test,
+                body: then_body,
+                else_body: body,
+            },
+            TagFrame::If { test, .. } => AstNode::If {
+                test,
+                body,
+                else_body: Vec::new(),
+            },
+            TagFrame::Where => AstNode::Where { body },
+            TagFrame::Set => AstNode::Set { body },
+            TagFrame::Trim {
+                prefix,
+                suffix,
+                prefix_overrides,
+                suffix_overrides,
+            } => AstNode::Trim {
+                prefix,
+                suffix,
+                prefix_overrides,
+                suffix_overrides,
+                body,
+            },
+            // Text between the branches of a `<choose>` is dropped.
+            TagFrame::Choose {
+                branches,
+                otherwise,
+            } => AstNode::Choose {
+                branches,
+                otherwise,
+            },
+            TagFrame::When { test } => {
+                if let Some(TagFrame::Choose { branches, .. }) = self.tag_stack.last_mut() {
+                    branches.push((test, body));
+                }
+                return;
+            }
+            TagFrame::Otherwise => {
+                if let Some(TagFrame::Choose { otherwise, .. }) = self.tag_stack.last_mut() {
+                    *otherwise = body;
+                }
+                return;
+            }
+            TagFrame::Foreach {
                 item,
+                index,
                 collection,
                 open,
                 separator,
                 close,
-            }) = self.tag_stack.pop()
-        {
-            let mut body = self.nodes_stack.pop().unwrap_or_default();
-            self.trim_text_nodes(&mut body);
-
-            self.append_node(AstNode::Foreach {
+            } => AstNode::Foreach {
                 item,
+                index,
                 collection,
                 open,
                 separator,
                 close,
                 body,
-            });
-            self.pos += 10;
-            return true;
-        }
-        false
+            },
+        };
+        self.append_node(node);
     }
 
     fn trim_text_nodes(&self, nodes: &mut Vec<AstNode>) {
@@ -223,25 +469,63 @@ impl<'a> Parser<'a> {
     /// Try to parse a variable expression: `#{var}`.
     fn try_parse_var(&mut self) -> bool {
         let remaining = &self.template[self.pos..];
-        if remaining.starts_with("#{")
+        let raw = remaining.starts_with("${");
+        if (raw || remaining.starts_with("#{"))
             && let Some(end) = remaining.find('}')
         {
             let var_name = remaining[2..end].trim();
             if !var_name.is_empty() {
-                self.append_node(AstNode::Var(var_name.to_string()));
+                let node = if raw {
+                    AstNode::RawVar(var_name.to_string())
+                } else {
+                    AstNode::Var(var_name.to_string())
+                };
+                self.append_node(node);
                 self.pos += end + 1;
                 return true;
             }
         }
+        if self.config.allow_colon_params
+            && let Some(len) = self.colon_param_len()
+        {
+            let var_name = &remaining[1..len];
+            self.append_node(AstNode::Var(var_name.to_string()));
+            self.pos += len;
+            return true;
+        }
         false
     }
 
-    /// Consume text until the next special sequence (`'<'` or `"#{"`).
+    /// If a `:name` parameter starts at the current position, returns its length including
+    /// the colon.
+    fn colon_param_len(&self) -> Option<usize> {
+        let remaining = &self.template[self.pos..];
+        let rest = remaining.strip_prefix(':')?;
+        if self.template[..self.pos].ends_with(':') {
+            return None;
+        }
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+        let name_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        Some(name_len + 1)
+    }
+
+    /// Consume text until the next special sequence (`'<'`, `"#{"` or `"${"`).
     fn parse_text(&mut self) {
         let remaining = &self.template[self.pos..];
         let next_tag = remaining.find('<').unwrap_or(remaining.len());
         let next_var = remaining.find("#{").unwrap_or(remaining.len());
-        let next_stop = std::cmp::min(next_tag, next_var);
+        let next_raw = remaining.find("${").unwrap_or(remaining.len());
+        let mut next_stop = next_tag.min(next_var).min(next_raw);
+        if self.config.allow_colon_params {
+            // Stop before a potential `:name`; text ending in `:` keeps `::` casts intact.
+            if let Some(i) = remaining[..next_stop].find(':') {
+                next_stop = i.max(1).min(next_stop);
+            }
+        }
 
         if next_stop > 0 {
             self.append_text(&remaining[..next_stop]);
@@ -274,35 +558,22 @@ impl<'a> Parser<'a> {
 
     /// Auto-close any remaining unclosed tags at the end of the template.
     fn close_remaining_tags(&mut self) {
-        while let Some(tag) = self.tag_stack.pop() {
-            let mut body = self.nodes_stack.pop().unwrap_or_default();
-            self.trim_text_nodes(&mut body);
-
-            let node = match tag {
-                TagFrame::If { test } => AstNode::If { test, body },
-                TagFrame::Foreach {
-                    item,
-                    collection,
-                    open,
-                    separator,
-                    close,
-                } => AstNode::Foreach {
-                    item,
-                    collection,
-                    open,
-                    separator,
-                    close,
-                    body,
-                },
-            };
-            self.append_node(node);
+        while let Some(frame) = self.tag_stack.pop() {
+            self.close_frame(frame);
         }
     }
 }
 
-/// Main entry point: parse a template string into an AST.
+/// Main entry point: parse a template string into an AST using the global default config.
+///
+/// See [`crate::tpl::engine::configure`].
 pub fn parse_template(template: &str) -> Vec<AstNode> {
-    Parser::new(template).parse()
+    parse_template_with_config(template, &default_config())
+}
+
+/// Parses a template string into an AST with the given optional syntax enabled.
+pub fn parse_template_with_config(template: &str, config: &ParserConfig) -> Vec<AstNode> {
+    Parser::new(template, config).parse()
 }
 
 /// Find the index of the closing `>` for a tag, ignoring quoted content.
@@ -397,6 +668,11 @@ fn parse_and_expr(input: &str) -> Expr {
 
 fn parse_atom(input: &str) -> Expr {
     let input = input.trim();
+
+    if let Some(expr) = parse_in(input) {
+        return expr;
+    }
+
     // Check operators. Order matters (longest first).
     let ops = [
         ("!=", Op::Ne),
@@ -417,6 +693,79 @@ fn parse_atom(input: &str) -> Expr {
     parse_val(input)
 }
 
+/// Parse `var in (a, b)` or `var not in (a, b)`.
+fn parse_in(input: &str) -> Option<Expr> {
+    // Only an ` in ` outside quotes and brackets is the operator, e.g. not the one in
+    // `m['a in b'] in (1)` or `x in ('not in')`.
+    let at = find_top_level(input, " in ")?;
+    let (left, negated) = match input[..at].strip_suffix(" not") {
+        Some(left) => (left, true),
+        None => (&input[..at], false),
+    };
+    let right = &input[at + " in ".len()..];
+
+    let list = right.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let values = split_unquoted(list, ',')
+        .into_iter()
+        .filter(|v| !v.trim().is_empty())
+        .map(parse_val)
+        .collect();
+
+    Some(Expr::In {
+        var: Box::new(parse_val(left)),
+        values,
+        negated,
+    })
+}
+
+/// Parse a `<bind>` value: `a + 'text' + b` becomes [`Expr::Concat`], anything else a
+/// single value.
+fn parse_concat(input: &str) -> Expr {
+    let parts = split_unquoted(input, '+');
+    if parts.len() == 1 {
+        return parse_val(input);
+    }
+    Expr::Concat(parts.into_iter().map(parse_val).collect())
+}
+
+/// Split on `sep`, ignoring separators inside quoted literals.
+fn split_unquoted(input: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, c) if c == sep => {
+                parts.push(&input[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// Byte offset of the first `needle` outside quoted literals and `(...)` / `[...]`.
+fn find_top_level(input: &str, needle: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (Some(_), _) => {}
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth = depth.saturating_sub(1),
+            (None, _) if depth == 0 && input[i..].starts_with(needle) => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
 fn parse_val(input: &str) -> Expr {
     let s = input.trim();
     if s == "null" {
@@ -492,7 +841,7 @@ mod tests {
         let nodes = parse_template(tpl);
         assert_eq!(nodes.len(), 1);
         match &nodes[0] {
-            AstNode::If { test, body } => {
+            AstNode::If { test, body, .. } => {
                 match test {
                     Expr::Binary(Op::Gt, left, right) => {
                         assert_eq!(**left, Expr::Var("a".to_string()));
@@ -510,6 +859,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_if_else() {
+        let tpl = r#"<if test="a"><if test="b">x<else/>y</if><else />z</if>"#;
+        let nodes = parse_template(tpl);
+        assert_eq!(nodes.len(), 1);
+        match &nodes[0] {
+            AstNode::If {
+                body, else_body, ..
+            } => {
+                assert!(matches!(&else_body[..], [AstNode::Text(t)] if t == "z"));
+                match &body[..] {
+                    [
+                        AstNode::If {
+                            body, else_body, ..
+                        },
+                    ] => {
+                        assert!(matches!(&body[..], [AstNode::Text(t)] if t == "x"));
+                        assert!(matches!(&else_body[..], [AstNode::Text(t)] if t == "y"));
+                    }
+                    other => panic!("Expected nested If, got {:?}", other),
+                }
+            }
+            other => panic!("Expected If, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stray_else_is_text() {
+        let nodes = parse_template("a<else/>b");
+        assert!(matches!(&nodes[..], [AstNode::Text(t)] if t == "a<else/>b"));
+
+        // Inside another tag nested in the `<if>`, and a second `<else/>`.
+        let tpl = r#"<if test="x"><foreach item="i" collection="l"><else/></foreach>1<else/>2<else/>3</if>"#;
+        match &parse_template(tpl)[0] {
+            AstNode::If {
+                body, else_body, ..
+            } => {
+                match &body[..] {
+                    [AstNode::Foreach { body, .. }, AstNode::Text(t)] => {
+                        assert!(matches!(&body[..], [AstNode::Text(e)] if e == "<else/>"));
+                        assert_eq!(t, "1");
+                    }
+                    other => panic!("Expected Foreach and Text, got {:?}", other),
+                }
+                assert!(matches!(&else_body[..], [AstNode::Text(t)] if t == "2<else/>3"));
+            }
+            other => panic!("Expected If, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_nested() {
         let tpl = r#"<if test="x"><foreach item="i" collection="list">#{i}</foreach></if>"#;
@@ -536,7 +935,7 @@ mod tests {
         let nodes = parse_template(tpl);
         assert_eq!(nodes.len(), 1);
         match &nodes[0] {
-            AstNode::If { test, body } => {
+            AstNode::If { test, body, .. } => {
                 match test {
                     Expr::Var(v) => assert_eq!(v, "x"),
                     _ => panic!("Expected Var"),
@@ -567,4 +966,177 @@ mod tests {
             _ => panic!("Expected If"),
         }
     }
+
+    #[test]
+    fn test_parse_choose() {
+        let tpl = r#"<choose>
+            <when test="a == 1">one</when>
+            <when test="b">two</when>
+            <otherwise>other</otherwise>
+        </choose>"#;
+        let nodes = parse_template(tpl);
+        assert_eq!(nodes.len(), 1);
+        match &nodes[0] {
+            AstNode::Choose {
+                branches,
+                otherwise,
+            } => {
+                assert_eq!(branches.len(), 2);
+                assert_eq!(branches[1].0, Expr::Var("b".to_string()));
+                assert!(matches!(&branches[0].1[..], [AstNode::Text(t)] if t == "one"));
+                assert!(matches!(&otherwise[..], [AstNode::Text(t)] if t == "other"));
+            }
+            other => panic!("Expected Choose, got {:?}", other),
+        }
+
+        // Empty, missing `<otherwise>` and unclosed.
+        let nodes = parse_template(r#"<choose></choose><choose><when test="x">a"#);
+        assert_eq!(nodes.len(), 2);
+        match (&nodes[0], &nodes[1]) {
+            (
+                AstNode::Choose {
+                    branches: empty, ..
+                },
+                AstNode::Choose {
+                    branches,
+                    otherwise,
+                },
+            ) => {
+                assert!(empty.is_empty());
+                assert_eq!(branches.len(), 1);
+                assert!(otherwise.is_empty());
+            }
+            other => panic!("Expected two Choose nodes, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_when_outside_choose_is_text() {
+        let tpl = r#"<when test="x">a</when><otherwise>b</otherwise>"#;
+        let nodes = parse_template(tpl);
+        assert_eq!(nodes.len(), 1);
+        match &nodes[0] {
+            AstNode::Text(t) => assert_eq!(t, tpl),
+            other => panic!("Expected Text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_in() {
+        let expr = parse_expr("status in ('active', 'a,b', 1, other)");
+        match expr {
+            Expr::In {
+                var,
+                values,
+                negated,
+            } => {
+                assert_eq!(*var, Expr::Var("status".to_string()));
+                assert_eq!(
+                    values,
+                    vec![
+                        Expr::Literal(Value::Str("active".to_string())),
+                        Expr::Literal(Value::Str("a,b".to_string())),
+                        Expr::Literal(Value::I64(1)),
+                        Expr::Var("other".to_string()),
+                    ]
+                );
+                assert!(!negated);
+            }
+            _ => panic!("Expected In, got {:?}", expr),
+        }
+
+        match parse_expr("status not in ('x') and a == 1") {
+            Expr::Binary(Op::And, left, _) => match *left {
+                Expr::In { negated, .. } => assert!(negated),
+                other => panic!("Expected In, got {:?}", other),
+            },
+            other => panic!("Expected And, got {:?}", other),
+        }
+
+        // ` in ` inside quotes or brackets is not the operator.
+        match parse_expr("x in ('a not in b', 'c in d')") {
+            Expr::In {
+                var,
+                values,
+                negated,
+            } => {
+                assert_eq!(*var, Expr::Var("x".to_string()));
+                assert_eq!(
+                    values,
+                    vec![
+                        Expr::Literal(Value::Str("a not in b".to_string())),
+                        Expr::Literal(Value::Str("c in d".to_string())),
+                    ]
+                );
+                assert!(!negated);
+            }
+            other => panic!("Expected In, got {:?}", other),
+        }
+        match parse_expr("m['a in b'] not in (1)") {
+            Expr::In { var, negated, .. } => {
+                assert_eq!(*var, parse_val("m['a in b']"));
+                assert!(negated);
+            }
+            other => panic!("Expected In, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind() {
+        let nodes = parse_template(r#"<bind name="p" value="'%' + q + '+%'"/>#{p}"#);
+        assert_eq!(nodes.len(), 2);
+        match &nodes[0] {
+            AstNode::Bind { name, value } => {
+                assert_eq!(name, "p");
+                assert_eq!(
+                    *value,
+                    Expr::Concat(vec![
+                        Expr::Literal(Value::Str("%".to_string())),
+                        Expr::Var("q".to_string()),
+                        Expr::Literal(Value::Str("+%".to_string())),
+                    ])
+                );
+            }
+            other => panic!("Expected Bind, got {:?}", other),
+        }
+
+        match &parse_template(r#"<bind name="p" value="q"/>"#)[0] {
+            AstNode::Bind { value, .. } => assert_eq!(*value, Expr::Var("q".to_string())),
+            other => panic!("Expected Bind, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_raw_var() {
+        let nodes = parse_template("SELECT * FROM ${table} WHERE id = #{id}");
+        assert_eq!(nodes.len(), 4);
+        match &nodes[1] {
+            AstNode::RawVar(v) => assert_eq!(v, "table"),
+            other => panic!("Expected RawVar, got {:?}", other),
+        }
+        match &nodes[3] {
+            AstNode::Var(v) => assert_eq!(v, "id"),
+            other => panic!("Expected Var, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_colon_params() {
+        let config = ParserConfig {
+            allow_colon_params: true,
+        };
+        let nodes = parse_template_with_config("a = :name AND b::int = :b_2, c = ':'", &config);
+        let rendered: Vec<String> = nodes
+            .iter()
+            .map(|n| match n {
+                AstNode::Text(t) => t.clone(),
+                AstNode::Var(v) => format!("#{{{}}}", v),
+                other => panic!("Unexpected node {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            rendered,
+            vec!["a = ", "#{name}", " AND b::int = ", "#{b_2}", ", c = ':'"]
+        );
+    }
 }
@@ -1,17 +1,81 @@
+use crate::Result;
+use crate::error::DbError;
 use crate::tpl::ast::{AstNode, Expr, Op};
 use crate::tpl::cache::TEMPLATE_CACHE;
-use crate::tpl::render_context::Context;
-use crate::udbc::driver::Driver;
+use crate::tpl::render_context::{Context, Scoped};
+use crate::udbc::driver::{Driver, PlaceholderStyle};
 use crate::udbc::value::Value;
+use std::borrow::Cow;
 
 pub struct RenderBuffer<'a> {
     pub sql: String,
     pub params: Vec<(String, Value)>,
     pub driver: &'a dyn Driver,
     pub param_count: usize,
+    /// Whether [`RenderBuffer::mismatch`] fails the render instead of logging a warning.
+    pub strict: bool,
+    /// For each entry of `params`, whether it was bound from a sensitive value (see
+    /// [`Context::is_sensitive`]) and must be masked in logs.
+    pub sensitive: Vec<bool>,
+    /// Whether `${...}` may be rendered; templates that aren't allowed it fail instead.
+    pub allow_raw: bool,
 }
 
 impl<'a> RenderBuffer<'a> {
+    /// Binds `value` for the parameter `name` and writes its placeholder.
+    fn bind(&mut self, name: &str, value: &Value, sensitive: bool) {
+        let prefix = match self.driver.placeholder_style() {
+            PlaceholderStyle::Positional => {
+                self.params.push((name.to_string(), value.clone()));
+                self.sensitive.push(sensitive);
+                self.param_count += 1;
+                let placeholder = self.driver.placeholder(self.param_count, name);
+                self.sql.push_str(&placeholder);
+                return;
+            }
+            PlaceholderStyle::NamedColon => ':',
+            PlaceholderStyle::NamedAt => '@',
+        };
+
+        let base: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let mut key = base.clone();
+        let mut suffix = 1;
+        loop {
+            match self.params.iter().find(|(k, _)| *k == key) {
+                Some((_, bound)) if bound == value => break,
+                Some(_) => {
+                    suffix += 1;
+                    key = format!("{}_{}", base, suffix);
+                }
+                None => {
+                    self.params.push((key.clone(), value.clone()));
+                    self.sensitive.push(sensitive);
+                    self.param_count += 1;
+                    break;
+                }
+            }
+        }
+        self.sql.push(prefix);
+        self.sql.push_str(&key);
+    }
+
+    /// Reports a node that can't be rendered with the values at hand: an error in strict
+    /// mode, otherwise a warning after which the caller skips the node.
+    fn mismatch(&self, template_name: &str, node_type: &str, message: String) -> Result<()> {
+        if self.strict {
+            return Err(DbError::TemplateError {
+                template_name: template_name.to_string(),
+                message,
+                node_type: Some(node_type.to_string()),
+            });
+        }
+        log::warn!("Template '{}' <{}>: {}", template_name, node_type, message);
+        Ok(())
+    }
+
     fn push_sql(&mut self, s: &str) {
         let s_starts_with_newline = s.starts_with('\n') || s.starts_with("\r\n");
 
@@ -37,6 +101,73 @@ impl<'a> RenderBuffer<'a> {
     }
 }
 
+/// Connectors removed from the start of a `<where>` body.
+const WHERE_PREFIXES: &[&str] = &["AND", "OR"];
+/// Separators removed from the end of a `<set>` body.
+const SET_SUFFIXES: &[&str] = &[","];
+
+/// Trims `body`, then removes the first matching token of `prefixes` from its start and of
+/// `suffixes` from its end, ignoring ASCII case.
+///
+/// A token made only of letters must be separated from the rest by whitespace, so `OR` does
+/// not eat the start of `ORDER`.
+fn trim_clause<'s, S: AsRef<str>>(body: &'s str, prefixes: &[S], suffixes: &[S]) -> &'s str {
+    let is_word = |token: &str| token.chars().all(|c| c.is_ascii_alphabetic());
+    let mut body = body.trim();
+    if let Some(token) = prefixes.iter().map(AsRef::as_ref).find(|token| {
+        body.get(..token.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(token))
+            && (!is_word(token) || body[token.len()..].starts_with(char::is_whitespace))
+    }) {
+        body = body[token.len()..].trim_start();
+    }
+    if let Some(token) = suffixes.iter().map(AsRef::as_ref).find(|token| {
+        body.len().checked_sub(token.len()).is_some_and(|split| {
+            body.get(split..)
+                .is_some_and(|tail| tail.eq_ignore_ascii_case(token))
+                && (!is_word(token) || body[..split].ends_with(char::is_whitespace))
+        })
+    }) {
+        body = body[..body.len() - token.len()].trim_end();
+    }
+    body
+}
+
+/// Renders `body` into a scratch string, trims it with [`trim_clause`] and writes
+/// `<prefix> <clause> <suffix>`, or nothing if the clause is empty.
+///
+/// Parameters are still bound into `buf` in order, so positional placeholders stay aligned.
+fn render_clause<S: AsRef<str>>(
+    template_name: &str,
+    body: &[AstNode],
+    (prefix, suffix): (&str, &str),
+    (prefix_overrides, suffix_overrides): (&[S], &[S]),
+    ctx: &mut Context,
+    buf: &mut RenderBuffer,
+) -> Result<()> {
+    let outer = std::mem::take(&mut buf.sql);
+    let result = render(template_name, body, ctx, buf);
+    let rendered = std::mem::replace(&mut buf.sql, outer);
+    result?;
+
+    let clause = trim_clause(&rendered, prefix_overrides, suffix_overrides);
+    if clause.is_empty() {
+        return Ok(());
+    }
+    if prefix.is_empty() {
+        buf.push_sql(clause);
+    } else {
+        buf.push_sql(prefix);
+        buf.sql.push(' ');
+        buf.sql.push_str(clause);
+    }
+    if !suffix.is_empty() {
+        buf.sql.push(' ');
+        buf.sql.push_str(suffix);
+    }
+    Ok(())
+}
+
 fn to_f64(v: &Value) -> Option<f64> {
     match v {
         Value::I16(n) => Some(*n as f64),
@@ -56,7 +187,54 @@ fn resolve_val(expr: &Expr, ctx: &Context) -> Value {
     match expr {
         Expr::Literal(v) => v.clone(),
         Expr::Var(name) => ctx.lookup(name).clone(),
-        Expr::Binary(..) => Value::Bool(eval_expr(expr, ctx)),
+        Expr::Concat(parts) => concat(parts, ctx).map_or(Value::Null, Value::Str),
+        Expr::Binary(..) | Expr::In { .. } => Value::Bool(eval_expr(expr, ctx)),
+    }
+}
+
+/// Whether a `<bind>` value reads a sensitive variable, so the local must be masked too.
+fn derives_from_sensitive(expr: &Expr, ctx: &Context) -> bool {
+    match expr {
+        Expr::Var(name) => ctx.is_sensitive(name),
+        Expr::Concat(parts) => parts.iter().any(|part| derives_from_sensitive(part, ctx)),
+        Expr::Literal(_) | Expr::Binary(..) | Expr::In { .. } => false,
+    }
+}
+
+/// Joins the parts of a `+` expression as text; `null` parts add nothing. Fails with the
+/// first value that has no text form.
+fn concat(parts: &[Expr], ctx: &Context) -> std::result::Result<String, Value> {
+    let mut text = String::new();
+    for part in parts {
+        match resolve_val(part, ctx) {
+            Value::Null => {}
+            v => match v.text_form() {
+                Some(s) => text.push_str(&s),
+                None => return Err(v),
+            },
+        }
+    }
+    Ok(text)
+}
+
+/// Equality used by `==`, `!=` and `in`: numbers compare by value regardless of width,
+/// and enum values compare equal to string literals with the same name.
+///
+/// `null` checks are JavaScript-style, not SQL `NULL` semantics: a missing key and a `None`
+/// field are both `null`, `null == null` is true, and `null` never equals a non-null value,
+/// so `x != null` is true whenever `x` has a value.
+fn values_equal(l: &Value, r: &Value) -> bool {
+    match (l, r) {
+        (Value::Null, Value::Null) => return true,
+        (Value::Null, _) | (_, Value::Null) => return false,
+        _ => {}
+    }
+    if let (Some(l), Some(r)) = (to_f64(l), to_f64(r)) {
+        return (l - r).abs() < f64::EPSILON;
+    }
+    match (l, r) {
+        (Value::Enum(a), Value::Str(b)) | (Value::Str(a), Value::Enum(b)) => a == b,
+        _ => l == r,
     }
 }
 
@@ -70,26 +248,23 @@ pub fn eval_expr(expr: &Expr, ctx: &Context) -> bool {
                 return eval_expr(left, ctx) || eval_expr(right, ctx);
             }
 
-            let l_val = resolve_val(left, ctx);
-            let r_val = resolve_val(right, ctx);
+            // Comparing two variables (e.g. `a > b`) resolves both in one lookup, by reference.
+            let (l_val, r_val) = match (left.as_ref(), right.as_ref()) {
+                (Expr::Var(l), Expr::Var(r)) => {
+                    let values = ctx.lookup_many(&[l, r]);
+                    (Cow::Borrowed(values[0]), Cow::Borrowed(values[1]))
+                }
+                _ => (
+                    Cow::Owned(resolve_val(left, ctx)),
+                    Cow::Owned(resolve_val(right, ctx)),
+                ),
+            };
             let l_f64 = to_f64(&l_val);
             let r_f64 = to_f64(&r_val);
 
             match op {
-                Op::Eq => {
-                    if let (Some(l), Some(r)) = (l_f64, r_f64) {
-                        (l - r).abs() < f64::EPSILON
-                    } else {
-                        l_val == r_val
-                    }
-                }
-                Op::Ne => {
-                    if let (Some(l), Some(r)) = (l_f64, r_f64) {
-                        (l - r).abs() > f64::EPSILON
-                    } else {
-                        l_val != r_val
-                    }
-                }
+                Op::Eq => values_equal(&l_val, &r_val),
+                Op::Ne => !values_equal(&l_val, &r_val),
                 Op::Gt => l_f64.zip(r_f64).is_some_and(|(l, r)| l > r),
                 Op::Ge => l_f64.zip(r_f64).is_some_and(|(l, r)| l >= r),
                 Op::Lt => l_f64.zip(r_f64).is_some_and(|(l, r)| l < r),
@@ -97,12 +272,68 @@ pub fn eval_expr(expr: &Expr, ctx: &Context) -> bool {
                 _ => false,
             }
         }
+        Expr::In {
+            var,
+            values,
+            negated,
+        } => {
+            let var_value = resolve_val(var, ctx);
+            let found = values
+                .iter()
+                .any(|v| values_equal(&resolve_val(v, ctx), &var_value));
+            found != *negated
+        }
         Expr::Literal(v) => is_truthy(v),
         Expr::Var(name) => is_truthy(ctx.lookup(name)),
+        Expr::Concat(_) => is_truthy(&resolve_val(expr, ctx)),
     }
 }
 
-fn resolve_include_key(current_template_name: &str, refid: &str) -> Option<String> {
+/// Allow-list for `${...}` values, which are written into the SQL verbatim: only
+/// identifier characters (`[A-Za-z0-9_.]`) may pass, so a value can name a table or column
+/// but can't inject SQL.
+fn validate_raw_value(template_name: &str, name: &str, raw: &str) -> Result<()> {
+    if !raw.is_empty()
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        return Ok(());
+    }
+    Err(DbError::TemplateEngineError(format!(
+        "Template '{}': value of ${{{}}} must be non-empty and only contain [A-Za-z0-9_.], got {:?}",
+        template_name, name, raw
+    )))
+}
+
+/// The `(index, item)` pairs a `<foreach>` iterates: list positions with their items, or map
+/// entries sorted by key so the generated SQL is stable. `None` if `collection` is neither
+/// (a `null` collection has no entries).
+fn foreach_entries(collection: &Value) -> Option<Vec<(Value, &Value)>> {
+    match collection {
+        Value::Null => Some(Vec::new()),
+        Value::List(items) => Some(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (Value::I64(i as i64), v))
+                .collect(),
+        ),
+        Value::Map(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            Some(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (Value::Str(k.clone()), v))
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn resolve_include_key(current_template_name: &str, refid: &str) -> Option<String> {
     if refid.contains('.') {
         return Some(refid.to_string());
     }
@@ -126,59 +357,175 @@ pub(crate) fn render(
     nodes: &[AstNode],
     ctx: &mut Context,
     buf: &mut RenderBuffer,
-) {
+) -> Result<()> {
+    // `<bind>` values stay visible until the end of the enclosing block.
+    let depth = ctx.depth();
+    let result = render_nodes(template_name, nodes, ctx, buf);
+    ctx.truncate(depth);
+    result
+}
+
+fn render_nodes(
+    template_name: &str,
+    nodes: &[AstNode],
+    ctx: &mut Context,
+    buf: &mut RenderBuffer,
+) -> Result<()> {
     for node in nodes {
         match node {
             AstNode::Text(t) => buf.push_sql(t),
             AstNode::Var(name) => {
                 let v = ctx.lookup(name);
-                buf.params.push((name.clone(), v.clone()));
-                buf.param_count += 1;
-                buf.sql
-                    .push_str(&buf.driver.placeholder(buf.param_count, name));
+                buf.bind(name, v, ctx.is_sensitive(name));
+            }
+            AstNode::RawVar(name) => {
+                if !buf.allow_raw {
+                    return Err(DbError::TemplateEngineError(format!(
+                        "Template '{}' uses ${{{}}}, but raw interpolation is not enabled for it",
+                        template_name, name
+                    )));
+                }
+                let raw = match ctx.lookup(name) {
+                    Value::Null => {
+                        return Err(DbError::TemplateEngineError(format!(
+                            "Template '{}': ${{{}}} has no value",
+                            template_name, name
+                        )));
+                    }
+                    v => match v.text_form() {
+                        Some(s) => s,
+                        None => {
+                            return Err(DbError::TemplateEngineError(format!(
+                                "Template '{}': ${{{}}} can't be inlined as text: {:?}",
+                                template_name, name, v
+                            )));
+                        }
+                    },
+                };
+                validate_raw_value(template_name, name, &raw)?;
+                buf.push_sql(&raw);
+            }
+            AstNode::Bind {
+                name,
+                value: value_expr,
+            } => {
+                let value = match value_expr {
+                    Expr::Concat(parts) => concat(parts, ctx).map(Value::Str).or_else(|v| {
+                        let message = format!("'{}' can't be concatenated as text: {:?}", name, v);
+                        buf.mismatch(template_name, "bind", message)
+                            .map(|_| Value::Null)
+                    })?,
+                    other => resolve_val(other, ctx),
+                };
+                let sensitive = derives_from_sensitive(value_expr, ctx);
+                ctx.push_owned(name, value, sensitive);
             }
             AstNode::Include { refid } => {
-                if let Some(key) = resolve_include_key(template_name, refid)
-                    && let Some(cached) = TEMPLATE_CACHE.get(&key)
+                match resolve_include_key(template_name, refid)
+                    .and_then(|key| TEMPLATE_CACHE.get(&key).map(|cached| (key, cached)))
                 {
-                    render(&key, &cached.ast, ctx, buf);
+                    Some((key, cached)) => render(&key, &cached.ast, ctx, buf)?,
+                    None => {
+                        let message = format!("refid '{}' not found", refid);
+                        buf.mismatch(template_name, "include", message)?;
+                    }
                 }
             }
-            AstNode::If { test, body } => {
-                if eval_expr(test, ctx) {
-                    render(template_name, body, ctx, buf);
-                }
+            AstNode::If {
+                test,
+                body,
+                else_body,
+            } => {
+                let branch = if eval_expr(test, ctx) {
+                    body
+                } else {
+                    else_body
+                };
+                render(template_name, branch, ctx, buf)?;
+            }
+            AstNode::Where { body } => {
+                let overrides = (WHERE_PREFIXES, &[][..]);
+                render_clause(template_name, body, ("WHERE", ""), overrides, ctx, buf)?;
+            }
+            AstNode::Set { body } => {
+                let overrides = (&[][..], SET_SUFFIXES);
+                render_clause(template_name, body, ("SET", ""), overrides, ctx, buf)?;
+            }
+            AstNode::Trim {
+                prefix,
+                suffix,
+                prefix_overrides,
+                suffix_overrides,
+                body,
+            } => {
+                let overrides = (&prefix_overrides[..], &suffix_overrides[..]);
+                render_clause(template_name, body, (prefix, suffix), overrides, ctx, buf)?;
+            }
+            AstNode::Choose {
+                branches,
+                otherwise,
+            } => {
+                let body = branches
+                    .iter()
+                    .find(|(test, _)| eval_expr(test, ctx))
+                    .map_or(otherwise, |(_, body)| body);
+                render(template_name, body, ctx, buf)?;
             }
             AstNode::Foreach {
                 item,
+                index,
                 collection,
                 open,
                 separator,
                 close,
                 body,
             } => {
-                let arr = match ctx.lookup(collection) {
-                    Value::List(v) => v,
-                    _ => continue,
+                // Items from the render arguments are borrowed; `<bind>` values are copied.
+                let entries: Option<Vec<(Value, Cow<Value>)>> = match ctx.resolve(collection) {
+                    None => continue,
+                    Some(Scoped::Args(v)) => foreach_entries(v)
+                        .map(|e| e.into_iter().map(|(k, v)| (k, Cow::Borrowed(v))).collect()),
+                    Some(Scoped::Bound(v)) => foreach_entries(v).map(|e| {
+                        e.into_iter()
+                            .map(|(k, v)| (k, Cow::Owned(v.clone())))
+                            .collect()
+                    }),
                 };
-                if arr.is_empty() {
+                let Some(entries) = entries else {
+                    let message = format!(
+                        "collection '{}' is not a list or map: {:?}",
+                        collection,
+                        ctx.lookup(collection)
+                    );
+                    buf.mismatch(template_name, "foreach", message)?;
+                    continue;
+                };
+                if entries.is_empty() {
                     continue;
                 }
+                let sensitive = ctx.is_sensitive(collection);
 
                 buf.sql.push_str(open);
-                for (i, v) in arr.iter().enumerate() {
+                for (i, (key, v)) in entries.into_iter().enumerate() {
                     if i > 0 {
                         buf.sql.push_str(separator);
                     }
 
-                    ctx.push(item, v);
-                    render(template_name, body, ctx, buf);
+                    ctx.push_owned(index, key, false);
+                    match v {
+                        Cow::Borrowed(v) => ctx.push(item, v),
+                        Cow::Owned(v) => ctx.push_owned(item, v, sensitive),
+                    }
+                    let rendered = render(template_name, body, ctx, buf);
                     ctx.pop();
+                    ctx.pop();
+                    rendered?;
                 }
                 buf.sql.push_str(close);
             }
         }
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -213,5 +560,97 @@ mod tests {
         // b
         let expr = Expr::Var("b".to_string());
         assert!(eval_expr(&expr, &ctx));
+
+        // a > c, a != b: both sides are variables
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), Value::I64(10));
+        map.insert("c".to_string(), Value::I32(3));
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+        let vars = |op, l: &str, r: &str| {
+            Expr::Binary(
+                op,
+                Box::new(Expr::Var(l.to_string())),
+                Box::new(Expr::Var(r.to_string())),
+            )
+        };
+        assert!(eval_expr(&vars(Op::Gt, "a", "c"), &ctx));
+        assert!(!eval_expr(&vars(Op::Le, "a", "c"), &ctx));
+        assert!(eval_expr(&vars(Op::Ne, "a", "c"), &ctx));
+        assert!(eval_expr(&vars(Op::Eq, "missing", "other"), &ctx));
+    }
+
+    #[test]
+    fn test_eval_in() {
+        let mut map = HashMap::new();
+        map.insert("status".to_string(), Value::Str("pending".to_string()));
+        map.insert("level".to_string(), Value::I32(2));
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+
+        let in_list = |var: &str, values: Vec<Value>, negated| Expr::In {
+            var: Box::new(Expr::Var(var.to_string())),
+            values: values.into_iter().map(Expr::Literal).collect(),
+            negated,
+        };
+
+        let statuses = vec![
+            Value::Str("active".to_string()),
+            Value::Str("pending".to_string()),
+        ];
+        assert!(eval_expr(&in_list("status", statuses.clone(), false), &ctx));
+        assert!(!eval_expr(&in_list("status", statuses, true), &ctx));
+
+        // Numbers compare by value across integer widths.
+        assert!(eval_expr(
+            &in_list("level", vec![Value::I64(1), Value::I64(2)], false),
+            &ctx
+        ));
+        assert!(!eval_expr(
+            &in_list("missing", vec![Value::I64(1)], false),
+            &ctx
+        ));
+    }
+
+    #[test]
+    fn test_eval_enum_equals_string() {
+        let mut map = HashMap::new();
+        map.insert("status".to_string(), Value::Enum("active".to_string()));
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+
+        let expr = Expr::Binary(
+            Op::Eq,
+            Box::new(Expr::Var("status".to_string())),
+            Box::new(Expr::Literal(Value::Str("active".to_string()))),
+        );
+        assert!(eval_expr(&expr, &ctx));
+    }
+
+    #[test]
+    fn test_eval_null_checks() {
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), Value::Str("x".to_string()));
+        map.insert("nick".to_string(), Value::Null);
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+
+        let cmp = |op, var: &str| {
+            Expr::Binary(
+                op,
+                Box::new(Expr::Var(var.to_string())),
+                Box::new(Expr::Literal(Value::Null)),
+            )
+        };
+
+        assert!(!eval_expr(&cmp(Op::Eq, "name"), &ctx));
+        assert!(eval_expr(&cmp(Op::Ne, "name"), &ctx));
+        // A `None` field and a missing key behave the same.
+        for var in ["nick", "missing"] {
+            assert!(eval_expr(&cmp(Op::Eq, var), &ctx));
+            assert!(!eval_expr(&cmp(Op::Ne, var), &ctx));
+        }
+        // Ordering against null is always false.
+        assert!(!eval_expr(&cmp(Op::Gt, "name"), &ctx));
     }
 }
@@ -1,8 +1,44 @@
-use crate::udbc::value::Value;
+use crate::udbc::value::{REDACTED, Value};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 pub struct Context<'a> {
     root: &'a Value,
-    locals: Vec<(String, &'a Value)>,
+    /// Loop items borrow from the render arguments; `<bind>` values are owned.
+    locals: Vec<Local<'a>>,
+    /// Values in `root` that are masked in its log representation; see
+    /// [`Context::with_log_value`].
+    sensitive: HashSet<*const Value>,
+}
+
+struct Local<'a> {
+    name: String,
+    value: Cow<'a, Value>,
+    /// Whether an owned value was computed from a sensitive one.
+    sensitive: bool,
+}
+
+/// Where a resolved value lives: in the render arguments (valid for the whole render) or in
+/// a `<bind>` local owned by the [`Context`].
+pub enum Scoped<'a, 's> {
+    Args(&'a Value),
+    Bound(&'s Value),
+}
+
+impl<'a: 's, 's> Scoped<'a, 's> {
+    pub fn value(&self) -> &'s Value {
+        match *self {
+            Scoped::Args(v) => v,
+            Scoped::Bound(v) => v,
+        }
+    }
+
+    fn then_path(self, path: &str) -> Option<Self> {
+        match self {
+            Scoped::Args(v) => resolve_path(v, path).map(Scoped::Args),
+            Scoped::Bound(v) => resolve_path(v, path).map(Scoped::Bound),
+        }
+    }
 }
 
 impl<'a> Context<'a> {
@@ -10,38 +46,106 @@ impl<'a> Context<'a> {
         Self {
             root,
             locals: Vec::new(),
+            sensitive: HashSet::new(),
         }
     }
 
+    /// Tracks which parts of the arguments `logged` (their [`ToValue::to_log_value`]) masks
+    /// as [`REDACTED`], so [`Context::is_sensitive`] can tell parameters bound from them.
+    ///
+    /// [`ToValue::to_log_value`]: crate::udbc::value::ToValue::to_log_value
+    pub fn with_log_value(mut self, logged: &Value) -> Self {
+        collect_sensitive(self.root, logged, &mut self.sensitive);
+        self
+    }
+
     pub fn push(&mut self, key: &str, value: &'a Value) {
-        self.locals.push((key.to_string(), value));
+        self.push_value(key, Cow::Borrowed(value), false);
+    }
+
+    /// Pushes a value computed during rendering, e.g. by `<bind>`; `sensitive` marks one
+    /// derived from a sensitive value.
+    pub fn push_owned(&mut self, key: &str, value: Value, sensitive: bool) {
+        self.push_value(key, Cow::Owned(value), sensitive);
+    }
+
+    fn push_value(&mut self, key: &str, value: Cow<'a, Value>, sensitive: bool) {
+        self.locals.push(Local {
+            name: key.to_string(),
+            value,
+            sensitive,
+        });
     }
 
     pub fn pop(&mut self) {
         self.locals.pop();
     }
 
-    pub fn lookup(&self, key: &str) -> &'a Value {
+    /// The number of locals in scope, for restoring with [`Context::truncate`].
+    pub fn depth(&self) -> usize {
+        self.locals.len()
+    }
+
+    /// Drops the locals pushed since [`Context::depth`] returned `depth`.
+    pub fn truncate(&mut self, depth: usize) {
+        self.locals.truncate(depth);
+    }
+
+    pub fn lookup(&self, key: &str) -> &Value {
+        self.resolve(key).map_or(&Value::Null, |v| v.value())
+    }
+
+    /// Resolves several keys at once, in order, with the same rules as [`Context::lookup`].
+    pub fn lookup_many(&self, keys: &[&str]) -> Vec<&Value> {
+        keys.iter().map(|key| self.lookup(key)).collect()
+    }
+
+    /// Copies every variable in scope into `target`: the root object's entries, then the
+    /// locals from outermost to innermost, so shadowed names end up with the visible value.
+    #[allow(dead_code)] // Not needed by the renderer itself yet.
+    pub fn lookup_all_into<'s>(&'s self, target: &mut HashMap<&'s str, &'s Value>) {
+        if let Value::Map(m) = self.root {
+            target.extend(m.iter().map(|(k, v)| (k.as_str(), v)));
+        }
+        target.extend(
+            self.locals
+                .iter()
+                .map(|local| (local.name.as_str(), local.value.as_ref())),
+        );
+    }
+
+    /// Like [`Context::lookup`], but tells whether the value borrows from the render
+    /// arguments or from a `<bind>` local. Returns `None` for a missing key.
+    pub fn resolve(&self, key: &str) -> Option<Scoped<'a, '_>> {
         // 1) Try an exact match (locals or a direct key on the root object).
         if let Some(v) = self.get_from_scope(key) {
-            return v;
+            return Some(v);
         }
 
-        // 2) Try dotted-path lookup (e.g. "user.name").
-        if let Some((head, rest)) = key.split_once('.') {
-            // Resolve the first segment.
-            if let Some(head_value) = self.get_from_scope(head) {
-                // Then resolve the remaining path.
-                if let Some(target) = Self::resolve_path(head_value, rest) {
-                    return target;
-                }
-            }
-        }
+        // 2) Try dotted-path lookup (e.g. "user.name"): resolve the first segment, then the
+        // remaining path.
+        let (head, rest) = key.split_once('.')?;
+        self.get_from_scope(head)?.then_path(rest)
+    }
 
-        &Value::Null
+    /// Whether `key` resolves to (part of) a value that is masked in logs, either directly
+    /// in the render arguments or through a local computed from one.
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        if self.sensitive.is_empty() {
+            return false;
+        }
+        let head = key.split_once('.').map(|(head, _)| head);
+        let flagged = |name: &str| self.find_local(name).is_some_and(|local| local.sensitive);
+        if flagged(key) || head.is_some_and(flagged) {
+            return true;
+        }
+        match self.resolve(key) {
+            Some(Scoped::Args(v)) => self.sensitive.contains(&(v as *const Value)),
+            _ => false,
+        }
     }
 
-    fn get_from_scope(&self, key: &str) -> Option<&'a Value> {
+    fn get_from_scope(&self, key: &str) -> Option<Scoped<'a, '_>> {
         // 1. Try exact match
         if let Some(v) = self.find_exact(key) {
             return Some(v);
@@ -56,43 +160,92 @@ impl<'a> Context<'a> {
     }
 
     /// Helper to find a value by exact key match in locals or root
-    fn find_exact(&self, key: &str) -> Option<&'a Value> {
+    fn find_exact(&self, key: &str) -> Option<Scoped<'a, '_>> {
         // 1. Prioritize local variables (Stack structure, search backwards to support shadowing)
-        if let Some((_, v)) = self.locals.iter().rev().find(|(k, _)| k == key) {
-            return Some(v);
+        if let Some(local) = self.locals.iter().rev().find(|local| local.name == key) {
+            return Some(match &local.value {
+                Cow::Borrowed(v) => Scoped::Args(v),
+                Cow::Owned(v) => Scoped::Bound(v),
+            });
         }
 
         // 2. Search root object
         if let Value::Map(m) = self.root {
-            return m.get(key);
+            return m.get(key).map(Scoped::Args);
         }
 
         None
     }
 
-    /// Resolve a dot-separated path within a `Value` (maps only).
-    fn resolve_path(mut current: &'a Value, path: &str) -> Option<&'a Value> {
-        for part in path.split('.') {
-            match current {
-                Value::Map(m) => {
-                    if let Some(v) = m.get(part) {
+    /// The innermost local named `key` (or its snake_case form).
+    fn find_local(&self, key: &str) -> Option<&Local<'a>> {
+        let find = |key: &str| self.locals.iter().rev().find(|local| local.name == key);
+        find(key).or_else(|| find(&to_snake_case(key)?))
+    }
+}
+
+/// Walks `real` and `logged` side by side, collecting every value in `real` (nested ones
+/// included) that `logged` replaces with [`REDACTED`].
+fn collect_sensitive(real: &Value, logged: &Value, out: &mut HashSet<*const Value>) {
+    match (real, logged) {
+        (Value::Map(r), Value::Map(l)) => {
+            for (k, rv) in r {
+                if let Some(lv) = l.get(k) {
+                    collect_sensitive(rv, lv, out);
+                }
+            }
+        }
+        (Value::List(r), Value::List(l)) => {
+            for (rv, lv) in r.iter().zip(l) {
+                collect_sensitive(rv, lv, out);
+            }
+        }
+        (real, Value::Str(s))
+            if s == REDACTED && !matches!(real, Value::Str(r) if r == REDACTED) =>
+        {
+            collect_all(real, out);
+        }
+        _ => {}
+    }
+}
+
+fn collect_all(v: &Value, out: &mut HashSet<*const Value>) {
+    out.insert(v);
+    match v {
+        Value::Map(m) => m.values().for_each(|v| collect_all(v, out)),
+        Value::List(l) => l.iter().for_each(|v| collect_all(v, out)),
+        _ => {}
+    }
+}
+
+/// Resolve a dot-separated path within a `Value`.
+///
+/// Map segments are looked up by key; numeric segments index into lists
+/// (`users.0.name`). Missing keys and out-of-bounds indices resolve to `None`.
+fn resolve_path<'v>(mut current: &'v Value, path: &str) -> Option<&'v Value> {
+    for part in path.split('.') {
+        match current {
+            Value::Map(m) => {
+                if let Some(v) = m.get(part) {
+                    current = v;
+                } else if let Some(snake_part) = to_snake_case(part) {
+                    // Try snake_case fallback
+                    if let Some(v) = m.get(&snake_part) {
                         current = v;
-                    } else if let Some(snake_part) = to_snake_case(part) {
-                        // Try snake_case fallback
-                        if let Some(v) = m.get(&snake_part) {
-                            current = v;
-                        } else {
-                            return None;
-                        }
                     } else {
                         return None;
                     }
+                } else {
+                    return None;
                 }
-                _ => return None,
             }
+            Value::List(list) => {
+                current = list.get(part.parse::<usize>().ok()?)?;
+            }
+            _ => return None,
         }
-        Some(current)
     }
+    Some(current)
 }
 
 /// Converts a camelCase string to snake_case.
@@ -119,7 +272,6 @@ fn to_snake_case(s: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
 
     #[test]
     fn test_lookup_simple() {
@@ -161,6 +313,26 @@ mod tests {
         assert_eq!(ctx.lookup("a"), &Value::I64(1));
     }
 
+    #[test]
+    fn test_push_owned() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), Value::I64(1));
+        let root = Value::Map(map);
+        let mut ctx = Context::new(&root);
+
+        let depth = ctx.depth();
+        let mut bound = HashMap::new();
+        bound.insert("b".to_string(), Value::I64(2));
+        ctx.push_owned("x", Value::Map(bound), false);
+        assert_eq!(ctx.lookup("x.b"), &Value::I64(2));
+        assert!(matches!(ctx.resolve("x.b"), Some(Scoped::Bound(_))));
+        assert!(matches!(ctx.resolve("a"), Some(Scoped::Args(_))));
+        assert!(ctx.resolve("x.c").is_none());
+
+        ctx.truncate(depth);
+        assert_eq!(ctx.lookup("x.b"), &Value::Null);
+    }
+
     #[test]
     fn test_lookup_exact_match_with_dot() {
         let mut map = HashMap::new();
@@ -202,4 +374,55 @@ mod tests {
             &Value::Str("John".to_string())
         );
     }
+
+    #[test]
+    fn test_lookup_list_index() {
+        let mut user = HashMap::new();
+        user.insert("name".to_string(), Value::Str("Alice".to_string()));
+
+        let mut map = HashMap::new();
+        map.insert(
+            "users".to_string(),
+            Value::List(vec![Value::Map(user), Value::I64(7)]),
+        );
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+
+        assert_eq!(ctx.lookup("users.0.name"), &Value::Str("Alice".to_string()));
+        assert_eq!(ctx.lookup("users.1"), &Value::I64(7));
+        assert_eq!(ctx.lookup("users.2"), &Value::Null);
+        assert_eq!(ctx.lookup("users.-1"), &Value::Null);
+        assert_eq!(ctx.lookup("users.x"), &Value::Null);
+    }
+
+    #[test]
+    fn test_lookup_many_and_all() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), Value::I64(1));
+        map.insert("user_id".to_string(), Value::I64(9));
+        let root = Value::Map(map);
+        let item = Value::Bool(true);
+        let mut ctx = Context::new(&root);
+        ctx.push("a", &Value::I64(2));
+        ctx.push("item", &item);
+        ctx.push_owned("label", Value::Str("x".to_string()), false);
+
+        assert_eq!(
+            ctx.lookup_many(&["a", "userId", "label", "missing"]),
+            vec![
+                &Value::I64(2),
+                &Value::I64(9),
+                &Value::Str("x".to_string()),
+                &Value::Null
+            ]
+        );
+
+        let mut all = HashMap::new();
+        ctx.lookup_all_into(&mut all);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all["a"], &Value::I64(2));
+        assert_eq!(all["user_id"], &Value::I64(9));
+        assert_eq!(all["item"], &Value::Bool(true));
+        assert_eq!(all["label"], &Value::Str("x".to_string()));
+    }
 }
@@ -3,6 +3,14 @@ use crate::udbc::value::Value;
 use async_trait::async_trait;
 use std::collections::HashMap;
 
+/// The rows of a query together with its column names in select-list order, which the
+/// rows' maps don't keep.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rows {
+    pub columns: Vec<String>,
+    pub rows: Vec<HashMap<String, Value>>,
+}
+
 /// An abstract database connection trait that defines the basic operations
 /// for interacting with a database.
 #[async_trait]
@@ -21,6 +29,43 @@ pub trait Connection: Send {
         args: &[(String, Value)],
     ) -> Result<Vec<HashMap<String, Value>>>;
 
+    /// [`Connection::query`] that also returns the column names in select-list order, so
+    /// rows can be mapped positionally (e.g. to tuples).
+    ///
+    /// The default returns no column names; drivers should override it.
+    async fn query_with_columns(&mut self, sql: &str, args: &[(String, Value)]) -> Result<Rows> {
+        Ok(Rows {
+            columns: Vec::new(),
+            rows: self.query(sql, args).await?,
+        })
+    }
+
+    /// Execute a query statement and return only its first row, if any.
+    ///
+    /// The default runs [`Connection::query`] and drops the remaining rows; drivers should
+    /// override it to stop reading after the first row.
+    async fn query_one(
+        &mut self,
+        sql: &str,
+        args: &[(String, Value)],
+    ) -> Result<Option<HashMap<String, Value>>> {
+        Ok(self.query(sql, args).await?.into_iter().next())
+    }
+
+    /// [`Connection::query_one`] that also returns the column names in select-list order.
+    ///
+    /// The returned [`Rows`] holds at most one row. The default runs
+    /// [`Connection::query_with_columns`] and drops the remaining rows.
+    async fn query_one_with_columns(
+        &mut self,
+        sql: &str,
+        args: &[(String, Value)],
+    ) -> Result<Rows> {
+        let mut rows = self.query_with_columns(sql, args).await?;
+        rows.rows.truncate(1);
+        Ok(rows)
+    }
+
     /// Execute a non-query statement (INSERT, UPDATE, DELETE) and return the number of affected rows.
     ///
     /// # Arguments
@@ -0,0 +1,572 @@
+use crate::error::DbError;
+use crate::udbc::value::Value;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::collections::HashMap;
+
+/// Maps a raw database row to any type implementing `serde::Deserialize`.
+///
+/// The row is exposed to serde as a map, so both regular structs and structs composed
+/// with `#[serde(flatten)]` can be deserialized from a single row:
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct UserWithRole {
+///     #[serde(flatten)]
+///     user: User,
+///     #[serde(flatten)]
+///     role: Role,
+/// }
+///
+/// let v: UserWithRole = RowDeserializer::new(row).deserialize()?;
+/// ```
+pub struct RowDeserializer {
+    row: HashMap<String, Value>,
+    transform: CaseTransform,
+}
+
+/// Column-name transformation applied when a struct field has no exactly matching column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaseTransform {
+    /// Column names must match field names exactly.
+    #[default]
+    Identity,
+    /// Fields are `snake_case`, columns are camelCase (`first_name` -> `FirstName` / `firstName`).
+    SnakeToUpperCamel,
+    /// Fields are camelCase, columns are `snake_case` (`firstName` -> `first_name`).
+    CamelToSnake,
+}
+
+impl CaseTransform {
+    /// Returns the candidate column names for the given field name, in lookup order.
+    fn candidates(&self, field: &str) -> Vec<String> {
+        match self {
+            CaseTransform::Identity => Vec::new(),
+            CaseTransform::SnakeToUpperCamel => {
+                let upper = snake_to_upper_camel(field);
+                let mut chars = upper.chars();
+                let lower = match chars.next() {
+                    Some(c0) => c0.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                };
+                vec![upper, lower]
+            }
+            CaseTransform::CamelToSnake => vec![camel_to_snake(field)],
+        }
+    }
+
+    /// Maps a column name back to the field naming convention.
+    ///
+    /// Used when the target fields are unknown (e.g. `#[serde(flatten)]`).
+    fn column_to_field(&self, column: String) -> String {
+        match self {
+            CaseTransform::Identity => column,
+            CaseTransform::SnakeToUpperCamel => camel_to_snake(&column),
+            CaseTransform::CamelToSnake => snake_to_camel(&column),
+        }
+    }
+}
+
+impl RowDeserializer {
+    pub fn new(row: HashMap<String, Value>) -> Self {
+        Self {
+            row,
+            transform: CaseTransform::Identity,
+        }
+    }
+
+    /// Sets the column-name transformation used for fields missing from the row.
+    pub fn with_transform(mut self, transform: CaseTransform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Consumes the deserializer and produces a `T` from the row.
+    pub fn deserialize<T: DeserializeOwned>(self) -> Result<T, DbError> {
+        T::deserialize(self)
+    }
+}
+
+/// Convenience wrapper around [`RowDeserializer`].
+pub fn from_row<T: DeserializeOwned>(row: HashMap<String, Value>) -> Result<T, DbError> {
+    RowDeserializer::new(row).deserialize()
+}
+
+impl<'de> de::Deserializer<'de> for RowDeserializer {
+    type Error = DbError;
+
+    // `#[serde(flatten)]` buffers all entries through `deserialize_any`, so the row must
+    // always be presented as a map here.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        let row = match self.transform {
+            CaseTransform::Identity => self.row,
+            transform => self
+                .row
+                .into_iter()
+                .map(|(k, v)| (transform.column_to_field(k), v))
+                .collect(),
+        };
+        visitor.visit_map(MapDeserializer::new(row))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DbError> {
+        if self.transform != CaseTransform::Identity {
+            for field in fields {
+                if self.row.contains_key(*field) {
+                    continue;
+                }
+                for candidate in self.transform.candidates(field) {
+                    if let Some(v) = self.row.remove(&candidate) {
+                        self.row.insert(field.to_string(), v);
+                        break;
+                    }
+                }
+            }
+        }
+        visitor.visit_map(MapDeserializer::new(self.row))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DbError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map enum
+        identifier ignored_any
+    }
+}
+
+/// Deserializer for a single column (or nested) `Value`.
+pub struct ValueDeserializer {
+    value: Value,
+}
+
+impl ValueDeserializer {
+    pub fn new(value: Value) -> Self {
+        Self { value }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DbError> for Value {
+    type Deserializer = ValueDeserializer;
+
+    fn into_deserializer(self) -> ValueDeserializer {
+        ValueDeserializer::new(self)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = DbError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Char(c) => visitor.visit_char(c),
+            Value::Str(s) | Value::Enum(s) => visitor.visit_string(s),
+            Value::I8(n) => visitor.visit_i8(n),
+            Value::I16(n) => visitor.visit_i16(n),
+            Value::I32(n) => visitor.visit_i32(n),
+            Value::I64(n) => visitor.visit_i64(n),
+            Value::I128(n) => visitor.visit_i128(n),
+            Value::U8(n) => visitor.visit_u8(n),
+            Value::U16(n) => visitor.visit_u16(n),
+            Value::U32(n) => visitor.visit_u32(n),
+            Value::U64(n) => visitor.visit_u64(n),
+            Value::U128(n) => visitor.visit_u128(n),
+            Value::F32(n) => visitor.visit_f32(n),
+            Value::F64(n) => visitor.visit_f64(n),
+            Value::Bytes(b) => visitor.visit_byte_buf(b),
+            Value::Date(d) => visitor.visit_string(d.to_string()),
+            Value::Time(t) => visitor.visit_string(t.to_string()),
+            Value::DateTime(dt) => {
+                visitor.visit_string(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+            }
+            Value::DateTimeUtc(dt) => visitor.visit_string(dt.to_rfc3339()),
+            Value::Decimal(d) => visitor.visit_string(d.to_string()),
+            Value::List(l) => visitor.visit_seq(SeqDeserializer::new(l)),
+            Value::Map(m) => visitor.visit_map(MapDeserializer::new(m)),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        // SQLite and MySQL both store booleans as integers.
+        match self.value {
+            Value::I32(n) => visitor.visit_bool(n != 0),
+            Value::I64(n) => visitor.visit_bool(n != 0),
+            Value::U64(n) => visitor.visit_bool(n != 0),
+            other => ValueDeserializer::new(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        // MySQL returns text columns as raw bytes.
+        match self.value {
+            Value::Bytes(b) => match String::from_utf8(b) {
+                Ok(s) => visitor.visit_string(s),
+                Err(e) => Err(DbError::TypeMismatch(format!("Invalid UTF-8 bytes: {}", e))),
+            },
+            other => ValueDeserializer::new(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DbError> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DbError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DbError> {
+        match self.value {
+            Value::Str(s) | Value::Enum(s) => visitor.visit_enum(s.into_deserializer()),
+            Value::Bytes(b) => match String::from_utf8(b) {
+                Ok(s) => visitor.visit_enum(s.into_deserializer()),
+                Err(e) => Err(DbError::TypeMismatch(format!("Invalid UTF-8 bytes: {}", e))),
+            },
+            other => Err(DbError::TypeMismatch(format!(
+                "Expected enum variant name, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct MapDeserializer {
+    iter: std::collections::hash_map::IntoIter<String, Value>,
+    value: Option<Value>,
+}
+
+impl MapDeserializer {
+    fn new(map: HashMap<String, Value>) -> Self {
+        Self {
+            iter: map.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = DbError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DbError> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DbError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| DbError::SerializationError("Map value missing".to_string()))?;
+        seed.deserialize(ValueDeserializer::new(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct SeqDeserializer {
+    iter: std::vec::IntoIter<Value>,
+}
+
+impl SeqDeserializer {
+    fn new(list: Vec<Value>) -> Self {
+        Self {
+            iter: list.into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = DbError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DbError> {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(ValueDeserializer::new(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Deserializes a `bool` from either a boolean or an integer column.
+///
+/// `ValueDeserializer` already coerces integers for plain `bool` fields, but fields inside
+/// a `#[serde(flatten)]` struct are buffered through `deserialize_any` first and lose that
+/// coercion. Annotate such fields with `#[serde(deserialize_with = "bool_from_int")]`.
+pub fn bool_from_int<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    struct BoolVisitor;
+
+    impl Visitor<'_> for BoolVisitor {
+        type Value = bool;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a boolean or an integer")
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> Result<bool, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<bool, E> {
+            Ok(v != 0)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<bool, E> {
+            Ok(v != 0)
+        }
+    }
+
+    deserializer.deserialize_any(BoolVisitor)
+}
+
+fn snake_to_camel(s: &str) -> String {
+    let mut out = String::new();
+    let mut parts = s.split('_').filter(|p| !p.is_empty());
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(c0) = chars.next() {
+            out.extend(c0.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    out
+}
+
+fn snake_to_upper_camel(s: &str) -> String {
+    let camel = snake_to_camel(s);
+    let mut chars = camel.chars();
+    match chars.next() {
+        Some(c0) => c0.to_uppercase().chain(chars).collect(),
+        None => camel,
+    }
+}
+
+fn camel_to_snake(s: &str) -> String {
+    let mut out = String::new();
+    let mut iter = s.chars().peekable();
+    let mut prev_is_lower_or_digit = false;
+
+    while let Some(ch) = iter.next() {
+        if ch == '_' {
+            if !out.ends_with('_') {
+                out.push('_');
+            }
+            prev_is_lower_or_digit = false;
+            continue;
+        }
+
+        if ch.is_uppercase() {
+            let next_is_lower_or_digit = iter
+                .peek()
+                .is_some_and(|n| n.is_lowercase() || n.is_numeric());
+
+            if !out.is_empty()
+                && (prev_is_lower_or_digit || next_is_lower_or_digit)
+                && !out.ends_with('_')
+            {
+                out.push('_');
+            }
+
+            out.extend(ch.to_lowercase());
+            prev_is_lower_or_digit = false;
+        } else {
+            out.push(ch);
+            prev_is_lower_or_digit = ch.is_lowercase() || ch.is_numeric();
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        id: i64,
+        name: String,
+        active: bool,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Role {
+        role_name: String,
+        level: Option<i32>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct UserWithRole {
+        #[serde(flatten)]
+        user: User,
+        #[serde(flatten)]
+        role: Role,
+    }
+
+    fn row() -> HashMap<String, Value> {
+        let mut row = HashMap::new();
+        row.insert("id".to_string(), Value::I64(1));
+        row.insert("name".to_string(), Value::Bytes(b"Alice".to_vec()));
+        row.insert("active".to_string(), Value::I64(1));
+        row.insert("role_name".to_string(), Value::Str("admin".to_string()));
+        row.insert("level".to_string(), Value::Null);
+        row
+    }
+
+    #[test]
+    fn test_deserialize_struct() {
+        let user: User = from_row(row()).unwrap();
+        assert_eq!(user.id, 1);
+        assert_eq!(user.name, "Alice");
+        assert!(user.active);
+    }
+
+    #[test]
+    fn test_deserialize_flatten() {
+        let mut row = row();
+        // Flattened fields are buffered through `deserialize_any`, so integer-encoded
+        // booleans are not coerced there (see `bool_from_int`).
+        row.insert("active".to_string(), Value::Bool(true));
+
+        let v: UserWithRole = RowDeserializer::new(row).deserialize().unwrap();
+        assert_eq!(v.user.id, 1);
+        assert_eq!(v.user.name, "Alice");
+        assert_eq!(v.role.role_name, "admin");
+        assert_eq!(v.role.level, None);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Flags {
+        #[serde(deserialize_with = "bool_from_int")]
+        active: bool,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct UserWithFlags {
+        id: i64,
+        #[serde(flatten)]
+        flags: Flags,
+    }
+
+    #[test]
+    fn test_deserialize_flatten_bool_coercion() {
+        // Without `bool_from_int` the buffered integer is rejected.
+        assert!(from_row::<UserWithRole>(row()).is_err());
+
+        let mut row = HashMap::new();
+        row.insert("id".to_string(), Value::I64(1));
+        row.insert("active".to_string(), Value::I64(1));
+
+        let v: UserWithFlags = from_row(row.clone()).unwrap();
+        assert!(v.flags.active);
+
+        row.insert("active".to_string(), Value::I32(0));
+        let v: UserWithFlags = from_row(row.clone()).unwrap();
+        assert!(!v.flags.active);
+
+        row.insert("active".to_string(), Value::Bool(true));
+        let v: UserWithFlags = from_row(row).unwrap();
+        assert!(v.flags.active);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Profile {
+        first_name: String,
+        last_name: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[allow(non_snake_case)]
+    struct CamelProfile {
+        firstName: String,
+    }
+
+    #[test]
+    fn test_transform_snake_to_camel() {
+        let mut row = HashMap::new();
+        row.insert("firstName".to_string(), Value::Str("John".to_string()));
+        row.insert("LastName".to_string(), Value::Str("Doe".to_string()));
+
+        let err = RowDeserializer::new(row.clone()).deserialize::<Profile>();
+        assert!(err.is_err());
+
+        let p: Profile = RowDeserializer::new(row)
+            .with_transform(CaseTransform::SnakeToUpperCamel)
+            .deserialize()
+            .unwrap();
+        assert_eq!(p.first_name, "John");
+        assert_eq!(p.last_name, "Doe");
+    }
+
+    #[test]
+    fn test_transform_camel_to_snake() {
+        let mut row = HashMap::new();
+        row.insert("first_name".to_string(), Value::Str("John".to_string()));
+
+        let p: CamelProfile = RowDeserializer::new(row)
+            .with_transform(CaseTransform::CamelToSnake)
+            .deserialize()
+            .unwrap();
+        assert_eq!(p.firstName, "John");
+    }
+}
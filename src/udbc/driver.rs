@@ -1,7 +1,21 @@
 use crate::Result;
+use crate::tpl::parser::ParserConfig;
+use crate::udbc::PoolStats;
 use crate::udbc::connection::Connection;
 use async_trait::async_trait;
 
+/// How bound parameters appear in rendered SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaceholderStyle {
+    /// Whatever [`Driver::placeholder`] returns (`?`, `$1`, ...); parameters are bound in order.
+    #[default]
+    Positional,
+    /// `:name`; each distinct parameter is bound once under its name.
+    NamedColon,
+    /// `@name`; each distinct parameter is bound once under its name.
+    NamedAt,
+}
+
 /// `Driver` defines a common interface for database drivers.
 ///
 /// A driver is responsible for:
@@ -37,6 +51,24 @@ pub trait Driver: Send + Sync {
     /// - Named parameters: `:param_name`
     fn placeholder(&self, param_seq: usize, param_name: &str) -> String;
 
+    /// Selects how the template engine writes parameters.
+    ///
+    /// With a named style, [`Driver::placeholder`] is not called: the engine emits `:name` or
+    /// `@name` itself, and the bound parameter list holds one entry per distinct name. Names
+    /// that are not valid identifiers (e.g. `user.id`) have other characters replaced with
+    /// `_`, and a name reused with a different value (e.g. inside `<foreach>`) gets a `_2`,
+    /// `_3`, ... suffix.
+    fn placeholder_style(&self) -> PlaceholderStyle {
+        PlaceholderStyle::Positional
+    }
+
+    /// Optional template syntax enabled for SQL run through this driver.
+    ///
+    /// Defaults to the global config set with [`crate::tpl::engine::configure`].
+    fn parser_config(&self) -> ParserConfig {
+        crate::tpl::engine::config().parser
+    }
+
     /// Creates and returns a new database connection.
     ///
     /// # Returns
@@ -44,6 +76,24 @@ pub trait Driver: Send + Sync {
     /// - `Err(Error)` if connection creation fails
     async fn acquire(&self) -> Result<Box<dyn Connection>>;
 
+    /// Pre-opens the pool's minimum number of idle connections.
+    ///
+    /// Drivers without a real pool can keep the default no-op implementation.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the connections were established (or nothing needed to be done)
+    /// - `Err(Error)` if a connection could not be created
+    async fn warmup(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns a snapshot of the connection pool's usage.
+    ///
+    /// Drivers that don't track pool metrics keep the default, which reports all zeros.
+    fn pool_stats(&self) -> PoolStats {
+        PoolStats::default()
+    }
+
     /// Closes the driver and releases any associated resources.
     ///
     /// This should be called when the driver is no longer needed.
@@ -1,4 +1,5 @@
 pub mod connection;
+pub mod deserializer;
 pub mod driver;
 #[cfg(feature = "mysql")]
 pub mod mysql;
@@ -8,11 +9,104 @@ pub mod value;
 
 pub use value::Value;
 
+use crate::Result;
+use crate::error::DbError;
+
 pub const DEFAULT_DB_NAME: &str = "default";
 
+#[derive(Debug, Clone, Default)]
 pub struct PoolOptions {
     pub max_open_conns: u64, // Set the maximum number of connections in the pool
     pub max_idle_conns: u64, // Set the maximum number of idle connections in the pool
+    pub min_idle_conns: u64, // Set the number of idle connections kept open (and created on warmup; MySQL: 0 keeps `max_idle_conns`)
     pub max_lifetime: u64,   // Set the maximum lifetime of a connection
-    pub timeout: u64,        // Set the timeout for getting a connection from the pool
+    pub timeout: u64, // MySQL: wait for a pooled connection; SQLite: `busy_timeout` on locked files (secs, 0 = none)
+    pub acquire_timeout_secs: u64, // Set the upper bound on a whole acquire() call (0 = no limit; MySQL falls back to `timeout`)
+    pub retry_attempts: u32,       // Set how many times a failed acquire is retried
+    pub retry_delay_ms: u64,       // Set the base backoff delay; doubles on every retry
+    pub retry_max_delay_ms: u64,   // Set the backoff cap (0 = 30s)
+}
+
+impl PoolOptions {
+    /// Checks `min_idle_conns <= max_idle_conns <= max_open_conns` and `max_open_conns > 0`.
+    ///
+    /// # Errors
+    /// `DbError::DriverError` describing the first violated constraint.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_open_conns == 0 {
+            return Err(DbError::DriverError(
+                "Invalid pool options: max_open_conns must be greater than 0".to_string(),
+            ));
+        }
+        if self.max_idle_conns > self.max_open_conns {
+            return Err(DbError::DriverError(format!(
+                "Invalid pool options: max_idle_conns ({}) > max_open_conns ({})",
+                self.max_idle_conns, self.max_open_conns
+            )));
+        }
+        if self.min_idle_conns > self.max_idle_conns {
+            return Err(DbError::DriverError(format!(
+                "Invalid pool options: min_idle_conns ({}) > max_idle_conns ({})",
+                self.min_idle_conns, self.max_idle_conns
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reads pool settings from `DB_MAX_OPEN_CONNS`, `DB_MAX_IDLE_CONNS`,
+    /// `DB_MAX_LIFETIME_SECS` and `DB_TIMEOUT_SECS`.
+    ///
+    /// Returns `None` when none of them is set, so the driver keeps its own defaults.
+    /// Otherwise `max_open_conns` defaults to 10 and `max_idle_conns` to `max_open_conns`.
+    ///
+    /// # Errors
+    /// `DbError::DriverError` naming the variable if a value is not a non-negative integer.
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::from_lookup(env_var)
+    }
+
+    pub(crate) fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Option<Self>> {
+        let max_open = parse_var(&lookup, "DB_MAX_OPEN_CONNS")?;
+        let max_idle = parse_var(&lookup, "DB_MAX_IDLE_CONNS")?;
+        let max_lifetime = parse_var(&lookup, "DB_MAX_LIFETIME_SECS")?;
+        let timeout = parse_var(&lookup, "DB_TIMEOUT_SECS")?;
+        if max_open.is_none() && max_idle.is_none() && max_lifetime.is_none() && timeout.is_none() {
+            return Ok(None);
+        }
+
+        let max_open_conns = max_open.unwrap_or(10);
+        Ok(Some(PoolOptions {
+            max_open_conns,
+            max_idle_conns: max_idle.unwrap_or(max_open_conns),
+            max_lifetime: max_lifetime.unwrap_or(0),
+            timeout: timeout.unwrap_or(0),
+            ..Default::default()
+        }))
+    }
+}
+
+/// Reads an environment variable, treating an empty value as unset.
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+fn parse_var(lookup: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<u64>> {
+    let Some(raw) = lookup(name) else {
+        return Ok(None);
+    };
+    raw.trim().parse().map(Some).map_err(|_| {
+        DbError::DriverError(format!(
+            "Invalid value for {}: '{}' is not a non-negative integer",
+            name, raw
+        ))
+    })
+}
+
+/// A snapshot of a driver's connection pool usage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub active_connections: u64, // Connections currently checked out by the application
+    pub idle_connections: u64,   // Connections sitting in the pool, ready for reuse
+    pub waiting_requests: u64,   // Acquire calls currently waiting for a connection
+    pub max_connections: u64,    // Configured upper bound on open connections (0 = unbounded)
 }
@@ -5,10 +5,26 @@ use std::collections::HashMap;
 
 use crate::Result;
 use crate::error::DbError;
-use crate::udbc::connection::Connection;
-use crate::udbc::mysql::value_codec::{from_mysql_value, to_mysql_value};
+use crate::udbc::connection::{Connection, Rows};
+use crate::udbc::mysql::value_codec::{from_mysql_column_value, to_mysql_value};
 use crate::udbc::value::Value;
 
+/// Server error codes for failures that are worth retrying.
+const RETRYABLE_ERROR_CODES: &[u16] = &[
+    1205, // ER_LOCK_WAIT_TIMEOUT
+    1213, // ER_LOCK_DEADLOCK
+];
+
+/// Converts a statement error, flagging retryable server errors as `DbError::Transient`.
+fn map_mysql_error(e: mysql_async::Error) -> DbError {
+    match &e {
+        mysql_async::Error::Server(se) if RETRYABLE_ERROR_CODES.contains(&se.code) => {
+            DbError::Transient(e.to_string())
+        }
+        _ => DbError::DbError(e.to_string()),
+    }
+}
+
 pub struct MysqlConnection {
     conn: Conn,
 }
@@ -28,7 +44,7 @@ impl MysqlConnection {
         let mut out_row = HashMap::with_capacity(values.len());
         // Zip values with columns. We rely on the driver ensuring lengths match.
         for (v, col) in values.into_iter().zip(columns.iter()) {
-            out_row.insert(col.name_str().to_string(), from_mysql_value(v));
+            out_row.insert(col.name_str().to_string(), from_mysql_column_value(v, col));
         }
         out_row
     }
@@ -41,17 +57,66 @@ impl Connection for MysqlConnection {
         sql: &str,
         args: &[(String, Value)],
     ) -> Result<Vec<HashMap<String, Value>>> {
+        Ok(self.query_with_columns(sql, args).await?.rows)
+    }
+
+    async fn query_with_columns(&mut self, sql: &str, args: &[(String, Value)]) -> Result<Rows> {
         // Map args to positional params. Note: We ignore keys in args as mysql_async
         // expects Positional params for '?' placeholders.
         let params =
             mysql_async::Params::Positional(args.iter().map(|(_, v)| to_mysql_value(v)).collect());
 
-        let rows: Vec<MyRow> = self
+        let rows: Vec<MyRow> = self.conn.exec(sql, params).await.map_err(map_mysql_error)?;
+        // Rows share their column metadata; an empty result has nothing to map.
+        let columns = rows
+            .first()
+            .map(|row| {
+                row.columns_ref()
+                    .iter()
+                    .map(|col| col.name_str().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Rows {
+            columns,
+            rows: rows.into_iter().map(Self::map_row).collect(),
+        })
+    }
+
+    async fn query_one(
+        &mut self,
+        sql: &str,
+        args: &[(String, Value)],
+    ) -> Result<Option<HashMap<String, Value>>> {
+        Ok(self.query_one_with_columns(sql, args).await?.rows.pop())
+    }
+
+    async fn query_one_with_columns(
+        &mut self,
+        sql: &str,
+        args: &[(String, Value)],
+    ) -> Result<Rows> {
+        let params =
+            mysql_async::Params::Positional(args.iter().map(|(_, v)| to_mysql_value(v)).collect());
+
+        let row: Option<MyRow> = self
             .conn
-            .exec(sql, params)
+            .exec_first(sql, params)
             .await
-            .map_err(|e| DbError::DbError(e.to_string()))?;
-        Ok(rows.into_iter().map(Self::map_row).collect())
+            .map_err(map_mysql_error)?;
+        let columns = row
+            .as_ref()
+            .map(|row| {
+                row.columns_ref()
+                    .iter()
+                    .map(|col| col.name_str().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Rows {
+            columns,
+            rows: row.into_iter().map(Self::map_row).collect(),
+        })
     }
 
     async fn execute(&mut self, sql: &str, args: &[(String, Value)]) -> Result<u64> {
@@ -61,7 +126,7 @@ impl Connection for MysqlConnection {
         self.conn
             .exec_drop(sql, params)
             .await
-            .map_err(|e| DbError::DbError(e.to_string()))?;
+            .map_err(map_mysql_error)?;
         Ok(self.conn.affected_rows())
     }
 
@@ -74,7 +139,7 @@ impl Connection for MysqlConnection {
         self.conn
             .query_drop("BEGIN")
             .await
-            .map_err(|e| DbError::DbError(e.to_string()))?;
+            .map_err(map_mysql_error)?;
         Ok(())
     }
 
@@ -82,7 +147,7 @@ impl Connection for MysqlConnection {
         self.conn
             .query_drop("COMMIT")
             .await
-            .map_err(|e| DbError::DbError(e.to_string()))?;
+            .map_err(map_mysql_error)?;
         Ok(())
     }
 
@@ -90,7 +155,30 @@ impl Connection for MysqlConnection {
         self.conn
             .query_drop("ROLLBACK")
             .await
-            .map_err(|e| DbError::DbError(e.to_string()))?;
+            .map_err(map_mysql_error)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mysql_async::ServerError;
+
+    #[test]
+    fn test_map_mysql_error() {
+        let server_error = |code| {
+            mysql_async::Error::Server(ServerError {
+                code,
+                message: String::new(),
+                state: String::new(),
+            })
+        };
+        assert!(matches!(
+            map_mysql_error(server_error(1213)),
+            DbError::Transient(_)
+        ));
+        assert!(map_mysql_error(server_error(1205)).is_retryable());
+        assert!(!map_mysql_error(server_error(1064)).is_retryable()); // ER_PARSE_ERROR
+    }
+}
@@ -1,16 +1,37 @@
 use crate::Result;
 use crate::error::DbError;
+use crate::tpl::parser::ParserConfig;
 use crate::udbc::connection::Connection;
 use crate::udbc::driver::Driver;
 use crate::udbc::mysql::connection::MysqlConnection;
-use crate::udbc::{DEFAULT_DB_NAME, PoolOptions};
+use crate::udbc::{DEFAULT_DB_NAME, PoolOptions, PoolStats, env_var};
 use async_trait::async_trait;
-use mysql_async::{Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts};
+use log::warn;
+use mysql_async::{
+    Conn, DEFAULT_POOL_CONSTRAINTS, Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts,
+    prelude::Queryable,
+};
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use tokio::time::timeout;
 
 const MYSQL_TYPE: &str = "mysql";
 
+/// Session setup run by [`MysqlDriver::with_defaults`]: UTF-8 everywhere and UTC datetimes.
+const DEFAULT_INIT_SQL: &[&str] = &["SET NAMES utf8mb4", "SET time_zone = '+00:00'"];
+
+/// Backoff cap used when `retry_max_delay_ms` is 0.
+const DEFAULT_RETRY_MAX_DELAY_MS: u64 = 30_000;
+
+/// Server error codes that retrying cannot fix (bad credentials, unknown database, ...).
+const NON_TRANSIENT_ERROR_CODES: &[u16] = &[
+    1044, // ER_DBACCESS_DENIED_ERROR
+    1045, // ER_ACCESS_DENIED_ERROR
+    1049, // ER_BAD_DB_ERROR
+    1251, // ER_NOT_SUPPORTED_AUTH_MODE
+    1698, // ER_ACCESS_DENIED_NO_PASSWORD_ERROR
+];
+
 /// `MysqlDriver` manages the MySQL connection pool and configuration.
 ///
 /// It implements the `Driver` trait to provide database connectivity.
@@ -20,6 +41,9 @@ pub struct MysqlDriver {
     url: String,
     name: String,
     options: Option<PoolOptions>,
+    parser_config: Option<ParserConfig>,
+    /// Statements run on every connection handed out by [`Driver::acquire`].
+    connection_init_sql: Vec<String>,
     pool: Option<Pool>,
 }
 
@@ -30,10 +54,47 @@ impl MysqlDriver {
             name: DEFAULT_DB_NAME.to_string(),
             url: url.into(),
             options: None,
+            parser_config: None,
+            connection_init_sql: Vec::new(),
             pool: None,
         }
     }
 
+    /// Creates a driver whose connections use `utf8mb4` and the UTC time zone, so strings and
+    /// datetimes don't depend on the server's defaults.
+    pub fn with_defaults(url: impl Into<String>) -> Self {
+        DEFAULT_INIT_SQL
+            .iter()
+            .fold(Self::new(url), |driver, sql| driver.init_sql(sql))
+    }
+
+    /// Creates and builds a driver from environment variables; the recommended way to
+    /// configure production deployments, since it keeps credentials out of the source.
+    ///
+    /// Reads `DATABASE_URL` (required), `DB_NAME` (defaults to `"default"`) and the pool
+    /// settings described in [`PoolOptions::from_env`].
+    ///
+    /// # Errors
+    /// `DbError::DbUrlError` if `DATABASE_URL` is missing, `DbError::DriverError` for an
+    /// invalid pool setting, and anything [`MysqlDriver::build`] reports.
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(env_var)
+    }
+
+    pub(crate) fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let url = lookup("DATABASE_URL").ok_or_else(|| {
+            DbError::DbUrlError("Environment variable DATABASE_URL is not set".to_string())
+        })?;
+        let mut driver = Self::new(url);
+        if let Some(name) = lookup("DB_NAME") {
+            driver = driver.name(name);
+        }
+        if let Some(options) = PoolOptions::from_lookup(&lookup)? {
+            driver = driver.options(options);
+        }
+        driver.build()
+    }
+
     /// Sets the name of the database driver instance.
     pub fn name(mut self, name: String) -> Self {
         self.name = name;
@@ -47,12 +108,26 @@ impl MysqlDriver {
         self
     }
 
+    /// Enables optional template syntax (e.g. `:name` parameters) for SQL run on this driver.
+    pub fn parser_config(mut self, config: ParserConfig) -> Self {
+        self.parser_config = Some(config);
+        self
+    }
+
+    /// Adds a statement to run on each connection right after it is acquired, e.g.
+    /// `SET time_zone = '+00:00'`. Statements run in the order they were added.
+    pub fn init_sql(mut self, sql: &str) -> Self {
+        self.connection_init_sql.push(sql.to_string());
+        self
+    }
+
     /// Builds the connection pool and prepares the driver for use.
     ///
     /// # Errors
     /// Returns `Error` if:
     /// - The connection URL is invalid.
-    /// - Pool constraints are invalid (e.g., max_idle > max_open or max_open == 0).
+    /// - Pool options fail [`PoolOptions::validate`] (reported as `DbError::DbError` tagged
+    ///   with the driver name).
     pub fn build(mut self) -> Result<Self> {
         let opts = Opts::from_url(&self.url).map_err(|e| {
             DbError::DbUrlError(format!("[{}] Invalid connection URL: {}", self.name, e))
@@ -66,25 +141,27 @@ impl MysqlDriver {
         builder = builder.tcp_keepalive(Some(60_000u32));
 
         if let Some(options) = &self.options {
-            // Validate basic constraints: max_open_conns must be > 0
-            if options.max_open_conns == 0 {
-                return Err(self.err_context(
-                    "Invalid pool constraints: max_open_conns must be greater than 0",
-                ));
-            }
+            options.validate().map_err(|e| match e {
+                DbError::DriverError(msg) => self.err_context(msg),
+                other => other,
+            })?;
 
             // Configure connection pool constraints (min/max connections)
             // mysql_async requires: min <= max and max > 0
-            let constraints = PoolConstraints::new(
-                options.max_idle_conns as usize,
-                options.max_open_conns as usize,
-            )
-            .ok_or_else(|| {
-                self.err_context(format!(
-                    "Invalid pool constraints: max_idle_conns ({}) > max_open_conns ({})",
-                    options.max_idle_conns, options.max_open_conns
-                ))
-            })?;
+            // Without `min_idle_conns` the pool keeps `max_idle_conns` open, as it always did.
+            let min_conns = if options.min_idle_conns > 0 {
+                options.min_idle_conns
+            } else {
+                options.max_idle_conns
+            };
+            let constraints =
+                PoolConstraints::new(min_conns as usize, options.max_open_conns as usize)
+                    .ok_or_else(|| {
+                        self.err_context(format!(
+                            "Invalid pool constraints: min connections ({}) > max_open_conns ({})",
+                            min_conns, options.max_open_conns
+                        ))
+                    })?;
 
             let mut pool_opts = PoolOpts::default().with_constraints(constraints);
 
@@ -102,12 +179,88 @@ impl MysqlDriver {
         Ok(self)
     }
 
+    /// Makes a single attempt to get a connection from the pool, honoring `timeout`.
+    ///
+    /// On failure, also reports whether the error is worth retrying.
+    async fn get_conn(&self, pool: &Pool) -> std::result::Result<Conn, (DbError, bool)> {
+        let get_conn_fut = pool.get_conn();
+
+        // Acquire a connection, optionally with a timeout
+        let timeout_secs = self.options.as_ref().map_or(0, |o| {
+            if o.acquire_timeout_secs > 0 {
+                o.acquire_timeout_secs
+            } else {
+                o.timeout
+            }
+        });
+        let result = if timeout_secs > 0 {
+            // Wrap acquisition in a timeout
+            match timeout(Duration::from_secs(timeout_secs), get_conn_fut).await {
+                Ok(result) => result,
+                Err(_) => {
+                    return Err((
+                        DbError::Timeout(format!(
+                            "[{}] Connection acquisition timed out (timeout: {}s)",
+                            self.name, timeout_secs
+                        )),
+                        true,
+                    ));
+                }
+            }
+        } else {
+            get_conn_fut.await
+        };
+
+        result.map_err(|e| {
+            let transient = is_transient(&e);
+            (self.err_context(e), transient)
+        })
+    }
+
+    /// Runs the configured init statements on a freshly acquired connection.
+    async fn run_init_sql(&self, conn: &mut Conn) -> Result<()> {
+        for sql in &self.connection_init_sql {
+            conn.query_drop(sql).await.map_err(|e| {
+                self.err_context(format!("Connection init SQL '{}' failed: {}", sql, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Backoff before retry number `attempt + 1`: `retry_delay_ms * 2^attempt`, capped.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let (base, cap) = self
+            .options
+            .as_ref()
+            .map_or((0, 0), |o| (o.retry_delay_ms, o.retry_max_delay_ms));
+        backoff_delay(base, cap, attempt)
+    }
+
     /// Helper to format errors with the driver name context.
     fn err_context<T: std::fmt::Display>(&self, msg: T) -> DbError {
         DbError::DbError(format!("[{}] {}", self.name, msg))
     }
 }
 
+fn backoff_delay(base_ms: u64, cap_ms: u64, attempt: u32) -> Duration {
+    let cap_ms = if cap_ms == 0 {
+        DEFAULT_RETRY_MAX_DELAY_MS
+    } else {
+        cap_ms
+    };
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    Duration::from_millis(base_ms.saturating_mul(factor).min(cap_ms))
+}
+
+/// Returns `false` for errors that will fail again no matter how often we retry.
+fn is_transient(e: &mysql_async::Error) -> bool {
+    match e {
+        mysql_async::Error::Server(se) => !NON_TRANSIENT_ERROR_CODES.contains(&se.code),
+        mysql_async::Error::Url(_) => false,
+        _ => true,
+    }
+}
+
 #[async_trait]
 impl Driver for MysqlDriver {
     fn name(&self) -> &str {
@@ -123,35 +276,72 @@ impl Driver for MysqlDriver {
         "?".to_string()
     }
 
+    fn parser_config(&self) -> ParserConfig {
+        self.parser_config
+            .unwrap_or_else(|| crate::tpl::engine::config().parser)
+    }
+
     async fn acquire(&self) -> Result<Box<dyn Connection>> {
         let pool = self.pool.as_ref().ok_or_else(|| {
             self.err_context("Connection pool not initialized (call build() first)")
         })?;
 
-        let get_conn_fut = pool.get_conn();
-
-        // Acquire a connection, optionally with a timeout
-        let conn = if let Some(options) = &self.options {
-            if options.timeout > 0 {
-                // Wrap acquisition in a timeout
-                match timeout(Duration::from_secs(options.timeout), get_conn_fut).await {
-                    Ok(result) => result,
-                    Err(_) => {
-                        return Err(self.err_context(format!(
-                            "Connection acquisition timed out (timeout: {}s)",
-                            options.timeout
-                        )));
+        let retry_attempts = self.options.as_ref().map_or(0, |o| o.retry_attempts);
+        let mut attempt = 0;
+        loop {
+            match self.get_conn(pool).await {
+                Ok(mut conn) => {
+                    self.run_init_sql(&mut conn).await?;
+                    return Ok(Box::new(MysqlConnection::new(conn)));
+                }
+                Err((err, transient)) => {
+                    if !transient || attempt >= retry_attempts {
+                        return Err(err);
                     }
+                    let delay = self.retry_delay(attempt);
+                    warn!(
+                        "[{}] Connection acquisition failed (attempt {}/{}), retrying in {}ms: {}",
+                        self.name,
+                        attempt + 1,
+                        retry_attempts + 1,
+                        delay.as_millis(),
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
                 }
-            } else {
-                get_conn_fut.await
             }
-        } else {
-            get_conn_fut.await
         }
-        .map_err(|e| self.err_context(e))?;
+    }
 
-        Ok(Box::new(MysqlConnection::new(conn)))
+    async fn warmup(&self) -> Result<()> {
+        let min_idle = self.options.as_ref().map_or(0, |o| o.min_idle_conns);
+
+        // Hold all connections at once so the pool has to open `min_idle` distinct ones;
+        // dropping them returns them to the pool as idle connections.
+        let mut conns = Vec::with_capacity(min_idle as usize);
+        for _ in 0..min_idle {
+            conns.push(self.acquire().await?);
+        }
+        Ok(())
+    }
+
+    fn pool_stats(&self) -> PoolStats {
+        let Some(pool) = &self.pool else {
+            return PoolStats::default();
+        };
+        let metrics = pool.metrics();
+        let total = metrics.connection_count.load(Ordering::Relaxed) as u64;
+        let idle = metrics.connections_in_pool.load(Ordering::Relaxed) as u64;
+        PoolStats {
+            active_connections: total.saturating_sub(idle),
+            idle_connections: idle,
+            waiting_requests: metrics.active_wait_requests.load(Ordering::Relaxed) as u64,
+            max_connections: self
+                .options
+                .as_ref()
+                .map_or(DEFAULT_POOL_CONSTRAINTS.max() as u64, |o| o.max_open_conns),
+        }
     }
 
     async fn close(&self) -> Result<()> {
@@ -167,3 +357,106 @@ impl Driver for MysqlDriver {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mysql_async::ServerError;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(100, 1_000, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(100, 1_000, 2), Duration::from_millis(400));
+        assert_eq!(backoff_delay(100, 1_000, 5), Duration::from_millis(1_000));
+        assert_eq!(
+            backoff_delay(100, 0, 63),
+            Duration::from_millis(DEFAULT_RETRY_MAX_DELAY_MS)
+        );
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let server_error = |code| {
+            mysql_async::Error::Server(ServerError {
+                code,
+                message: String::new(),
+                state: String::new(),
+            })
+        };
+        assert!(!is_transient(&server_error(1045)));
+        assert!(is_transient(&server_error(1040))); // ER_CON_COUNT_ERROR
+    }
+
+    #[tokio::test]
+    async fn test_from_env() {
+        let lookup = |vars: &'static [(&'static str, &'static str)]| {
+            move |k: &str| {
+                vars.iter()
+                    .find(|(name, _)| *name == k)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        let driver = MysqlDriver::from_lookup(lookup(&[
+            ("DATABASE_URL", "mysql://root@localhost:3306/app"),
+            ("DB_NAME", "primary"),
+            ("DB_MAX_OPEN_CONNS", "20"),
+            ("DB_TIMEOUT_SECS", "5"),
+        ]))
+        .unwrap();
+        assert_eq!(Driver::name(&driver), "primary");
+        let options = driver.options.as_ref().unwrap();
+        assert_eq!(options.max_open_conns, 20);
+        assert_eq!(options.max_idle_conns, 20);
+        assert_eq!(options.timeout, 5);
+
+        let err = MysqlDriver::from_lookup(lookup(&[
+            ("DATABASE_URL", "mysql://root@localhost:3306/app"),
+            ("DB_MAX_IDLE_CONNS", "many"),
+        ]))
+        .err()
+        .unwrap();
+        assert!(matches!(err, DbError::DriverError(msg) if msg.contains("DB_MAX_IDLE_CONNS")));
+
+        let err = MysqlDriver::from_lookup(lookup(&[])).err().unwrap();
+        assert!(matches!(err, DbError::DbUrlError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_options_name_the_driver() {
+        let err = MysqlDriver::new("mysql://root@localhost:3306/app")
+            .name("primary".to_string())
+            .options(PoolOptions {
+                max_open_conns: 2,
+                max_idle_conns: 3,
+                ..PoolOptions::default()
+            })
+            .build()
+            .err()
+            .unwrap();
+        assert!(
+            matches!(&err, DbError::DbError(msg) if msg.starts_with("[primary] ") && msg.contains("max_idle_conns (3)")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_init_sql() {
+        let driver = MysqlDriver::with_defaults("mysql://root@localhost:3306/app")
+            .init_sql("SET SESSION sql_mode = 'STRICT_ALL_TABLES'");
+        assert_eq!(
+            driver.connection_init_sql,
+            vec![
+                "SET NAMES utf8mb4",
+                "SET time_zone = '+00:00'",
+                "SET SESSION sql_mode = 'STRICT_ALL_TABLES'",
+            ]
+        );
+        assert!(
+            MysqlDriver::new("mysql://root@localhost:3306/app")
+                .connection_init_sql
+                .is_empty()
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::udbc::value::Value;
 use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use mysql_async::Column;
 use mysql_async::Value as MyValue;
+use mysql_async::consts::{ColumnFlags, ColumnType};
 
 pub fn from_mysql_value(v: MyValue) -> Value {
     match v {
@@ -34,6 +36,31 @@ pub fn from_mysql_value(v: MyValue) -> Value {
     }
 }
 
+/// Converts a value read from `column`, using the column metadata to recognize ENUM and
+/// DECIMAL columns.
+pub fn from_mysql_column_value(v: MyValue, column: &Column) -> Value {
+    let is_enum = column.column_type() == ColumnType::MYSQL_TYPE_ENUM
+        || column.flags().contains(ColumnFlags::ENUM_FLAG);
+    let is_decimal = matches!(
+        column.column_type(),
+        ColumnType::MYSQL_TYPE_NEWDECIMAL | ColumnType::MYSQL_TYPE_DECIMAL
+    );
+    match v {
+        MyValue::Bytes(b) if is_enum => match String::from_utf8(b) {
+            Ok(s) => Value::Enum(s),
+            Err(e) => Value::Bytes(e.into_bytes()),
+        },
+        // DECIMAL 以文本传输，按原始精度解析；超出 rust_decimal 范围时保留原始字节
+        MyValue::Bytes(b) if is_decimal => {
+            match std::str::from_utf8(&b).ok().and_then(|s| s.parse().ok()) {
+                Some(d) => Value::Decimal(d),
+                None => Value::Bytes(b),
+            }
+        }
+        v => from_mysql_value(v),
+    }
+}
+
 pub fn to_mysql_value(v: &Value) -> MyValue {
     match v {
         Value::Null => MyValue::NULL,
@@ -51,7 +78,7 @@ pub fn to_mysql_value(v: &Value) -> MyValue {
         Value::F32(f) => MyValue::Float(*f),
         Value::F64(f) => MyValue::Double(*f),
         Value::Char(c) => MyValue::Bytes(c.to_string().into_bytes()),
-        Value::Str(s) => MyValue::Bytes(s.as_bytes().to_vec()),
+        Value::Str(s) | Value::Enum(s) => MyValue::Bytes(s.as_bytes().to_vec()),
         Value::Bytes(b) => MyValue::Bytes(b.clone()),
         Value::Date(d) => to_mysql_date_value(*d, NaiveTime::default()),
         Value::Time(t) => MyValue::Time(
@@ -88,6 +115,44 @@ fn to_mysql_date_value(d: NaiveDate, t: NaiveTime) -> MyValue {
 mod tests {
     use super::*;
     use chrono::{NaiveDate, NaiveTime};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_enum_column_conversion() {
+        let enum_col =
+            Column::new(ColumnType::MYSQL_TYPE_STRING).with_flags(ColumnFlags::ENUM_FLAG);
+        let text_col = Column::new(ColumnType::MYSQL_TYPE_STRING);
+        let raw = || MyValue::Bytes(b"active".to_vec());
+
+        assert_eq!(
+            from_mysql_column_value(raw(), &enum_col),
+            Value::Enum("active".to_string())
+        );
+        assert_eq!(
+            from_mysql_column_value(raw(), &text_col),
+            Value::Bytes(b"active".to_vec())
+        );
+        assert_eq!(to_mysql_value(&Value::Enum("active".to_string())), raw());
+    }
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let decimal_col = Column::new(ColumnType::MYSQL_TYPE_NEWDECIMAL);
+        let d: Decimal = "12345678901234.567890123".parse().unwrap();
+
+        let my_val = to_mysql_value(&Value::Decimal(d));
+        assert_eq!(my_val, MyValue::Bytes(b"12345678901234.567890123".to_vec()));
+        assert_eq!(
+            from_mysql_column_value(my_val, &decimal_col),
+            Value::Decimal(d)
+        );
+
+        let text_col = Column::new(ColumnType::MYSQL_TYPE_VAR_STRING);
+        assert_eq!(
+            from_mysql_column_value(MyValue::Bytes(b"007".to_vec()), &text_col),
+            Value::Bytes(b"007".to_vec())
+        );
+    }
 
     #[test]
     fn test_date_conversion() {
@@ -1,11 +1,16 @@
 use crate::Result;
 use crate::error::DbError;
-use crate::udbc::connection::Connection;
+use crate::udbc::connection::{Connection, Rows};
 use crate::udbc::sqlite::value_codec::{from_sqlite_value, to_sqlite_value};
 use crate::udbc::value::Value;
 use async_trait::async_trait;
+use log::error;
 use rusqlite::params_from_iter;
+use std::any::Any;
 use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Connection implementation for SQLite.
 ///
@@ -15,17 +20,47 @@ pub struct SqliteConnection {
     /// The underlying SQLite connection.
     /// Wrapped in Option to allow moving it into the blocking task.
     conn: Option<rusqlite::Connection>,
+    /// Keeps the owning driver's active-connection count up to date.
+    _active: Option<ActiveGuard>,
+}
+
+/// Increments a counter on creation and decrements it on drop.
+struct ActiveGuard(Arc<AtomicU64>);
+
+impl ActiveGuard {
+    fn new(counter: Arc<AtomicU64>) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl SqliteConnection {
     pub fn new(conn: rusqlite::Connection) -> Self {
-        Self { conn: Some(conn) }
+        Self {
+            conn: Some(conn),
+            _active: None,
+        }
+    }
+
+    /// Creates a connection that is counted in `active` for as long as it is alive.
+    pub(crate) fn tracked(conn: rusqlite::Connection, active: Arc<AtomicU64>) -> Self {
+        Self {
+            conn: Some(conn),
+            _active: Some(ActiveGuard::new(active)),
+        }
     }
 
     /// Helper method to run a blocking closure with the database connection.
     ///
     /// This method handles the boilerplate of moving the connection into a `spawn_blocking` task
-    /// and moving it back after execution.
+    /// and moving it back after execution. A panic in `f` is caught on the blocking thread, so
+    /// the connection is still handed back and the panic message becomes the error.
     async fn run_blocking<F, T>(&mut self, f: F) -> Result<T>
     where
         F: FnOnce(&mut rusqlite::Connection) -> std::result::Result<T, rusqlite::Error>
@@ -34,16 +69,16 @@ impl SqliteConnection {
         T: Send + 'static,
     {
         // Take the connection from the struct.
-        // If it's None, it means the connection was lost (e.g., due to a previous panic).
+        // If it's None, it means the connection was lost (e.g., the blocking task was cancelled).
         let conn = self
             .conn
             .take()
             .ok_or_else(|| DbError::DbError("Connection closed".to_string()))?;
 
         // Spawn a blocking task to run the database operation.
-        let (conn, result): (rusqlite::Connection, std::result::Result<T, rusqlite::Error>) = tokio::task::spawn_blocking(move || -> (rusqlite::Connection, std::result::Result<T, rusqlite::Error>) {
+        let (conn, result) = tokio::task::spawn_blocking(move || {
             let mut conn = conn;
-            let result = f(&mut conn);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(&mut conn)));
             (conn, result)
         })
         .await
@@ -52,11 +87,54 @@ impl SqliteConnection {
         // Put the connection back.
         self.conn = Some(conn);
 
-        // Return the result of the database operation.
-        result.map_err(|e: rusqlite::Error| DbError::DbError(e.to_string()))
+        match result {
+            Ok(result) => result.map_err(|e: rusqlite::Error| DbError::DbError(e.to_string())),
+            Err(payload) => {
+                let message = panic_message(payload.as_ref());
+                error!("SQLite operation panicked: {}", message);
+                Err(DbError::DbError(format!(
+                    "SQLite operation panicked: {}",
+                    message
+                )))
+            }
+        }
+    }
+}
+
+/// The message of a panic payload, if it was raised with a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "<non-string panic payload>"
     }
 }
 
+/// Column names of a prepared statement, looked up once per query.
+fn column_names(stmt: &rusqlite::Statement) -> Vec<String> {
+    (0..stmt.column_count())
+        .map(|i| {
+            stmt.column_name(i)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| i.to_string())
+        })
+        .collect()
+}
+
+fn read_row(
+    row: &rusqlite::Row,
+    column_names: &[String],
+) -> rusqlite::Result<HashMap<String, Value>> {
+    let mut map = HashMap::with_capacity(column_names.len());
+    for (i, name) in column_names.iter().enumerate() {
+        let v = row.get_ref(i)?;
+        map.insert(name.clone(), from_sqlite_value(v));
+    }
+    Ok(map)
+}
+
 #[async_trait]
 impl Connection for SqliteConnection {
     async fn query(
@@ -64,6 +142,10 @@ impl Connection for SqliteConnection {
         sql: &str,
         args: &[(String, Value)],
     ) -> Result<Vec<HashMap<String, Value>>> {
+        Ok(self.query_with_columns(sql, args).await?.rows)
+    }
+
+    async fn query_with_columns(&mut self, sql: &str, args: &[(String, Value)]) -> Result<Rows> {
         let sql = sql.to_string();
         // Convert arguments to SQLite values.
         let params = args
@@ -73,29 +155,54 @@ impl Connection for SqliteConnection {
 
         self.run_blocking(move |conn| {
             let mut stmt = conn.prepare(&sql)?;
-            let column_count = stmt.column_count();
-
-            // pre-allocate column names to avoid repeated lookups
-            let column_names: Vec<String> = (0..column_count)
-                .map(|i| {
-                    stmt.column_name(i)
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|_| i.to_string())
-                })
-                .collect();
+            let column_names = column_names(&stmt);
 
             let mut rows = stmt.query(params_from_iter(params))?;
             let mut out = Vec::new();
 
             while let Some(row) = rows.next()? {
-                let mut map = HashMap::with_capacity(column_count);
-                for (i, name) in column_names.iter().enumerate() {
-                    let v = row.get_ref(i)?;
-                    map.insert(name.clone(), from_sqlite_value(v));
-                }
-                out.push(map);
+                out.push(read_row(row, &column_names)?);
             }
-            Ok(out)
+            Ok(Rows {
+                columns: column_names,
+                rows: out,
+            })
+        })
+        .await
+    }
+
+    async fn query_one(
+        &mut self,
+        sql: &str,
+        args: &[(String, Value)],
+    ) -> Result<Option<HashMap<String, Value>>> {
+        Ok(self.query_one_with_columns(sql, args).await?.rows.pop())
+    }
+
+    async fn query_one_with_columns(
+        &mut self,
+        sql: &str,
+        args: &[(String, Value)],
+    ) -> Result<Rows> {
+        let sql = sql.to_string();
+        let params = args
+            .iter()
+            .map(|(_, v)| to_sqlite_value(v))
+            .collect::<Vec<_>>();
+
+        self.run_blocking(move |conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let column_names = column_names(&stmt);
+
+            let mut rows = stmt.query(params_from_iter(params))?;
+            let first = match rows.next()? {
+                Some(row) => vec![read_row(row, &column_names)?],
+                None => Vec::new(),
+            };
+            Ok(Rows {
+                columns: column_names,
+                rows: first,
+            })
         })
         .await
     }
@@ -147,3 +254,34 @@ impl Connection for SqliteConnection {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_panic_keeps_connection() {
+        let mut conn = SqliteConnection::new(rusqlite::Connection::open_in_memory().unwrap());
+        conn.execute("CREATE TABLE t (id INTEGER)", &[])
+            .await
+            .unwrap();
+
+        let err = conn
+            .run_blocking(|_| -> std::result::Result<(), rusqlite::Error> {
+                panic!("boom in closure")
+            })
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, DbError::DbError(msg) if msg.contains("boom in closure")),
+            "{:?}",
+            err
+        );
+
+        conn.execute("INSERT INTO t (id) VALUES (1)", &[])
+            .await
+            .unwrap();
+        let rows = conn.query("SELECT id FROM t", &[]).await.unwrap();
+        assert_eq!(rows[0]["id"], Value::I64(1));
+    }
+}
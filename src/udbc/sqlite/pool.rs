@@ -1,13 +1,16 @@
 use async_trait::async_trait;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use crate::Result;
 use crate::error::DbError;
+use crate::tpl::parser::ParserConfig;
 use crate::udbc::connection::Connection;
 use crate::udbc::driver::Driver;
 use crate::udbc::sqlite::connection::SqliteConnection;
-use crate::udbc::{DEFAULT_DB_NAME, PoolOptions};
+use crate::udbc::{DEFAULT_DB_NAME, PoolOptions, PoolStats, env_var};
 use rusqlite::OpenFlags;
 
 const SQLITE_TYPE: &str = "sqlite";
@@ -45,12 +48,49 @@ impl FromStr for SqliteTarget {
     }
 }
 
+/// Mode passed to `PRAGMA wal_checkpoint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointMode {
+    /// Checkpoint as many frames as possible without waiting for readers or writers.
+    Passive,
+    /// Wait for writers, then checkpoint all frames.
+    Full,
+    /// Like `Full`, and additionally wait for readers so the log restarts from the beginning.
+    Restart,
+    /// Like `Restart`, and additionally truncate the WAL file to zero bytes.
+    Truncate,
+}
+
+impl CheckpointMode {
+    fn as_sql(&self) -> &'static str {
+        match self {
+            CheckpointMode::Passive => "PASSIVE",
+            CheckpointMode::Full => "FULL",
+            CheckpointMode::Restart => "RESTART",
+            CheckpointMode::Truncate => "TRUNCATE",
+        }
+    }
+}
+
+/// Result row of `PRAGMA wal_checkpoint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointResult {
+    /// 1 if the checkpoint could not complete because of a lock, otherwise 0.
+    pub busy: u32,
+    /// Number of frames in the WAL file.
+    pub log: u32,
+    /// Number of frames moved back into the database file.
+    pub checkpointed: u32,
+}
+
 pub struct SqliteDriver {
     url: String,
     name: String,
     // type is constant "sqlite", no need to store it
     options: Option<PoolOptions>,
+    parser_config: Option<ParserConfig>,
     target: Option<SqliteTarget>,
+    active: Arc<AtomicU64>,
 }
 
 impl SqliteDriver {
@@ -59,8 +99,31 @@ impl SqliteDriver {
             name: DEFAULT_DB_NAME.to_string(),
             url: url.into(),
             options: None,
+            parser_config: None,
             target: None,
+            active: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Creates and builds a driver from `SQLITE_URL` (required) and `SQLITE_NAME` (defaults
+    /// to `"default"`); the recommended way to configure production deployments.
+    ///
+    /// # Errors
+    /// `DbError::DbUrlError` if `SQLITE_URL` is missing, and anything
+    /// [`SqliteDriver::build`] reports.
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(env_var)
+    }
+
+    pub(crate) fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let url = lookup("SQLITE_URL").ok_or_else(|| {
+            DbError::DbUrlError("Environment variable SQLITE_URL is not set".to_string())
+        })?;
+        let mut driver = Self::new(url);
+        if let Some(name) = lookup("SQLITE_NAME") {
+            driver = driver.name(name);
         }
+        driver.build()
     }
 
     pub fn name(mut self, name: impl Into<String>) -> Self {
@@ -73,12 +136,66 @@ impl SqliteDriver {
         self
     }
 
+    /// Enables optional template syntax (e.g. `:name` parameters) for SQL run on this driver.
+    pub fn parser_config(mut self, config: ParserConfig) -> Self {
+        self.parser_config = Some(config);
+        self
+    }
+
+    /// Validates the pool options (if any) and parses the connection URL.
+    ///
+    /// # Errors
+    /// Returns `Error` if the URL is invalid or the options fail [`PoolOptions::validate`].
     pub fn build(mut self) -> Result<Self> {
+        if let Some(options) = &self.options {
+            options.validate()?;
+        }
         self.target = Some(SqliteTarget::from_str(&self.url)?);
         Ok(self)
     }
 
-    fn open_connection(target: &SqliteTarget, timeout_secs: u64) -> Result<rusqlite::Connection> {
+    /// Runs `PRAGMA wal_checkpoint(mode)` on a short-lived connection.
+    ///
+    /// SQLite reports `-1` for `log` / `checkpointed` when the database is not in WAL mode
+    /// (e.g. in-memory databases); these are returned as `0`.
+    pub async fn checkpoint(&self, mode: CheckpointMode) -> Result<CheckpointResult> {
+        let target = self.target.clone().ok_or_else(|| {
+            DbError::DbError(
+                "Driver not built (target missing). Call build() after new().".to_string(),
+            )
+        })?;
+        let busy_timeout_secs = self.options.as_ref().map_or(0, |o| o.timeout);
+
+        let handle = tokio::task::spawn_blocking(move || {
+            let conn = Self::open_connection(&target, busy_timeout_secs)?;
+            let sql = format!("PRAGMA wal_checkpoint({})", mode.as_sql());
+            conn.query_row(&sql, [], |row| {
+                let busy: i64 = row.get(0)?;
+                let log: i64 = row.get(1)?;
+                let checkpointed: i64 = row.get(2)?;
+                Ok(CheckpointResult {
+                    busy: busy.max(0) as u32,
+                    log: log.max(0) as u32,
+                    checkpointed: checkpointed.max(0) as u32,
+                })
+            })
+            .map_err(|e| DbError::DbError(format!("Failed to run wal_checkpoint: {}", e)))
+        });
+
+        handle.await.map_err(|e: tokio::task::JoinError| {
+            DbError::DbError(format!("Task join error: {}", e))
+        })?
+    }
+
+    /// Opens a physical connection and applies the PRAGMAs every connection needs.
+    ///
+    /// `busy_timeout_secs` is how long SQLite itself waits on a locked database file before
+    /// failing with `SQLITE_BUSY` (`PoolOptions::timeout`). It is unrelated to
+    /// `PoolOptions::acquire_timeout_secs`, which bounds the whole `acquire()` call.
+    fn open_connection(
+        target: &SqliteTarget,
+        busy_timeout_secs: u64,
+    ) -> Result<rusqlite::Connection> {
         let conn = match target {
             SqliteTarget::Memory => rusqlite::Connection::open_in_memory(),
             SqliteTarget::Path(p) => {
@@ -91,8 +208,8 @@ impl SqliteDriver {
         .map_err(|e| DbError::DbError(format!("Failed to open connection: {}", e)))?;
 
         // Set busy_timeout FIRST to handle potential locks during PRAGMA execution
-        if timeout_secs > 0 {
-            conn.busy_timeout(Duration::from_secs(timeout_secs))
+        if busy_timeout_secs > 0 {
+            conn.busy_timeout(Duration::from_secs(busy_timeout_secs))
                 .map_err(|e| DbError::DbError(format!("Failed to set busy_timeout: {}", e)))?;
         }
 
@@ -124,6 +241,11 @@ impl Driver for SqliteDriver {
         "?".to_string()
     }
 
+    fn parser_config(&self) -> ParserConfig {
+        self.parser_config
+            .unwrap_or_else(|| crate::tpl::engine::config().parser)
+    }
+
     async fn acquire(&self) -> Result<Box<dyn Connection>> {
         let target = self.target.as_ref().ok_or_else(|| {
             DbError::DbError(
@@ -132,7 +254,9 @@ impl Driver for SqliteDriver {
         })?;
 
         let target_clone = target.clone();
-        let timeout_secs = self.options.as_ref().map(|o| o.timeout).unwrap_or(0);
+        let busy_timeout_secs = self.options.as_ref().map_or(0, |o| o.timeout);
+        let acquire_timeout_secs = self.options.as_ref().map_or(0, |o| o.acquire_timeout_secs);
+        let active = self.active.clone();
 
         // SQLite operations are synchronous. Spawn a blocking task to avoid stalling the async runtime.
         // NOTE: This creates a new physical connection per call. For high throughput, a connection pool (e.g. r2d2) is recommended.
@@ -140,17 +264,41 @@ impl Driver for SqliteDriver {
         // To share in-memory state, use a file-based URL with shared cache (e.g. "file::memory:?cache=shared") and SqliteTarget::Path.
         let handle: tokio::task::JoinHandle<Result<Box<dyn Connection>>> =
             tokio::task::spawn_blocking(move || {
-                let conn = Self::open_connection(&target_clone, timeout_secs)?;
+                let conn = Self::open_connection(&target_clone, busy_timeout_secs)?;
                 Ok::<Box<dyn Connection>, DbError>(
-                    Box::new(SqliteConnection::new(conn)) as Box<dyn Connection>
+                    Box::new(SqliteConnection::tracked(conn, active)) as Box<dyn Connection>,
                 )
             });
 
-        handle.await.map_err(|e: tokio::task::JoinError| {
+        let joined = if acquire_timeout_secs > 0 {
+            // The blocking task can't be cancelled; if it finishes late, its connection is
+            // simply dropped.
+            tokio::time::timeout(Duration::from_secs(acquire_timeout_secs), handle)
+                .await
+                .map_err(|_| {
+                    DbError::Timeout(format!(
+                        "[{}] Connection acquisition timed out (acquire_timeout_secs: {}s)",
+                        self.name, acquire_timeout_secs
+                    ))
+                })?
+        } else {
+            handle.await
+        };
+
+        joined.map_err(|e: tokio::task::JoinError| {
             DbError::DbError(format!("Task join error: {}", e))
         })?
     }
 
+    fn pool_stats(&self) -> PoolStats {
+        // Every acquire opens a fresh connection, so there are never idle or waiting ones.
+        PoolStats {
+            active_connections: self.active.load(Ordering::Relaxed),
+            max_connections: self.options.as_ref().map_or(0, |o| o.max_open_conns),
+            ..PoolStats::default()
+        }
+    }
+
     async fn close(&self) -> Result<()> {
         // No-op: connections are closed when dropped.
         Ok(())
@@ -194,4 +342,144 @@ mod tests {
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].get("name"), Some(&Value::Str("alice".to_string())));
     }
+
+    #[tokio::test]
+    async fn test_sqlite_wal_checkpoint() {
+        let path = std::env::temp_dir().join(format!(
+            "uorm_checkpoint_{}.db",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let driver = SqliteDriver::new(path.display().to_string())
+            .build()
+            .unwrap();
+
+        let mut conn = driver.acquire().await.unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY)", &[])
+            .await
+            .unwrap();
+        conn.execute("INSERT INTO t (id) VALUES (1)", &[])
+            .await
+            .unwrap();
+
+        let result = driver.checkpoint(CheckpointMode::Passive).await.unwrap();
+        assert_eq!(result.busy, 0);
+        assert_eq!(result.log, result.checkpointed);
+
+        let result = driver.checkpoint(CheckpointMode::Truncate).await.unwrap();
+        assert_eq!(result.busy, 0);
+        assert_eq!(result.log, 0);
+
+        drop(conn);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_acquire_timeout_on_locked_file() {
+        let path = std::env::temp_dir().join(format!(
+            "uorm_acquire_timeout_{}.db",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let locker = rusqlite::Connection::open(&path).unwrap();
+        locker
+            .execute_batch("CREATE TABLE t (id INTEGER); BEGIN EXCLUSIVE;")
+            .unwrap();
+
+        let driver = SqliteDriver::new(path.display().to_string())
+            .options(PoolOptions {
+                max_open_conns: 1,
+                timeout: 2,
+                acquire_timeout_secs: 1,
+                ..PoolOptions::default()
+            })
+            .build()
+            .unwrap();
+        let err = driver.acquire().await.err().unwrap();
+        assert!(matches!(err, DbError::Timeout(_)), "{:?}", err);
+
+        drop(locker);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_build_validates_pool_options() {
+        let build = |options: PoolOptions| {
+            SqliteDriver::new("sqlite::memory:")
+                .options(options)
+                .build()
+        };
+
+        assert!(matches!(
+            build(PoolOptions::default()),
+            Err(DbError::DriverError(msg)) if msg.contains("max_open_conns")
+        ));
+        assert!(matches!(
+            build(PoolOptions {
+                max_open_conns: 2,
+                max_idle_conns: 3,
+                ..PoolOptions::default()
+            }),
+            Err(DbError::DriverError(msg)) if msg.contains("max_idle_conns (3)")
+        ));
+        assert!(matches!(
+            build(PoolOptions {
+                max_open_conns: 4,
+                max_idle_conns: 2,
+                min_idle_conns: 3,
+                ..PoolOptions::default()
+            }),
+            Err(DbError::DriverError(msg)) if msg.contains("min_idle_conns (3)")
+        ));
+        assert!(
+            build(PoolOptions {
+                max_open_conns: 4,
+                max_idle_conns: 2,
+                min_idle_conns: 1,
+                ..PoolOptions::default()
+            })
+            .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pool_stats_counts_active_connections() {
+        let driver = SqliteDriver::new("sqlite::memory:")
+            .options(PoolOptions {
+                max_open_conns: 4,
+                ..PoolOptions::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(driver.pool_stats().active_connections, 0);
+
+        let a = driver.acquire().await.unwrap();
+        let b = driver.acquire().await.unwrap();
+        let stats = driver.pool_stats();
+        assert_eq!(stats.active_connections, 2);
+        assert_eq!(stats.max_connections, 4);
+
+        drop(a);
+        drop(b);
+        assert_eq!(driver.pool_stats().active_connections, 0);
+    }
+
+    #[test]
+    fn test_from_env() {
+        let vars = std::collections::HashMap::from([
+            ("SQLITE_URL", "sqlite::memory:"),
+            ("SQLITE_NAME", "from_env"),
+        ]);
+        let driver = SqliteDriver::from_lookup(|k| vars.get(k).map(|v| v.to_string())).unwrap();
+        assert_eq!(Driver::name(&driver), "from_env");
+
+        let err = SqliteDriver::from_lookup(|_| None).err().unwrap();
+        assert!(matches!(err, DbError::DbUrlError(msg) if msg.contains("SQLITE_URL")));
+    }
 }
@@ -7,6 +7,9 @@ pub fn from_sqlite_value(v: ValueRef<'_>) -> Value {
         ValueRef::Integer(i) => Value::I64(i),
         ValueRef::Real(f) => Value::F64(f),
         ValueRef::Text(b) => match std::str::from_utf8(b) {
+            #[cfg(feature = "sqlite-datetime-parse")]
+            Ok(s) => parse_datetime_text(s).unwrap_or_else(|| Value::Str(s.to_string())),
+            #[cfg(not(feature = "sqlite-datetime-parse"))]
             Ok(s) => Value::Str(s.to_string()),
             Err(_) => Value::Bytes(b.to_vec()),
         },
@@ -14,6 +17,37 @@ pub fn from_sqlite_value(v: ValueRef<'_>) -> Value {
     }
 }
 
+/// Parses ISO 8601 text (`YYYY-MM-DD`, `HH:MM:SS`, `YYYY-MM-DD HH:MM:SS`,
+/// `YYYY-MM-DDTHH:MM:SS[Z|±HH:MM]`) into the matching date/time value.
+#[cfg(feature = "sqlite-datetime-parse")]
+fn parse_datetime_text(s: &str) -> Option<Value> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    // Cheap shape check so ordinary text skips the parsers.
+    let bytes = s.as_bytes();
+    if bytes.len() < 8 || !bytes[0].is_ascii_digit() || !bytes[1].is_ascii_digit() {
+        return None;
+    }
+    match bytes.len() {
+        10 => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .map(Value::Date),
+        _ if bytes[2] == b':' => NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+            .ok()
+            .map(Value::Time),
+        _ if bytes.len() > 10 && bytes[4] == b'-' && matches!(bytes[10], b' ' | b'T') => {
+            if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+                return Some(Value::DateTimeUtc(dt.with_timezone(&Utc)));
+            }
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f"))
+                .ok()
+                .map(Value::DateTime)
+        }
+        _ => None,
+    }
+}
+
 pub fn to_sqlite_value(v: &Value) -> SqliteValue {
     match v {
         Value::Null => SqliteValue::Null,
@@ -31,7 +65,7 @@ pub fn to_sqlite_value(v: &Value) -> SqliteValue {
         Value::F32(f) => SqliteValue::Real(*f as f64),
         Value::F64(f) => SqliteValue::Real(*f),
         Value::Char(c) => SqliteValue::Text(c.to_string()),
-        Value::Str(s) => SqliteValue::Text(s.clone()),
+        Value::Str(s) | Value::Enum(s) => SqliteValue::Text(s.clone()),
         Value::Bytes(b) => SqliteValue::Blob(b.clone()),
         Value::Date(d) => SqliteValue::Text(d.to_string()),
         Value::Time(t) => SqliteValue::Text(t.to_string()),
@@ -41,3 +75,71 @@ pub fn to_sqlite_value(v: &Value) -> SqliteValue {
         Value::List(_) | Value::Map(_) => SqliteValue::Null,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::udbc::value::FromValue;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let d: Decimal = "0.1000000000000000000000000001".parse().unwrap();
+        let stored = to_sqlite_value(&Value::Decimal(d));
+        assert_eq!(stored, SqliteValue::Text(d.to_string()));
+
+        let SqliteValue::Text(text) = &stored else {
+            unreachable!()
+        };
+        let read = from_sqlite_value(ValueRef::Text(text.as_bytes()));
+        assert_eq!(Decimal::from_value(read).unwrap(), d);
+    }
+
+    #[cfg(feature = "sqlite-datetime-parse")]
+    #[test]
+    fn test_datetime_text_parsing() {
+        use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+
+        let read = |s: &str| from_sqlite_value(ValueRef::Text(s.as_bytes()));
+        let date = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let time = NaiveTime::from_hms_opt(7, 8, 9).unwrap();
+
+        assert_eq!(read("2024-05-06"), Value::Date(date));
+        assert_eq!(read("07:08:09"), Value::Time(time));
+        assert_eq!(
+            read("2024-05-06 07:08:09"),
+            Value::DateTime(date.and_time(time))
+        );
+        assert_eq!(
+            read("2024-05-06T07:08:09"),
+            Value::DateTime(date.and_time(time))
+        );
+        assert_eq!(
+            read("2024-05-06T07:08:09Z"),
+            Value::DateTimeUtc(Utc.from_utc_datetime(&date.and_time(time)))
+        );
+        assert_eq!(read("2024-13-45"), Value::Str("2024-13-45".to_string()));
+        assert_eq!(read("hello world"), Value::Str("hello world".to_string()));
+
+        // Values written by the codec read back as the same variant.
+        let dt = Value::DateTimeUtc(Utc.from_utc_datetime(&date.and_time(time)));
+        let SqliteValue::Text(text) = to_sqlite_value(&dt) else {
+            unreachable!()
+        };
+        assert_eq!(read(&text), dt);
+    }
+
+    #[cfg(feature = "sqlite-datetime-parse")]
+    #[test]
+    fn test_parsed_datetime_reads_as_string() {
+        let read = |s: &str| from_sqlite_value(ValueRef::Text(s.as_bytes()));
+
+        for text in ["2024-05-06", "07:08:09", "2024-05-06 07:08:09"] {
+            assert_eq!(String::from_value(read(text)).unwrap(), text);
+        }
+        assert_eq!(
+            Option::<String>::from_value(read("2024-05-06T07:08:09Z")).unwrap(),
+            Some("2024-05-06T07:08:09+00:00".to_string())
+        );
+    }
+}
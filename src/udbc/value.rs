@@ -1,7 +1,12 @@
 use crate::error::DbError;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use rust_decimal::prelude::FromPrimitive;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
@@ -42,11 +47,410 @@ pub enum Value {
 
     /// Key-value map (e.g. structs, JSON objects)
     Map(HashMap<String, Value>),
+
+    /// Value of an ENUM-typed column or a Rust enum (the variant name)
+    Enum(String),
+}
+
+/// The variant of a [`Value`], without its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Null,
+    Bool,
+    Char,
+    Str,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Bytes,
+    Date,
+    Time,
+    DateTime,
+    DateTimeUtc,
+    Decimal,
+    List,
+    Map,
+    Enum,
+}
+
+macro_rules! cast_int {
+    ($v:expr, $rust_type:ty, $variant:ident) => {{
+        let v = $v;
+        let out_of_range = |n: &dyn std::fmt::Display| {
+            DbError::TypeMismatch(format!(
+                "Value {} out of range for {}",
+                n,
+                stringify!($rust_type)
+            ))
+        };
+        let n: $rust_type = match v {
+            Value::I8(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::I16(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::I32(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::I64(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::I128(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::U8(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::U16(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::U32(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::U64(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::U128(n) => <$rust_type>::try_from(*n).map_err(|_| out_of_range(n))?,
+            Value::Str(s) => s.trim().parse::<$rust_type>().map_err(|e| {
+                DbError::TypeMismatch(format!(
+                    "Cannot parse {:?} as {}: {}",
+                    s,
+                    stringify!($rust_type),
+                    e
+                ))
+            })?,
+            _ => {
+                return Err(DbError::TypeMismatch(format!(
+                    "Expected numeric value, got {:?}",
+                    v
+                )));
+            }
+        };
+        Value::$variant(n)
+    }};
+}
+
+impl Value {
+    /// Returns the kind of this value.
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Null => ValueKind::Null,
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Char(_) => ValueKind::Char,
+            Value::Str(_) => ValueKind::Str,
+            Value::I8(_) => ValueKind::I8,
+            Value::I16(_) => ValueKind::I16,
+            Value::I32(_) => ValueKind::I32,
+            Value::I64(_) => ValueKind::I64,
+            Value::I128(_) => ValueKind::I128,
+            Value::U8(_) => ValueKind::U8,
+            Value::U16(_) => ValueKind::U16,
+            Value::U32(_) => ValueKind::U32,
+            Value::U64(_) => ValueKind::U64,
+            Value::U128(_) => ValueKind::U128,
+            Value::F32(_) => ValueKind::F32,
+            Value::F64(_) => ValueKind::F64,
+            Value::Bytes(_) => ValueKind::Bytes,
+            Value::Date(_) => ValueKind::Date,
+            Value::Time(_) => ValueKind::Time,
+            Value::DateTime(_) => ValueKind::DateTime,
+            Value::DateTimeUtc(_) => ValueKind::DateTimeUtc,
+            Value::Decimal(_) => ValueKind::Decimal,
+            Value::List(_) => ValueKind::List,
+            Value::Map(_) => ValueKind::Map,
+            Value::Enum(_) => ValueKind::Enum,
+        }
+    }
+
+    /// Returns `true` for [`Value::Null`].
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Returns the entries of a `Map`, or `None` for any other variant.
+    pub fn as_map(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Returns the items of a `List`, or `None` for any other variant.
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a `Str` or `Enum`, or `None` for any other variant.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) | Value::Enum(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i64` if [`Value::try_cast_to`] can convert it.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.try_cast_to(ValueKind::I64) {
+            Ok(Value::I64(n)) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Returns the entry `key` of a `Map`, or `None` if it's missing or `self` isn't a `Map`.
+    ///
+    /// Chains through nested maps: `row.get("user")?.get("name")`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_map()?.get(key)
+    }
+
+    /// Mutable version of [`Value::get`].
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match self {
+            Value::Map(m) => m.get_mut(key),
+            _ => None,
+        }
+    }
+
+    /// Returns item `idx` of a `List`, or `None` if it's out of range or `self` isn't a `List`.
+    pub fn get_index(&self, idx: usize) -> Option<&Value> {
+        self.as_list()?.get(idx)
+    }
+
+    /// Returns the value of a `Bool`, or `None` for any other variant.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Consumes a `Map` and returns its entries.
+    ///
+    /// # Errors
+    /// `DbError::TypeMismatch` for any other variant.
+    pub fn into_map(self) -> Result<HashMap<String, Value>, DbError> {
+        match self {
+            Value::Map(m) => Ok(m),
+            other => Err(DbError::TypeMismatch(format!(
+                "Expected Map, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Consumes a `List` and returns its items.
+    ///
+    /// # Errors
+    /// `DbError::TypeMismatch` for any other variant.
+    pub fn into_list(self) -> Result<Vec<Value>, DbError> {
+        match self {
+            Value::List(l) => Ok(l),
+            other => Err(DbError::TypeMismatch(format!(
+                "Expected List, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Converts the value into a `String`, with the same rules as `String::from_value`.
+    pub fn into_str(self) -> Result<String, DbError> {
+        String::from_value(self)
+    }
+
+    /// Converts the value into an `i64`, with the same rules as `i64::from_value`.
+    pub fn into_i64(self) -> Result<i64, DbError> {
+        i64::from_value(self)
+    }
+
+    /// Renders this value for logs without leaking long strings or binary data.
+    ///
+    /// Strings longer than `max_string_len` characters become `<truncated>`, `Bytes` become
+    /// `<bytes N bytes>`, and `List` / `Map` contents are rendered recursively (map keys sorted).
+    pub fn display_safe(&self, max_string_len: usize) -> String {
+        match self {
+            Value::Null => "NULL".to_string(),
+            Value::Str(s) if s.chars().count() > max_string_len => "<truncated>".to_string(),
+            Value::Str(s) => s.clone(),
+            Value::Bytes(b) => format!("<bytes {} bytes>", b.len()),
+            Value::List(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|v| v.display_safe(max_string_len))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            Value::Map(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                let entries: Vec<String> = entries
+                    .into_iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.display_safe(max_string_len)))
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            other => other.text_form().unwrap_or_else(|| format!("{:?}", other)),
+        }
+    }
+
+    /// The text form of a scalar, as written into SQL text and logs. `None` for `Null`,
+    /// `Bytes` that aren't UTF-8, `List` and `Map`.
+    pub(crate) fn text_form(&self) -> Option<String> {
+        Some(match self {
+            Value::Str(s) | Value::Enum(s) => s.clone(),
+            Value::Bytes(b) => String::from_utf8(b.clone()).ok()?,
+            Value::Char(c) => c.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::I8(n) => n.to_string(),
+            Value::I16(n) => n.to_string(),
+            Value::I32(n) => n.to_string(),
+            Value::I64(n) => n.to_string(),
+            Value::I128(n) => n.to_string(),
+            Value::U8(n) => n.to_string(),
+            Value::U16(n) => n.to_string(),
+            Value::U32(n) => n.to_string(),
+            Value::U64(n) => n.to_string(),
+            Value::U128(n) => n.to_string(),
+            Value::F32(f) => f.to_string(),
+            Value::F64(f) => f.to_string(),
+            Value::Decimal(d) => d.to_string(),
+            Value::Date(d) => d.to_string(),
+            Value::Time(t) => t.to_string(),
+            Value::DateTime(dt) => dt.to_string(),
+            Value::DateTimeUtc(dt) => dt.to_rfc3339(),
+            Value::Null | Value::List(_) | Value::Map(_) => return None,
+        })
+    }
+
+    /// Converts this value to the given kind.
+    ///
+    /// All scalar coercion rules live here: integers convert between widths with a
+    /// range check, `F32`/`F64` convert into each other, `0`/`1` integers become booleans,
+    /// text is parsed into numbers, booleans (`"true"`/`"false"`/`"1"`/`"0"`), dates and
+    /// decimals, and `Bytes` are read as UTF-8 text. `List` and `Map` only "cast" to
+    /// themselves; structural decomposition is left to `FromValue`.
+    pub fn try_cast_to(&self, kind: ValueKind) -> Result<Value, DbError> {
+        if self.kind() == kind {
+            return Ok(self.clone());
+        }
+
+        let mismatch = || DbError::TypeMismatch(format!("Cannot cast {:?} to {:?}", self, kind));
+
+        let value = match kind {
+            ValueKind::I8 => cast_int!(self, i8, I8),
+            ValueKind::I16 => cast_int!(self, i16, I16),
+            ValueKind::I32 => cast_int!(self, i32, I32),
+            ValueKind::I64 => cast_int!(self, i64, I64),
+            ValueKind::I128 => cast_int!(self, i128, I128),
+            ValueKind::U8 => cast_int!(self, u8, U8),
+            ValueKind::U16 => cast_int!(self, u16, U16),
+            ValueKind::U32 => cast_int!(self, u32, U32),
+            ValueKind::U64 => cast_int!(self, u64, U64),
+            ValueKind::U128 => cast_int!(self, u128, U128),
+            ValueKind::F32 => match self.try_cast_to(ValueKind::F64)? {
+                Value::F64(f) => Value::F32(f as f32),
+                _ => return Err(mismatch()),
+            },
+            ValueKind::F64 => Value::F64(match self {
+                Value::F32(f) => *f as f64,
+                Value::Str(s) => s.trim().parse::<f64>().map_err(|_| mismatch())?,
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::Bool => Value::Bool(match self {
+                Value::Str(s) => match s.trim().to_lowercase().as_str() {
+                    "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => return Err(mismatch()),
+                },
+                Value::I32(1) | Value::I64(1) => true,
+                Value::I32(0) | Value::I64(0) => false,
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::Str => Value::Str(match self {
+                Value::Bytes(b) => String::from_utf8(b.clone())
+                    .map_err(|e| DbError::TypeMismatch(format!("Invalid UTF-8 bytes: {}", e)))?,
+                Value::Enum(e) => e.clone(),
+                // SQLite text timestamps may already have been parsed by the codec.
+                Value::Date(_) | Value::Time(_) | Value::DateTime(_) | Value::DateTimeUtc(_) => {
+                    self.text_form().ok_or_else(mismatch)?
+                }
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::Decimal => Value::Decimal(match self {
+                Value::Str(s) => s.trim().parse::<Decimal>().map_err(|_| mismatch())?,
+                Value::Bytes(b) => std::str::from_utf8(b)
+                    .ok()
+                    .and_then(|s| s.trim().parse::<Decimal>().ok())
+                    .ok_or_else(mismatch)?,
+                Value::F32(f) => Decimal::try_from(*f).map_err(|_| mismatch())?,
+                Value::F64(f) => Decimal::try_from(*f).map_err(|_| mismatch())?,
+                _ => match self.try_cast_to(ValueKind::I128) {
+                    Ok(Value::I128(n)) => Decimal::from_i128(n).ok_or_else(mismatch)?,
+                    _ => return Err(mismatch()),
+                },
+            }),
+            ValueKind::Date => Value::Date(match self {
+                Value::Str(s) => s.trim().parse().map_err(|_| mismatch())?,
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::Time => Value::Time(match self {
+                Value::Str(s) => s.trim().parse().map_err(|_| mismatch())?,
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::DateTime => Value::DateTime(match self {
+                Value::Str(s) => parse_naive_datetime(s).ok_or_else(mismatch)?,
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::DateTimeUtc => Value::DateTimeUtc(match self {
+                Value::Str(s) => match s.trim().parse() {
+                    Ok(dt) => dt,
+                    // Text without an offset (e.g. SQLite `CURRENT_TIMESTAMP`) is taken as UTC.
+                    Err(_) => parse_naive_datetime(s).ok_or_else(mismatch)?.and_utc(),
+                },
+                // MySQL `DATETIME` columns carry no offset.
+                Value::DateTime(dt) => dt.and_utc(),
+                _ => return Err(mismatch()),
+            }),
+            ValueKind::Enum => {
+                match self {
+                    Value::Str(s) => Value::Enum(s.clone()),
+                    Value::Bytes(b) => Value::Enum(String::from_utf8(b.clone()).map_err(|e| {
+                        DbError::TypeMismatch(format!("Invalid UTF-8 bytes: {}", e))
+                    })?),
+                    _ => return Err(mismatch()),
+                }
+            }
+            ValueKind::Null
+            | ValueKind::Char
+            | ValueKind::Bytes
+            | ValueKind::List
+            | ValueKind::Map => return Err(mismatch()),
+        };
+        Ok(value)
+    }
 }
 
+/// Parses `YYYY-MM-DDTHH:MM:SS[.f]` or the space-separated form produced by
+/// `NaiveDateTime::to_string` and SQLite's `CURRENT_TIMESTAMP`.
+fn parse_naive_datetime(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    s.parse()
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").ok())
+}
+
+/// Placeholder written to logs in place of `#[param(sensitive)]` fields.
+pub const REDACTED: &str = "***REDACTED***";
+
 /// 任何能转换为 Value 的类型
 pub trait ToValue {
     fn to_value(&self) -> Value;
+
+    /// The value to show in log output. Defaults to [`ToValue::to_value`]; `#[derive(Param)]`
+    /// overrides it to replace `#[param(sensitive)]` fields with [`REDACTED`].
+    fn to_log_value(&self) -> Value {
+        self.to_value()
+    }
+
+    /// Borrows the value when `self` already is one, so passing a [`Value`] as arguments
+    /// doesn't copy it. Defaults to an owned [`ToValue::to_value`].
+    fn as_value(&self) -> Cow<'_, Value> {
+        Cow::Owned(self.to_value())
+    }
 }
 
 /// 任何能从 Value 还原的类型
@@ -65,84 +469,40 @@ macro_rules! impl_to_value_primitive {
     };
 }
 
-macro_rules! impl_from_value_int {
-    ($rust_type:ty) => {
+macro_rules! impl_from_value_cast {
+    ($rust_type:ty, $variant:ident) => {
         impl FromValue for $rust_type {
             fn from_value(v: Value) -> Result<Self, DbError> {
-                match v {
-                    Value::I8(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::I16(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::I32(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::I64(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::I128(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::U8(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::U16(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::U32(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::U64(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    Value::U128(n) => <$rust_type>::try_from(n).map_err(|_| {
-                        DbError::TypeMismatch(format!(
-                            "Value {} out of range for {}",
-                            n,
-                            stringify!($rust_type)
-                        ))
-                    }),
-                    _ => Err(DbError::TypeMismatch(format!(
+                match v.try_cast_to(ValueKind::$variant)? {
+                    Value::$variant(n) => Ok(n),
+                    other => Err(DbError::TypeMismatch(format!(
+                        "Expected {}, got {:?}",
+                        stringify!($variant),
+                        other
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+// Numbers are read from numeric columns only; parsing text is left to `try_cast_to`.
+macro_rules! impl_from_value_number {
+    ($rust_type:ty, $variant:ident) => {
+        impl FromValue for $rust_type {
+            fn from_value(v: Value) -> Result<Self, DbError> {
+                if let Value::Str(_) = v {
+                    return Err(DbError::TypeMismatch(format!(
                         "Expected numeric value, got {:?}",
                         v
+                    )));
+                }
+                match v.try_cast_to(ValueKind::$variant)? {
+                    Value::$variant(n) => Ok(n),
+                    other => Err(DbError::TypeMismatch(format!(
+                        "Expected {}, got {:?}",
+                        stringify!($variant),
+                        other
                     ))),
                 }
             }
@@ -152,50 +512,15 @@ macro_rules! impl_from_value_int {
 
 // bool 类型的特殊处理
 impl_to_value_primitive!(bool, Bool);
-impl FromValue for bool {
-    fn from_value(v: Value) -> Result<Self, DbError> {
-        match v {
-            Value::Bool(b) => Ok(b),
-            // 生产级增强：支持从整数 0/1 转换
-            Value::I32(1) => Ok(true),
-            Value::I32(0) => Ok(false),
-            Value::I64(1) => Ok(true),
-            Value::I64(0) => Ok(false),
-            // 生产级增强：支持从字符串 "true"/"false" 转换
-            Value::Str(s) if s.to_lowercase() == "true" => Ok(true),
-            Value::Str(s) if s.to_lowercase() == "false" => Ok(false),
-            _ => Err(DbError::TypeMismatch(format!("Expected Bool, got {:?}", v))),
-        }
-    }
-}
+impl_from_value_cast!(bool, Bool);
 
 // char 类型的特殊处理
 impl_to_value_primitive!(char, Char);
-impl FromValue for char {
-    fn from_value(v: Value) -> Result<Self, DbError> {
-        if let Value::Char(val) = v {
-            Ok(val)
-        } else {
-            Err(DbError::TypeMismatch(format!("Expected Char, got {:?}", v)))
-        }
-    }
-}
+impl_from_value_cast!(char, Char);
 
 // string 类型的特殊处理
 impl_to_value_primitive!(String, Str);
-impl FromValue for String {
-    fn from_value(v: Value) -> Result<Self, DbError> {
-        match v {
-            Value::Str(s) => Ok(s),
-            Value::Bytes(b) => String::from_utf8(b)
-                .map_err(|e| DbError::TypeMismatch(format!("Invalid UTF-8 bytes: {}", e))),
-            _ => Err(DbError::TypeMismatch(format!(
-                "Expected Str or Bytes, got {:?}",
-                v
-            ))),
-        }
-    }
-}
+impl_from_value_cast!(String, Str);
 impl ToValue for &str {
     fn to_value(&self) -> Value {
         Value::Str(self.to_string())
@@ -214,42 +539,85 @@ impl_to_value_primitive!(u32, U32);
 impl_to_value_primitive!(u64, U64);
 impl_to_value_primitive!(u128, U128);
 
-impl_from_value_int!(i8);
-impl_from_value_int!(i16);
-impl_from_value_int!(i32);
-impl_from_value_int!(i64);
-impl_from_value_int!(i128);
-impl_from_value_int!(u8);
-impl_from_value_int!(u16);
-impl_from_value_int!(u32);
-impl_from_value_int!(u64);
-impl_from_value_int!(u128);
+impl_from_value_number!(i8, I8);
+impl_from_value_number!(i16, I16);
+impl_from_value_number!(i32, I32);
+impl_from_value_number!(i64, I64);
+impl_from_value_number!(i128, I128);
+impl_from_value_number!(u8, U8);
+impl_from_value_number!(u16, U16);
+impl_from_value_number!(u32, U32);
+impl_from_value_number!(u64, U64);
+impl_from_value_number!(u128, U128);
 
 // float 类型的特殊处理
 impl_to_value_primitive!(f32, F32);
-impl FromValue for f32 {
-    fn from_value(v: Value) -> Result<Self, DbError> {
-        if let Value::F32(val) = v {
-            Ok(val)
-        } else if let Value::F64(val) = v {
-            Ok(val as f32)
-        } else {
-            Err(DbError::TypeMismatch(format!("Expected F32, got {:?}", v)))
-        }
-    }
-}
+impl_from_value_number!(f32, F32);
 
 // double 类型的特殊处理
 impl_to_value_primitive!(f64, F64);
-impl FromValue for f64 {
+impl_from_value_number!(f64, F64);
+
+// 日期时间类型：SQLite 以文本存储，读取时通过 try_cast_to 解析
+impl_to_value_primitive!(NaiveDate, Date);
+impl_from_value_cast!(NaiveDate, Date);
+impl_to_value_primitive!(NaiveTime, Time);
+impl_from_value_cast!(NaiveTime, Time);
+impl_to_value_primitive!(NaiveDateTime, DateTime);
+impl_from_value_cast!(NaiveDateTime, DateTime);
+impl_to_value_primitive!(DateTime<Utc>, DateTimeUtc);
+impl_from_value_cast!(DateTime<Utc>, DateTimeUtc);
+
+// decimal 类型：数据库常以文本返回，读取时通过 try_cast_to 解析
+impl_to_value_primitive!(Decimal, Decimal);
+impl_from_value_cast!(Decimal, Decimal);
+
+// Network addresses: stored as their text form and parsed back with `FromStr`.
+macro_rules! impl_value_via_string {
+    ($($rust_type:ty),* $(,)?) => {
+        $(
+            impl ToValue for $rust_type {
+                fn to_value(&self) -> Value {
+                    Value::Str(self.to_string())
+                }
+            }
+            impl FromValue for $rust_type {
+                fn from_value(v: Value) -> Result<Self, DbError> {
+                    let s = String::from_value(v)?;
+                    s.parse().map_err(|e| {
+                        DbError::TypeMismatch(format!(
+                            "Invalid {} '{}': {}",
+                            stringify!($rust_type),
+                            s,
+                            e
+                        ))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_value_via_string!(
+    std::net::IpAddr,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+    std::net::SocketAddr,
+);
+
+// Duration: stored as whole milliseconds in an `I64`, the canonical representation until a
+// dedicated interval variant exists. Sub-millisecond precision is dropped.
+impl ToValue for std::time::Duration {
+    fn to_value(&self) -> Value {
+        Value::I64(i64::try_from(self.as_millis()).unwrap_or(i64::MAX))
+    }
+}
+impl FromValue for std::time::Duration {
     fn from_value(v: Value) -> Result<Self, DbError> {
-        if let Value::F64(val) = v {
-            Ok(val)
-        } else if let Value::F32(val) = v {
-            Ok(val as f64)
-        } else {
-            Err(DbError::TypeMismatch(format!("Expected F64, got {:?}", v)))
-        }
+        let ms = i64::from_value(v)?;
+        let ms = u64::try_from(ms)
+            .map_err(|_| DbError::TypeMismatch(format!("Negative duration: {}ms", ms)))?;
+        Ok(std::time::Duration::from_millis(ms))
     }
 }
 
@@ -258,6 +626,10 @@ impl ToValue for Value {
     fn to_value(&self) -> Value {
         self.clone()
     }
+
+    fn as_value(&self) -> Cow<'_, Value> {
+        Cow::Borrowed(self)
+    }
 }
 
 // Allow Value to be returned as result
@@ -267,6 +639,30 @@ impl FromValue for Value {
     }
 }
 
+/// Parses a literal the way it would appear in a config file or payload: an integer becomes
+/// `I64`, a finite float `F64`, `true`/`false` `Bool` and `null` `Null` (all ignoring ASCII
+/// case); anything else is kept as `Str`.
+impl std::str::FromStr for Value {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(n) = s.parse::<i64>() {
+            return Ok(Value::I64(n));
+        }
+        if let Ok(f) = s.parse::<f64>()
+            && f.is_finite()
+        {
+            return Ok(Value::F64(f));
+        }
+        Ok(match s {
+            s if s.eq_ignore_ascii_case("true") => Value::Bool(true),
+            s if s.eq_ignore_ascii_case("false") => Value::Bool(false),
+            s if s.eq_ignore_ascii_case("null") => Value::Null,
+            s => Value::Str(s.to_string()),
+        })
+    }
+}
+
 // Implement FromValue for unit type () to allow functions returning Result<()>
 impl FromValue for () {
     fn from_value(_v: Value) -> Result<Self, DbError> {
@@ -288,6 +684,44 @@ where
     fn to_value(&self) -> Value {
         (**self).to_value()
     }
+
+    fn to_log_value(&self) -> Value {
+        (**self).to_log_value()
+    }
+
+    fn as_value(&self) -> Cow<'_, Value> {
+        (**self).as_value()
+    }
+}
+
+// Smart pointers delegate to the pointee. `Rc` is not `Send`, so an `Rc` argument can't be
+// held across an `.await` inside a future that must be `Send` (e.g. `tokio::spawn`).
+macro_rules! impl_to_value_deref {
+    ($($ptr:ident),+) => {
+        $(
+            impl<T: ToValue + ?Sized> ToValue for $ptr<T> {
+                fn to_value(&self) -> Value {
+                    (**self).to_value()
+                }
+
+                fn to_log_value(&self) -> Value {
+                    (**self).to_log_value()
+                }
+
+                fn as_value(&self) -> Cow<'_, Value> {
+                    (**self).as_value()
+                }
+            }
+        )+
+    };
+}
+
+impl_to_value_deref!(Box, Arc, Rc);
+
+impl<T: FromValue> FromValue for Box<T> {
+    fn from_value(v: Value) -> Result<Self, DbError> {
+        T::from_value(v).map(Box::new)
+    }
 }
 
 // Option
@@ -298,6 +732,13 @@ impl<T: ToValue> ToValue for Option<T> {
             None => Value::Null,
         }
     }
+
+    fn to_log_value(&self) -> Value {
+        match self {
+            Some(v) => v.to_log_value(),
+            None => Value::Null,
+        }
+    }
 }
 impl<T: FromValue> FromValue for Option<T> {
     fn from_value(v: Value) -> Result<Self, DbError> {
@@ -323,6 +764,10 @@ impl<T: ToValue> ToValue for Vec<T> {
     fn to_value(&self) -> Value {
         Value::List(self.iter().map(|v| v.to_value()).collect())
     }
+
+    fn to_log_value(&self) -> Value {
+        Value::List(self.iter().map(|v| v.to_log_value()).collect())
+    }
 }
 impl<T: FromValue> FromValue for Vec<T> {
     fn from_value(v: Value) -> Result<Self, DbError> {
@@ -333,8 +778,58 @@ impl<T: FromValue> FromValue for Vec<T> {
     }
 }
 
-// HashMap
-impl<T: ToValue> ToValue for HashMap<String, T> {
+// Slices and arrays; `&[T]` is covered by the blanket `&T` impl.
+impl<T: ToValue> ToValue for [T] {
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|v| v.to_value()).collect())
+    }
+
+    fn to_log_value(&self) -> Value {
+        Value::List(self.iter().map(|v| v.to_log_value()).collect())
+    }
+}
+
+impl<T: ToValue, const N: usize> ToValue for [T; N] {
+    fn to_value(&self) -> Value {
+        self.as_slice().to_value()
+    }
+
+    fn to_log_value(&self) -> Value {
+        self.as_slice().to_log_value()
+    }
+}
+
+// VecDeque
+impl<T: ToValue> ToValue for VecDeque<T> {
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|v| v.to_value()).collect())
+    }
+}
+impl<T: FromValue> FromValue for VecDeque<T> {
+    fn from_value(v: Value) -> Result<Self, DbError> {
+        match v {
+            Value::List(l) => l.into_iter().map(T::from_value).collect(),
+            _ => Err(DbError::TypeMismatch(format!("Expected List, got {:?}", v))),
+        }
+    }
+}
+
+// HashSet: 列表顺序不确定
+impl<T: ToValue, S> ToValue for HashSet<T, S> {
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|v| v.to_value()).collect())
+    }
+}
+
+// BTreeSet: 按元素顺序输出
+impl<T: ToValue> ToValue for BTreeSet<T> {
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|v| v.to_value()).collect())
+    }
+}
+
+// HashMap (any hasher)
+impl<T: ToValue, S> ToValue for HashMap<String, T, S> {
     fn to_value(&self) -> Value {
         let mut map = HashMap::new();
         for (k, v) in self {
@@ -343,11 +838,11 @@ impl<T: ToValue> ToValue for HashMap<String, T> {
         Value::Map(map)
     }
 }
-impl<T: FromValue> FromValue for HashMap<String, T> {
+impl<T: FromValue, S: BuildHasher + Default> FromValue for HashMap<String, T, S> {
     fn from_value(v: Value) -> Result<Self, DbError> {
         match v {
             Value::Map(m) => {
-                let mut out = HashMap::new();
+                let mut out = HashMap::with_capacity_and_hasher(m.len(), S::default());
                 for (k, val) in m {
                     out.insert(k, T::from_value(val)?);
                 }
@@ -357,3 +852,594 @@ impl<T: FromValue> FromValue for HashMap<String, T> {
         }
     }
 }
+
+// BTreeMap
+impl<T: ToValue> ToValue for BTreeMap<String, T> {
+    fn to_value(&self) -> Value {
+        Value::Map(
+            self.iter()
+                .map(|(k, v)| (k.clone(), v.to_value()))
+                .collect(),
+        )
+    }
+}
+impl<T: FromValue> FromValue for BTreeMap<String, T> {
+    fn from_value(v: Value) -> Result<Self, DbError> {
+        match v {
+            Value::Map(m) => m
+                .into_iter()
+                .map(|(k, val)| Ok((k, T::from_value(val)?)))
+                .collect(),
+            _ => Err(DbError::TypeMismatch(format!("Expected Map, got {:?}", v))),
+        }
+    }
+}
+
+// Tuple
+macro_rules! impl_value_tuple {
+    ($len:expr; $($name:ident),+) => {
+        impl<$($name: ToValue),+> ToValue for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn to_value(&self) -> Value {
+                let ($($name,)+) = self;
+                Value::List(vec![$($name.to_value()),+])
+            }
+        }
+
+        impl<$($name: FromValue),+> FromValue for ($($name,)+) {
+            fn from_value(v: Value) -> Result<Self, DbError> {
+                match v {
+                    Value::List(l) => {
+                        if l.len() < $len {
+                            return Err(DbError::TypeMismatch(format!(
+                                "Expected List of at least {} elements, got {}",
+                                $len,
+                                l.len()
+                            )));
+                        }
+                        let mut iter = l.into_iter();
+                        Ok(($($name::from_value(iter.next().unwrap())?,)+))
+                    }
+                    _ => Err(DbError::TypeMismatch(format!("Expected List, got {:?}", v))),
+                }
+            }
+        }
+    };
+}
+
+impl_value_tuple!(1; T1);
+impl_value_tuple!(2; T1, T2);
+impl_value_tuple!(3; T1, T2, T3);
+impl_value_tuple!(4; T1, T2, T3, T4);
+impl_value_tuple!(5; T1, T2, T3, T4, T5);
+impl_value_tuple!(6; T1, T2, T3, T4, T5, T6);
+impl_value_tuple!(7; T1, T2, T3, T4, T5, T6, T7);
+impl_value_tuple!(8; T1, T2, T3, T4, T5, T6, T7, T8);
+
+// --- serde 支持 ---
+// 日期时间与 Decimal 序列化为字符串，Decimal 借此保留完整精度。
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{SerializeMap, SerializeSeq};
+
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Char(c) => serializer.serialize_char(*c),
+            Value::Str(s) | Value::Enum(s) => serializer.serialize_str(s),
+            Value::I8(n) => serializer.serialize_i8(*n),
+            Value::I16(n) => serializer.serialize_i16(*n),
+            Value::I32(n) => serializer.serialize_i32(*n),
+            Value::I64(n) => serializer.serialize_i64(*n),
+            Value::I128(n) => serializer.serialize_i128(*n),
+            Value::U8(n) => serializer.serialize_u8(*n),
+            Value::U16(n) => serializer.serialize_u16(*n),
+            Value::U32(n) => serializer.serialize_u32(*n),
+            Value::U64(n) => serializer.serialize_u64(*n),
+            Value::U128(n) => serializer.serialize_u128(*n),
+            Value::F32(f) => serializer.serialize_f32(*f),
+            Value::F64(f) => serializer.serialize_f64(*f),
+            Value::Bytes(b) => serializer.serialize_bytes(b),
+            Value::Date(d) => serializer.collect_str(d),
+            Value::Time(t) => serializer.collect_str(t),
+            Value::DateTime(dt) => serializer.collect_str(dt),
+            Value::DateTimeUtc(dt) => serializer.serialize_str(&dt.to_rfc3339()),
+            Value::Decimal(d) => serializer.collect_str(d),
+            Value::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Value::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (k, v) in entries {
+                    map.serialize_entry(k, v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any self-describing value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::I64(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Value, E> {
+        Ok(Value::I128(v))
+    }
+
+    // 多数格式不区分正整数的符号，能放入 i64 的值统一为 I64，与数据库读取结果一致
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(i64::try_from(v).map_or(Value::U64(v), Value::I64))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Value, E> {
+        Ok(Value::U128(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::F64(v))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Value, E> {
+        Ok(Value::Char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::Str(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::Str(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Value, E> {
+        Ok(Value::Bytes(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: serde::Deserializer<'de>>(self, d: D) -> Result<Value, D::Error> {
+        serde::Deserialize::deserialize(d)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::List(items))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let mut map = HashMap::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some((k, v)) = access.next_entry::<String, Value>()? {
+            map.insert(k, v);
+        }
+        Ok(Value::Map(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_value_borrows_values() {
+        let v = Value::Map(HashMap::from([("id".to_string(), Value::I64(1))]));
+        assert!(matches!(v.as_value(), Cow::Borrowed(b) if std::ptr::eq(b, &v)));
+        assert!(matches!((&&v).as_value(), Cow::Borrowed(_)));
+        assert!(matches!(1i64.as_value(), Cow::Owned(Value::I64(1))));
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        let t = (1i64, "a".to_string(), true);
+        let v = t.to_value();
+        assert_eq!(
+            v,
+            Value::List(vec![
+                Value::I64(1),
+                Value::Str("a".to_string()),
+                Value::Bool(true)
+            ])
+        );
+        let back = <(i64, String, bool)>::from_value(v).unwrap();
+        assert_eq!(back, t);
+    }
+
+    #[test]
+    fn test_tuple_from_short_list() {
+        let v = Value::List(vec![Value::I64(1)]);
+        let err = <(i64, i32)>::from_value(v).unwrap_err();
+        assert!(matches!(err, DbError::TypeMismatch(_)));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let mut map = HashMap::new();
+        map.insert("id".to_string(), Value::I32(7));
+        let v = Value::Map(map);
+        assert_eq!(v.as_map().unwrap()["id"].as_i64(), Some(7));
+        assert!(v.as_list().is_none());
+        assert!(matches!(
+            Value::Null.into_map(),
+            Err(DbError::TypeMismatch(_))
+        ));
+        let items = Value::List(vec![Value::Str("a".to_string())])
+            .into_list()
+            .unwrap();
+        assert_eq!(items[0].as_str(), Some("a"));
+        assert_eq!(Value::I32(42).into_i64().unwrap(), 42);
+        assert_eq!(Value::Bytes(b"3".to_vec()).into_str().unwrap(), "3");
+        assert!(Value::Null.is_null());
+        assert_eq!(Value::Bool(true).as_bool(), Some(true));
+    }
+
+    #[test]
+    fn test_get_nested() {
+        let mut user = HashMap::new();
+        user.insert("name".to_string(), Value::Str("Alice".to_string()));
+        user.insert(
+            "tags".to_string(),
+            Value::List(vec![Value::Str("admin".to_string())]),
+        );
+        let mut root = HashMap::new();
+        root.insert("user".to_string(), Value::Map(user));
+        let mut v = Value::Map(root);
+
+        let name = v.get("user").and_then(|u| u.get("name"));
+        assert_eq!(name.and_then(Value::as_str), Some("Alice"));
+        let tag = v
+            .get("user")
+            .and_then(|u| u.get("tags"))
+            .and_then(|t| t.get_index(0));
+        assert_eq!(tag.and_then(Value::as_str), Some("admin"));
+        assert!(v.get("missing").is_none());
+        assert!(v.get_index(0).is_none());
+        assert!(Value::I32(1).get("user").is_none());
+
+        *v.get_mut("user").unwrap().get_mut("name").unwrap() = Value::Str("Bob".to_string());
+        assert_eq!(
+            v.get("user").unwrap().get("name").unwrap().as_str(),
+            Some("Bob")
+        );
+    }
+
+    #[test]
+    fn test_parse_literal() {
+        let parse = |s: &str| s.parse::<Value>().unwrap();
+        assert_eq!(parse("42"), Value::I64(42));
+        assert_eq!(parse("-7"), Value::I64(-7));
+        assert_eq!(parse("2.5"), Value::F64(2.5));
+        assert_eq!(parse("true"), Value::Bool(true));
+        assert_eq!(parse("FALSE"), Value::Bool(false));
+        assert_eq!(parse("null"), Value::Null);
+        assert_eq!(parse("NaN"), Value::Str("NaN".to_string()));
+        assert_eq!(parse("inf"), Value::Str("inf".to_string()));
+        assert_eq!(parse(""), Value::Str(String::new()));
+        assert_eq!(parse("alice"), Value::Str("alice".to_string()));
+    }
+
+    #[test]
+    fn test_net_addresses() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        assert_eq!(ip.to_value(), Value::Str("10.0.0.1".to_string()));
+        assert_eq!(IpAddr::from_value(ip.to_value()).unwrap(), ip);
+        assert_eq!(
+            Ipv6Addr::from_value(Value::Str("::1".to_string())).unwrap(),
+            Ipv6Addr::LOCALHOST
+        );
+        assert_eq!(
+            Ipv4Addr::from_value(Value::Str("127.0.0.1".to_string())).unwrap(),
+            Ipv4Addr::LOCALHOST
+        );
+        let addr: SocketAddr = "[::1]:5432".parse().unwrap();
+        assert_eq!(SocketAddr::from_value(addr.to_value()).unwrap(), addr);
+        assert!(matches!(
+            Ipv4Addr::from_value(Value::Str("::1".to_string())),
+            Err(DbError::TypeMismatch(msg)) if msg.contains("Ipv4Addr")
+        ));
+    }
+
+    #[test]
+    fn test_duration_as_millis() {
+        let d = std::time::Duration::from_micros(1_500_900);
+        assert_eq!(d.to_value(), Value::I64(1500));
+        assert_eq!(
+            std::time::Duration::from_value(Value::I64(1500)).unwrap(),
+            std::time::Duration::from_millis(1500)
+        );
+        assert!(matches!(
+            std::time::Duration::from_value(Value::I64(-1)),
+            Err(DbError::TypeMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_try_cast_to() {
+        assert_eq!(
+            Value::Str("42".to_string())
+                .try_cast_to(ValueKind::I64)
+                .unwrap(),
+            Value::I64(42)
+        );
+        assert_eq!(
+            Value::I64(7).try_cast_to(ValueKind::U8).unwrap(),
+            Value::U8(7)
+        );
+        assert!(Value::I64(300).try_cast_to(ValueKind::U8).is_err());
+        assert_eq!(
+            Value::Str("1".to_string())
+                .try_cast_to(ValueKind::Bool)
+                .unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            Value::Str("FALSE".to_string())
+                .try_cast_to(ValueKind::Bool)
+                .unwrap(),
+            Value::Bool(false)
+        );
+        assert!(Value::I64(2).try_cast_to(ValueKind::Bool).is_err());
+        assert!(Value::Bool(true).try_cast_to(ValueKind::I64).is_err());
+        assert!(Value::I32(3).try_cast_to(ValueKind::Str).is_err());
+        assert_eq!(
+            Value::Bytes(b"abc".to_vec())
+                .try_cast_to(ValueKind::Str)
+                .unwrap(),
+            Value::Str("abc".to_string())
+        );
+        assert_eq!(
+            Value::Str("2024-01-02".to_string())
+                .try_cast_to(ValueKind::Date)
+                .unwrap(),
+            Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
+        );
+        assert!(Value::List(vec![]).try_cast_to(ValueKind::Str).is_err());
+    }
+
+    #[test]
+    fn test_from_value_delegates_to_cast() {
+        assert_eq!(i64::from_value(Value::U8(12)).unwrap(), 12);
+        assert!(i64::from_value(Value::Str("12".to_string())).is_err());
+        assert!(i64::from_value(Value::Bool(true)).is_err());
+        assert!(String::from_value(Value::I64(1)).is_err());
+        assert!(bool::from_value(Value::I64(1)).unwrap());
+        assert!(bool::from_value(Value::Str("0".to_string())).is_ok_and(|b| !b));
+        assert_eq!(f64::from_value(Value::F32(2.0)).unwrap(), 2.0);
+        assert_eq!(Option::<i32>::from_value(Value::Null).unwrap(), None);
+    }
+
+    #[test]
+    fn test_map_round_trip() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), "x".to_string());
+        let back = HashMap::<String, String>::from_value(map.to_value()).unwrap();
+        assert_eq!(back, map);
+
+        let mut tree = BTreeMap::new();
+        tree.insert("b".to_string(), 2i64);
+        tree.insert("a".to_string(), 1i64);
+        let v = tree.to_value();
+        let mut expected = HashMap::new();
+        expected.insert("a".to_string(), Value::I64(1));
+        expected.insert("b".to_string(), Value::I64(2));
+        assert_eq!(v, Value::Map(expected));
+        assert_eq!(BTreeMap::<String, i64>::from_value(v).unwrap(), tree);
+
+        assert!(BTreeMap::<String, i64>::from_value(Value::I64(1)).is_err());
+    }
+
+    #[test]
+    fn test_enum_casts() {
+        let e = Value::Enum("active".to_string());
+        assert_eq!(String::from_value(e.clone()).unwrap(), "active");
+        assert_eq!(
+            Value::Str("active".to_string())
+                .try_cast_to(ValueKind::Enum)
+                .unwrap(),
+            e
+        );
+        assert!(Value::I64(1).try_cast_to(ValueKind::Enum).is_err());
+    }
+
+    #[test]
+    fn test_decimal_from_value() {
+        let d: Decimal = "79228162514264337593543950.335".parse().unwrap();
+        assert_eq!(Decimal::from_value(Value::Decimal(d)).unwrap(), d);
+        assert_eq!(Decimal::from_value(Value::Str(d.to_string())).unwrap(), d);
+        assert_eq!(
+            Decimal::from_value(Value::Bytes(d.to_string().into_bytes())).unwrap(),
+            d
+        );
+        assert_eq!(
+            Decimal::from_value(Value::I64(42)).unwrap(),
+            Decimal::from(42)
+        );
+        // SQLite hands back REAL columns as F64.
+        assert_eq!(
+            Decimal::from_value(Value::F64(12.5)).unwrap(),
+            Decimal::new(125, 1)
+        );
+        assert!(matches!(
+            Decimal::from_value(Value::Str("abc".to_string())),
+            Err(DbError::TypeMismatch(_))
+        ));
+        assert!(matches!(
+            Decimal::from_value(Value::F64(f64::NAN)),
+            Err(DbError::TypeMismatch(_))
+        ));
+        assert_eq!(d.to_value(), Value::Decimal(d));
+        assert_eq!(Decimal::from_value(d.to_value()).unwrap(), d);
+    }
+
+    #[test]
+    fn test_display_safe() {
+        let value = Value::Map(HashMap::from([
+            (
+                "token".to_string(),
+                Value::Str("secret_token_xyz".to_string()),
+            ),
+            ("name".to_string(), Value::Str("bob".to_string())),
+            ("avatar".to_string(), Value::Bytes(vec![0; 3])),
+            (
+                "ids".to_string(),
+                Value::List(vec![Value::I64(1), Value::Null, Value::Bool(true)]),
+            ),
+        ]));
+        assert_eq!(
+            value.display_safe(8),
+            "{avatar: <bytes 3 bytes>, ids: [1, NULL, true], name: bob, token: <truncated>}"
+        );
+        assert_eq!(Value::Str("héllo".to_string()).display_safe(5), "héllo");
+    }
+
+    #[test]
+    fn test_collection_impls() {
+        let deque: VecDeque<i64> = VecDeque::from(vec![3, 1, 2]);
+        let value = deque.to_value();
+        assert_eq!(
+            value,
+            Value::List(vec![Value::I64(3), Value::I64(1), Value::I64(2)])
+        );
+        assert_eq!(VecDeque::<i64>::from_value(value).unwrap(), deque);
+        assert!(VecDeque::<i64>::from_value(Value::I64(1)).is_err());
+
+        let ids = vec![3i64, 1, 2];
+        let slice: &[i64] = &ids;
+        assert_eq!(slice.to_value(), ids.to_value());
+        assert_eq!([3i64, 1, 2].to_value(), ids.to_value());
+        assert_eq!(
+            ["a", "b"].to_value(),
+            Value::List(vec![
+                Value::Str("a".to_string()),
+                Value::Str("b".to_string())
+            ])
+        );
+
+        let set: BTreeSet<i64> = [3, 1, 2].into_iter().collect();
+        assert_eq!(
+            set.to_value(),
+            Value::List(vec![Value::I64(1), Value::I64(2), Value::I64(3)])
+        );
+
+        let set: HashSet<i64> = [1, 2].into_iter().collect();
+        let Value::List(items) = set.to_value() else {
+            panic!("expected list");
+        };
+        assert_eq!(items.len(), 2);
+        assert!(items.contains(&Value::I64(1)) && items.contains(&Value::I64(2)));
+    }
+
+    #[test]
+    fn test_serde_json_round_trip() {
+        let d: Decimal = "12345678901234567890.12345678".parse().unwrap();
+        let value = Value::Map(HashMap::from([
+            ("id".to_string(), Value::I64(7)),
+            ("price".to_string(), Value::Decimal(d)),
+            (
+                "tags".to_string(),
+                Value::List(vec![Value::Str("a".to_string()), Value::Null]),
+            ),
+        ]));
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"id": 7, "price": "12345678901234567890.12345678", "tags": ["a", null]})
+        );
+
+        let back: Value = serde_json::from_value(json).unwrap();
+        let Value::Map(map) = back else {
+            panic!("expected map");
+        };
+        assert_eq!(map["id"], Value::I64(7));
+        assert_eq!(Decimal::from_value(map["price"].clone()).unwrap(), d);
+        assert_eq!(
+            map["tags"],
+            Value::List(vec![Value::Str("a".to_string()), Value::Null])
+        );
+        let f: Value = serde_json::from_str("1.5").unwrap();
+        assert_eq!(f, Value::F64(1.5));
+    }
+
+    #[test]
+    fn test_smart_pointer_impls() {
+        assert_eq!(Box::new(5i64).to_value(), Value::I64(5));
+        assert_eq!(
+            Arc::new("a".to_string()).to_value(),
+            Value::Str("a".to_string())
+        );
+        assert_eq!(Rc::new(true).to_value(), Value::Bool(true));
+
+        assert_eq!(*Box::<i64>::from_value(Value::I64(5)).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_chrono_from_value() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let time = NaiveTime::from_hms_opt(3, 4, 5).unwrap();
+        let dt = date.and_time(time);
+
+        assert_eq!(NaiveDate::from_value(Value::Date(date)).unwrap(), date);
+        assert_eq!(
+            NaiveDate::from_value(Value::Str("2024-01-02".into())).unwrap(),
+            date
+        );
+        assert!(NaiveDate::from_value(Value::Str("2024-02-30".into())).is_err());
+        assert_eq!(
+            NaiveTime::from_value(Value::Str("03:04:05".into())).unwrap(),
+            time
+        );
+
+        // Both the `T` form and the form written by `to_string` / `CURRENT_TIMESTAMP`.
+        assert_eq!(
+            NaiveDateTime::from_value(Value::Str(dt.to_string())).unwrap(),
+            dt
+        );
+        assert_eq!(
+            NaiveDateTime::from_value(Value::Str("2024-01-02T03:04:05".into())).unwrap(),
+            dt
+        );
+        assert_eq!(
+            DateTime::<Utc>::from_value(Value::Str("2024-01-02 03:04:05".into())).unwrap(),
+            dt.and_utc()
+        );
+        assert_eq!(dt.to_value(), Value::DateTime(dt));
+    }
+}
@@ -3,7 +3,9 @@ use uorm::Param;
 use uorm::Result;
 use uorm::driver_manager::U;
 use uorm::udbc::sqlite::pool::SqliteDriver;
-use uorm::{mapper_assets, sql};
+use uorm::{
+    mapper_assets, sql, sql_delete, sql_get, sql_insert, sql_list, sql_namespace, sql_update,
+};
 
 #[derive(Debug, Param)]
 struct User {
@@ -66,21 +68,164 @@ impl UserDao {
         exec!()
     }
 
+    #[sql(id = "list_all", method = "get_one")]
+    pub async fn first_user() -> Result<Option<User>> {
+        exec!()
+    }
+
+    #[sql(id = "get_by_id", method = "get_one")]
+    pub async fn get_first_by_id(id: i64) -> Result<User> {
+        exec!()
+    }
+
+    #[sql(id = "get_by_id", method = "list")]
+    pub async fn list_by_id(id: i64) -> Result<Vec<User>> {
+        exec!()
+    }
+
     #[sql("update_age")]
     pub async fn update_age(id: i64, age: i32) -> Result<u64> {
         exec!()
     }
 
+    #[sql("name_and_age")]
+    pub async fn name_and_age(id: i64) -> Result<(String, i32)> {
+        exec!()
+    }
+
+    #[sql(id = "update_status_locked", optimistic_lock = "age")]
+    pub async fn update_status_locked(id: i64, status: String, age: i32) -> Result<u64> {
+        exec!()
+    }
+
+    #[sql("insert")]
+    pub async fn insert_get_id(name: String, age: i32) -> Result<u64> {
+        insert!()
+    }
+
+    #[sql("list_all")]
+    pub async fn list_all_explicit() -> Result<Vec<User>> {
+        list!()
+    }
+
+    #[sql("get_by_id")]
+    pub async fn find_by_id(id: i64) -> Result<Option<User>> {
+        get!()
+    }
+
+    #[sql("count_by_min_age")]
+    pub async fn count_by_min_age(min_age: i32) -> Result<u64> {
+        count!()
+    }
+
     #[sql(id = "get_by_id", namespace = "user")]
     pub async fn get_by_id_named(id: i64) -> Result<Vec<User>> {
         exec!()
     }
 }
 
+/// A DAO that carries the database name it should run against.
+#[sql("user")]
+struct TenantUserDao {
+    db: String,
+}
+
+impl TenantUserDao {
+    fn db_name(&self) -> &str {
+        &self.db
+    }
+
+    #[sql(id = "get_by_id", database = "self.db_name()")]
+    pub async fn get_by_id(&self, id: i64) -> Result<Vec<User>> {
+        exec!()
+    }
+
+    #[sql(id = "get_by_id", database = "self.db.as_str()")]
+    pub async fn get_by_id_expr(&self, id: i64) -> Result<Vec<User>> {
+        exec!()
+    }
+
+    #[sql(id = "get_by_id", db_expr = "&self.db")]
+    pub async fn get_by_id_db_expr(&self, id: i64) -> Result<Vec<User>> {
+        exec!()
+    }
+}
+
+/// A DAO whose own `NAMESPACE` constant means something else.
+struct UserQueries;
+
+impl UserQueries {
+    pub const NAMESPACE: &'static str = "reporting";
+}
+
+#[sql_namespace("user")]
+impl UserQueries {
+    #[sql("get_by_id")]
+    pub async fn get_by_id(id: i64) -> Result<Option<User>> {
+        exec!()
+    }
+
+    #[sql(id = "list_all")]
+    pub async fn list_all() -> Result<Vec<User>> {
+        exec!()
+    }
+
+    #[sql("user.count_by_min_age")]
+    pub async fn count_by_min_age(min_age: i32) -> Result<u64> {
+        count!()
+    }
+
+    #[sql_update("update_age")]
+    pub async fn update_age(id: i64, age: i32) -> Result<u64> {
+        exec!()
+    }
+
+    #[uorm::sql_delete("delete_by_id")]
+    pub async fn delete_by_id(id: i64) -> Result<u64> {
+        exec!()
+    }
+}
+
+/// A DAO using the typed `#[sql_*]` attributes instead of return-type inference.
+#[sql("user")]
+struct TypedUserDao;
+
+impl TypedUserDao {
+    #[sql_insert("insert")]
+    pub async fn insert(name: String, age: i32) -> Result<i64> {
+        exec!()
+    }
+
+    #[sql_get("get_by_id")]
+    pub async fn get(id: i64) -> Result<User> {
+        exec!()
+    }
+
+    #[sql_get("get_by_id")]
+    pub async fn find(id: i64) -> Result<Option<User>> {
+        exec!()
+    }
+
+    #[sql_list(id = "list_all")]
+    pub async fn list_all() -> Result<Vec<User>> {
+        exec!()
+    }
+
+    #[sql_update("update_age")]
+    pub async fn update_age(id: i64, age: i32) -> Result<u64> {
+        exec!()
+    }
+
+    #[sql_delete("delete_by_id")]
+    pub async fn delete(id: i64) -> Result<i64> {
+        exec!()
+    }
+}
+
 static INIT: Once = Once::new();
 
 // Use mapper_assets to load the XML at compile time
-mapper_assets!["tests/resources/mapper"];
+mapper_assets!["tests/resources/mapper", "tests/resources/extra_mapper"];
 
 async fn setup_db() -> Box<dyn uorm::udbc::connection::Connection> {
     INIT.call_once(|| {
@@ -167,12 +312,23 @@ async fn test_user_dao_macros() {
     assert!(missing.is_none());
 
     let missing_err = UserDao::get_one_by_id(999).await.unwrap_err();
-    assert!(matches!(missing_err, uorm::error::DbError::DbError(_)));
+    assert!(
+        matches!(&missing_err, uorm::error::DbError::NotFound(msg) if msg.contains("user.get_by_id")),
+        "{:?}",
+        missing_err
+    );
 
     // 3. Test list_all
     UserDao::insert("Bob".to_string(), 30).await.unwrap();
     let users = UserDao::list_all().await.unwrap();
     assert!(users.len() >= 2);
+    assert!(UserDao::first_user().await.unwrap().is_some());
+    assert_eq!(UserDao::get_first_by_id(1).await.unwrap().id, Some(1));
+    assert!(matches!(
+        UserDao::get_first_by_id(-1).await,
+        Err(uorm::error::DbError::NotFound(msg)) if msg.contains("user.get_by_id")
+    ));
+    assert_eq!(UserDao::list_by_id(1).await.unwrap().len(), 1);
 
     // 4. Test update
     let alice_id = users
@@ -187,4 +343,145 @@ async fn test_user_dao_macros() {
     // 5. Verify update
     let updated_users = UserDao::get_by_id_named(alice_id).await.unwrap();
     assert_eq!(updated_users[0].age, Some(21));
+
+    let (name, age) = UserDao::name_and_age(alice_id).await.unwrap();
+    assert_eq!((name.as_str(), age), ("Alice", 21));
+}
+
+#[tokio::test]
+async fn test_optimistic_lock() {
+    let _conn = setup_db().await;
+
+    UserDao::insert("Carol".to_string(), 40).await.unwrap();
+    let carol = UserDao::list_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|u| u.name.as_deref() == Some("Carol"))
+        .unwrap();
+    let id = carol.id.unwrap();
+
+    let affected = UserDao::update_status_locked(id, "locked".to_string(), 40)
+        .await
+        .unwrap();
+    assert_eq!(affected, 1);
+
+    // The version column moved to 41, so a second write with the stale value conflicts.
+    let err = UserDao::update_status_locked(id, "stale".to_string(), 40)
+        .await
+        .unwrap_err();
+    match err {
+        uorm::error::DbError::OptimisticLockConflict(msg) => {
+            assert!(msg.contains(&format!("id={}", id)), "{}", msg);
+            assert!(msg.contains("age=40"), "{}", msg);
+        }
+        other => panic!("Expected OptimisticLockConflict, got {:?}", other),
+    }
+
+    let user = UserDao::get_by_id_named(id).await.unwrap().remove(0);
+    assert_eq!(user.age, Some(41));
+
+    // A statement that does not compare the version itself is rejected, not rewritten.
+    let args = std::collections::HashMap::from([
+        ("id".to_string(), uorm::udbc::value::Value::I64(id)),
+        ("version".to_string(), uorm::udbc::value::Value::I32(41)),
+    ]);
+    let err = U
+        .mapper()
+        .unwrap()
+        .update_with_version("user.update_age", &args, "version")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, uorm::error::DbError::TemplateEngineError(_)),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn test_mapper_assets_multiple_dirs() {
+    assert!(uorm::mapper_loader::find_statement("user.get_by_id", "sqlite").is_some());
+    assert!(uorm::mapper_loader::find_statement("stats.count_users", "sqlite").is_some());
+}
+
+#[tokio::test]
+async fn test_sql_with_self_receiver() {
+    let _conn = setup_db().await;
+
+    let dao = TenantUserDao {
+        db: "default".to_string(),
+    };
+    assert!(dao.get_by_id(-1).await.unwrap().is_empty());
+    assert!(dao.get_by_id_expr(-1).await.unwrap().is_empty());
+    assert!(dao.get_by_id_db_expr(-1).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_explicit_exec_macros() {
+    let _conn = setup_db().await;
+
+    let id = UserDao::insert_get_id("Explicit".to_string(), 140)
+        .await
+        .unwrap();
+    let user = UserDao::find_by_id(id as i64).await.unwrap().unwrap();
+    assert_eq!(user.name.as_deref(), Some("Explicit"));
+    assert!(UserDao::find_by_id(-1).await.unwrap().is_none());
+
+    let users = UserDao::list_all_explicit().await.unwrap();
+    assert!(users.iter().any(|u| u.id == Some(id as i64)));
+    assert_eq!(UserDao::count_by_min_age(140).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_sql_namespace_on_impl() {
+    let _conn = setup_db().await;
+
+    let id = UserDao::insert_get_id("Namespaced".to_string(), 160)
+        .await
+        .unwrap();
+    let user = UserQueries::get_by_id(id as i64).await.unwrap().unwrap();
+    assert_eq!(user.name.as_deref(), Some("Namespaced"));
+    assert!(
+        UserQueries::list_all()
+            .await
+            .unwrap()
+            .iter()
+            .any(|u| u.id == Some(id as i64))
+    );
+    assert_eq!(UserQueries::count_by_min_age(160).await.unwrap(), 1);
+    assert_eq!(UserQueries::update_age(id as i64, 161).await.unwrap(), 1);
+    assert_eq!(UserQueries::count_by_min_age(161).await.unwrap(), 1);
+    assert_eq!(UserQueries::delete_by_id(id as i64).await.unwrap(), 1);
+    assert!(UserQueries::get_by_id(id as i64).await.unwrap().is_none());
+    assert_eq!(UserQueries::NAMESPACE, "reporting");
+}
+
+#[tokio::test]
+async fn test_typed_sql_macros() {
+    let _conn = setup_db().await;
+
+    let id = TypedUserDao::insert("Typed".to_string(), 170)
+        .await
+        .unwrap();
+    assert!(id > 0);
+    let user = TypedUserDao::get(id).await.unwrap();
+    assert_eq!(user.name.as_deref(), Some("Typed"));
+    assert!(TypedUserDao::find(-1).await.unwrap().is_none());
+    assert!(matches!(
+        TypedUserDao::get(-1).await,
+        Err(uorm::error::DbError::NotFound(_))
+    ));
+    assert!(
+        TypedUserDao::list_all()
+            .await
+            .unwrap()
+            .iter()
+            .any(|u| u.id == Some(id))
+    );
+
+    assert_eq!(TypedUserDao::update_age(id, 171).await.unwrap(), 1);
+    assert_eq!(TypedUserDao::get(id).await.unwrap().age, Some(171));
+    assert_eq!(TypedUserDao::delete(id).await.unwrap(), 1);
+    assert_eq!(TypedUserDao::delete(id).await.unwrap(), 0);
 }
@@ -4,6 +4,7 @@ use uorm::driver_manager::U;
 use uorm::executor::mapper::Mapper;
 use uorm::udbc::connection::Connection;
 use uorm::udbc::sqlite::pool::SqliteDriver;
+use uorm::udbc::value::Value;
 
 #[derive(Debug, Clone, PartialEq, Param)]
 struct User {
@@ -179,6 +180,39 @@ async fn test_insert_return_key() {
     assert_eq!(users[0].name.as_deref(), Some("Eve"));
 }
 
+#[tokio::test]
+async fn test_insert_returning() {
+    let (mapper, _conn) = setup_mapper("insert_returning").await;
+
+    let rows: Vec<User> = mapper
+        .execute_returning(
+            "user.insert_returning",
+            &NameAgeArg {
+                name: "Grace".to_string(),
+                age: 33,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].id.unwrap() > 0);
+    assert_eq!(rows[0].name.as_deref(), Some("Grace"));
+
+    // `returning="true"` also routes `execute()` through the query path.
+    let user: User = mapper
+        .execute(
+            "user.insert_returning",
+            &NameAgeArg {
+                name: "Heidi".to_string(),
+                age: 34,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(user.name.as_deref(), Some("Heidi"));
+    assert_eq!(user.age, Some(34));
+}
+
 #[tokio::test]
 async fn test_insert_return_key_in_transaction() {
     let (mapper, _conn) = setup_mapper("insert_return_key_tx").await;
@@ -488,3 +522,397 @@ async fn test_update_delete() {
     assert_eq!(all.len(), 1);
     assert_eq!(all[0].name.as_deref(), Some("Charlie"));
 }
+
+#[tokio::test]
+async fn test_list_and_get() {
+    let (mapper, _conn) = setup_mapper("list_and_get").await;
+
+    for (name, age) in [("Alice", 20), ("Bob", 30)] {
+        mapper
+            .execute::<i64, _>(
+                "user.insert",
+                &NameAgeArg {
+                    name: name.to_string(),
+                    age,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let users = mapper.list::<User, _>("user.list_all", &()).await.unwrap();
+    assert_eq!(users.len(), 2);
+
+    let user = mapper
+        .get::<User, _>("user.get_by_id", &IdArg { id: 2 })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.name.as_deref(), Some("Bob"));
+
+    let missing = mapper
+        .get::<User, _>("user.get_by_id", &IdArg { id: 99 })
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+
+    // Tuples take the columns in order; a single column reads as a scalar.
+    let row = mapper
+        .get_one::<(String, i32), _>("user.name_and_age", &IdArg { id: 2 })
+        .await
+        .unwrap();
+    assert_eq!(row, Some(("Bob".to_string(), 30)));
+    let count = mapper
+        .get::<i64, _>("user.count_by_min_age", &MinAgeArg { min_age: 0 })
+        .await
+        .unwrap();
+    assert_eq!(count, Some(2));
+}
+
+#[derive(Param)]
+struct MinAgeArg {
+    min_age: i32,
+}
+
+#[tokio::test]
+async fn test_count() {
+    let (mapper, _conn) = setup_mapper("count").await;
+
+    for (name, age) in [("Alice", 20), ("Bob", 30), ("Carol", 40)] {
+        mapper
+            .execute::<i64, _>(
+                "user.insert",
+                &NameAgeArg {
+                    name: name.to_string(),
+                    age,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let count = mapper
+        .count("user.count_by_min_age", &MinAgeArg { min_age: 25 })
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    // NULL (max over no rows) counts as zero.
+    let none = mapper
+        .count("user.max_age_by_min_age", &MinAgeArg { min_age: 100 })
+        .await
+        .unwrap();
+    assert_eq!(none, 0);
+
+    // Extra columns are ignored: the count is the first column of the first row.
+    let first_id = mapper.count("user.list_all", &()).await.unwrap();
+    assert_eq!(first_id, 1);
+    // A non-numeric first column is an error.
+    assert!(
+        mapper
+            .count("user.name_and_age", &IdArg { id: 1 })
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_get_required() {
+    let (mapper, _conn) = setup_mapper("get_required").await;
+
+    for (name, age) in [("Alice", 20), ("Bob", 30)] {
+        mapper
+            .execute::<i64, _>(
+                "user.insert",
+                &NameAgeArg {
+                    name: name.to_string(),
+                    age,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let user: User = mapper
+        .get_required("user.get_by_id", &IdArg { id: 1 }, "user 1")
+        .await
+        .unwrap();
+    assert_eq!(user.name.as_deref(), Some("Alice"));
+
+    let err = mapper
+        .get_required::<User, _>("user.get_by_id", &IdArg { id: 99 }, "user 99")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, uorm::error::DbError::NotFound(msg) if msg == "user 99"));
+
+    let err = mapper
+        .get_required::<User, _>("user.list_all", &(), "any user")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, uorm::error::DbError::TooManyRows(2)));
+}
+
+#[tokio::test]
+async fn test_insert_and_get_id() {
+    let (mapper, _conn) = setup_mapper("insert_and_get_id").await;
+
+    // `user.insert` does not declare returnKey, yet the generated key is still returned.
+    let first = mapper
+        .insert_and_get_id(
+            "user.insert",
+            &NameAgeArg {
+                name: "Alice".to_string(),
+                age: 20,
+            },
+        )
+        .await
+        .unwrap();
+    let second = mapper
+        .insert_and_get_id(
+            "user.insert_return_key",
+            &NameAgeArg {
+                name: "Bob".to_string(),
+                age: 30,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(second, first + 1);
+
+    let err = mapper
+        .insert_and_get_id("user.list_all", &())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, uorm::error::DbError::TemplateEngineError(msg)
+        if msg == "insert_and_get_id requires an Insert statement type"));
+}
+
+#[tokio::test]
+async fn test_update_and_require() {
+    let (mapper, _conn) = setup_mapper("update_and_require").await;
+
+    for (name, age) in [("Alice", 20), ("Bob", 30)] {
+        mapper
+            .execute::<i64, _>(
+                "user.insert",
+                &NameAgeArg {
+                    name: name.to_string(),
+                    age,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let affected = mapper
+        .update_and_require("user.update_age", &UpdateAgeArg { id: 1, age: 21 }, 1)
+        .await
+        .unwrap();
+    assert_eq!(affected, 1);
+
+    let err = mapper
+        .update_and_require("user.update_age", &UpdateAgeArg { id: 99, age: 21 }, 1)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, uorm::error::DbError::NotFound(msg) if msg == "Update affected 0 rows"));
+
+    mapper
+        .update_one("user.update_age", &UpdateAgeArg { id: 2, age: 31 })
+        .await
+        .unwrap();
+
+    // More than one affected row is rejected and rolled back.
+    let err = mapper
+        .update_one("user.delete_by_condition", &MaxAgeArg { max_age: 100 })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, uorm::error::DbError::TooManyRows(2)));
+    let users: Vec<User> = mapper.execute("user.list_all", &()).await.unwrap();
+    assert_eq!(users.len(), 2);
+}
+
+#[tokio::test]
+async fn test_render() {
+    let (mapper, _conn) = setup_mapper("render").await;
+
+    let (sql, params) = mapper
+        .render("user.update_age", &UpdateAgeArg { id: 7, age: 21 })
+        .unwrap();
+    assert_eq!(sql, "UPDATE users SET age = ? WHERE id = ?");
+    assert_eq!(
+        params,
+        vec![
+            ("age".to_string(), Value::I32(21)),
+            ("id".to_string(), Value::I64(7)),
+        ]
+    );
+
+    // Nothing was executed.
+    let users: Vec<User> = mapper.execute("user.list_all", &()).await.unwrap();
+    assert!(users.is_empty());
+
+    assert!(mapper.render("user.missing", &()).is_err());
+}
+
+#[tokio::test]
+async fn test_execute_raw() {
+    let (mapper, _conn) = setup_mapper("execute_raw").await;
+
+    let affected: u64 = mapper
+        .execute_raw(
+            "INSERT INTO users (name, age) VALUES (?, ?), (?, ?)",
+            vec![
+                Value::Str("Ann".to_string()),
+                Value::I64(30),
+                Value::Str("Ben".to_string()),
+                Value::I64(40),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(affected, 2);
+
+    // `#{...}` is not a directive here; it is just text inside a string literal.
+    let users: Vec<User> = mapper
+        .execute_raw(
+            "SELECT * FROM users WHERE age > ? AND name <> '#{name}' ORDER BY id",
+            vec![Value::I64(35)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name.as_deref(), Some("Ben"));
+
+    let count: i64 = mapper
+        .execute_raw("SELECT COUNT(*) FROM users", Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn test_execute_value() {
+    let (mapper, _conn) = setup_mapper("execute_value").await;
+
+    let mut args = std::collections::HashMap::new();
+    args.insert("name".to_string(), Value::Str("Cleo".to_string()));
+    args.insert("age".to_string(), Value::I32(28));
+    let affected: u64 = mapper
+        .execute_value("user.insert", Value::Map(args))
+        .await
+        .unwrap();
+    assert_eq!(affected, 1);
+
+    let mut args = std::collections::HashMap::new();
+    args.insert("id".to_string(), Value::I64(1));
+    let users: Vec<User> = mapper
+        .execute_value("user.get_by_id", Value::Map(args.clone()))
+        .await
+        .unwrap();
+    assert_eq!(users[0].name.as_deref(), Some("Cleo"));
+
+    let session = uorm::executor::session::Session::new(mapper.pool.clone());
+    let rows = session
+        .query_raw_value("SELECT age FROM users WHERE id = #{id}", Value::Map(args))
+        .await
+        .unwrap();
+    assert_eq!(rows[0]["age"].as_i64(), Some(28));
+}
+
+#[tokio::test]
+async fn test_execute_and_count() {
+    let (mapper, _conn) = setup_mapper("execute_and_count").await;
+
+    for (name, expected_id) in [("Dora", 1), ("Eli", 2)] {
+        let (id, affected): (i64, u64) = mapper
+            .execute_and_count(
+                "user.insert",
+                &NameAgeArg {
+                    name: name.to_string(),
+                    age: 30,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!((id, affected), (expected_id, 1));
+    }
+
+    let (id, affected): (i64, u64) = mapper
+        .execute_and_count("user.update_age", &UpdateAgeArg { id: 2, age: 31 })
+        .await
+        .unwrap();
+    assert_eq!((id, affected), (0, 1));
+}
+
+#[tokio::test]
+async fn test_execute_many() {
+    let (mapper, _conn) = setup_mapper("execute_many").await;
+    let session = uorm::driver_manager::U
+        .session_by_name(mapper.pool.name())
+        .unwrap();
+
+    session.begin().await.unwrap();
+    let ids: Vec<i64> = mapper
+        .execute_many(
+            "user.insert_return_key",
+            ["Gus", "Hal", "Ivy"]
+                .into_iter()
+                .map(|name| NameAgeArg {
+                    name: name.to_string(),
+                    age: 50,
+                })
+                .collect(),
+        )
+        .await
+        .unwrap();
+    session.commit().await.unwrap();
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    let err = mapper
+        .execute_many::<User, _>(
+            "user.get_by_id",
+            vec![IdArg { id: 1 }, IdArg { id: 99 }, IdArg { id: 2 }],
+        )
+        .await
+        .unwrap_err();
+    match err {
+        uorm::error::DbError::BatchError { index, source } => {
+            assert_eq!(index, 1);
+            assert!(matches!(*source, uorm::error::DbError::NotFound(_)));
+        }
+        other => panic!("expected BatchError, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_execute_single_row() {
+    let (mapper, _conn) = setup_mapper("execute_single_row").await;
+
+    let err = mapper
+        .execute::<User, _>("user.list_all", &())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, uorm::error::DbError::NotFound(_)),
+        "{:?}",
+        err
+    );
+
+    for (name, age) in [("Ada", 36), ("Bo", 41)] {
+        mapper
+            .execute::<u64, _>(
+                "user.insert",
+                &NameAgeArg {
+                    name: name.to_string(),
+                    age,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let first: User = mapper.execute("user.list_all", &()).await.unwrap();
+    assert_eq!(first.name.as_deref(), Some("Ada"));
+    let all: Vec<User> = mapper.execute("user.list_all", &()).await.unwrap();
+    assert_eq!(all.len(), 2);
+}
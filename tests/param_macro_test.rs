@@ -56,3 +56,284 @@ fn test_param_struct_to_value() {
         _ => panic!("Expected Value::Map"),
     }
 }
+
+#[derive(Debug, PartialEq, Param)]
+struct Paginated<T> {
+    page: u32,
+    items: T,
+    #[param(ignore)]
+    cursor: T,
+}
+
+#[test]
+fn test_param_generic_struct() {
+    let p = Paginated {
+        page: 2,
+        items: vec![1i64, 2],
+        cursor: vec![9],
+    };
+
+    let val = p.to_value();
+    match &val {
+        Value::Map(map) => {
+            assert_eq!(map.get("page"), Some(&Value::U32(2)));
+            assert_eq!(
+                map.get("items"),
+                Some(&Value::List(vec![Value::I64(1), Value::I64(2)]))
+            );
+            assert!(!map.contains_key("cursor"));
+        }
+        _ => panic!("Expected Value::Map"),
+    }
+
+    let back = <Paginated<Vec<i64>> as FromValue>::from_value(val).unwrap();
+    assert_eq!(back.page, 2);
+    assert_eq!(back.items, vec![1, 2]);
+    assert!(back.cursor.is_empty());
+}
+
+#[derive(Debug, PartialEq, Param)]
+struct WithDefaults {
+    id: i32,
+    #[param(default = "0")]
+    age: i32,
+    #[param(default = "String::from(\"guest\")")]
+    role: String,
+}
+
+#[test]
+fn test_param_default_for_missing_field() {
+    let mut row = std::collections::HashMap::new();
+    row.insert("id".to_string(), Value::I32(1));
+
+    let v = <WithDefaults as FromValue>::from_value(Value::Map(row)).unwrap();
+    assert_eq!(v.age, 0);
+    assert_eq!(v.role, "guest");
+
+    let mut row = std::collections::HashMap::new();
+    row.insert("id".to_string(), Value::I32(2));
+    row.insert("age".to_string(), Value::I32(30));
+    row.insert("role".to_string(), Value::Str("admin".to_string()));
+
+    let v = <WithDefaults as FromValue>::from_value(Value::Map(row)).unwrap();
+    assert_eq!(v.age, 30);
+    assert_eq!(v.role, "admin");
+}
+
+#[derive(Debug, PartialEq, Param)]
+enum Status {
+    Active,
+    #[param(rename = "on_hold")]
+    OnHold,
+}
+
+#[derive(Debug, PartialEq, Param)]
+struct Account {
+    id: i64,
+    status: Status,
+}
+
+#[test]
+fn test_param_enum() {
+    assert_eq!(Status::Active.to_value(), Value::Enum("Active".to_string()));
+    assert_eq!(
+        Status::OnHold.to_value(),
+        Value::Enum("on_hold".to_string())
+    );
+
+    // Both enum columns and plain text columns are accepted.
+    assert_eq!(
+        Status::from_value(Value::Enum("Active".to_string())).unwrap(),
+        Status::Active
+    );
+    assert_eq!(
+        Status::from_value(Value::Str("on_hold".to_string())).unwrap(),
+        Status::OnHold
+    );
+    assert!(Status::from_value(Value::Str("Deleted".to_string())).is_err());
+
+    let mut row = std::collections::HashMap::new();
+    row.insert("id".to_string(), Value::I64(1));
+    row.insert("status".to_string(), Value::Str("Active".to_string()));
+    let account = Account::from_value(Value::Map(row)).unwrap();
+    assert_eq!(
+        account,
+        Account {
+            id: 1,
+            status: Status::Active
+        }
+    );
+}
+
+#[derive(Param)]
+struct Credentials {
+    user_name: String,
+    #[param(sensitive)]
+    password: String,
+}
+
+#[derive(Param)]
+struct SignUp {
+    credentials: Credentials,
+    age: i32,
+}
+
+#[test]
+fn test_param_sensitive() {
+    let signup = SignUp {
+        credentials: Credentials {
+            user_name: "alice".to_string(),
+            password: "hunter2".to_string(),
+        },
+        age: 30,
+    };
+
+    // Binding still sees the real value.
+    let Value::Map(map) = signup.to_value() else {
+        panic!("expected map");
+    };
+    let Some(Value::Map(creds)) = map.get("credentials") else {
+        panic!("expected nested map");
+    };
+    assert_eq!(creds["password"], Value::Str("hunter2".to_string()));
+
+    // Logging sees the redacted value, including through the nested struct.
+    let Value::Map(map) = signup.to_log_value() else {
+        panic!("expected map");
+    };
+    let Some(Value::Map(creds)) = map.get("credentials") else {
+        panic!("expected nested map");
+    };
+    assert_eq!(
+        creds["password"],
+        Value::Str(uorm::udbc::value::REDACTED.to_string())
+    );
+    assert_eq!(creds["userName"], Value::Str("alice".to_string()));
+    assert_eq!(map["age"], Value::I32(30));
+}
+
+/// Stand-in for an external type without `ToValue`/`FromValue` impls.
+#[derive(Debug, PartialEq)]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+fn parse_version(s: String) -> Result<Version, uorm::error::DbError> {
+    let parse = || {
+        let (major, minor) = s.split_once('.')?;
+        Some(Version {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    };
+    parse().ok_or_else(|| uorm::error::DbError::TypeMismatch(format!("bad version: {}", s)))
+}
+
+fn version_code(v: &Version) -> i64 {
+    i64::from(v.major) * 100 + i64::from(v.minor)
+}
+
+#[derive(Debug, PartialEq, Param)]
+struct Release {
+    #[param(via = "to_string", via_from = "parse_version")]
+    version: Version,
+}
+
+#[derive(Param)]
+struct ReleaseCode {
+    #[param(via = "version_code", via_from = "parse_version")]
+    version: Version,
+}
+
+#[test]
+fn test_param_via() {
+    let release = Release {
+        version: Version { major: 1, minor: 2 },
+    };
+    let value = release.to_value();
+    let Value::Map(map) = &value else {
+        panic!("expected map");
+    };
+    assert_eq!(map["version"], Value::Str("1.2".to_string()));
+    assert_eq!(Release::from_value(value).unwrap(), release);
+
+    let code = ReleaseCode {
+        version: Version { major: 3, minor: 4 },
+    };
+    let Value::Map(map) = code.to_value() else {
+        panic!("expected map");
+    };
+    assert_eq!(map["version"], Value::I64(304));
+
+    let mut row = std::collections::HashMap::new();
+    row.insert("version".to_string(), Value::Str("x".to_string()));
+    assert!(Release::from_value(Value::Map(row)).is_err());
+}
+
+#[derive(Debug, PartialEq, Param)]
+struct TaggedAccount {
+    #[param(vec_as_csv)]
+    tags: Vec<String>,
+    #[param(vec_as_csv = "|")]
+    permissions: Vec<String>,
+}
+
+#[test]
+fn test_param_vec_as_csv() {
+    let account = TaggedAccount {
+        tags: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        permissions: vec!["read".to_string(), "write".to_string()],
+    };
+    let value = account.to_value();
+    let Value::Map(map) = &value else {
+        panic!("expected map");
+    };
+    assert_eq!(map["tags"], Value::Str("a,b,c".to_string()));
+    assert_eq!(map["permissions"], Value::Str("read|write".to_string()));
+    assert_eq!(TaggedAccount::from_value(value).unwrap(), account);
+
+    let mut row = std::collections::HashMap::new();
+    row.insert("tags".to_string(), Value::Str(String::new()));
+    row.insert("permissions".to_string(), Value::Null);
+    assert_eq!(
+        TaggedAccount::from_value(Value::Map(row)).unwrap(),
+        TaggedAccount {
+            tags: Vec::new(),
+            permissions: Vec::new(),
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Param)]
+struct EmptyArgs {}
+
+#[derive(Debug, PartialEq, Param)]
+struct UnitArgs;
+
+#[test]
+fn test_param_empty_struct() {
+    assert_eq!(
+        EmptyArgs {}.to_value(),
+        Value::Map(std::collections::HashMap::new())
+    );
+    assert_eq!(
+        UnitArgs.to_value(),
+        Value::Map(std::collections::HashMap::new())
+    );
+
+    let mut row = std::collections::HashMap::new();
+    row.insert("anything".to_string(), Value::I64(1));
+    assert_eq!(
+        EmptyArgs::from_value(Value::Map(row.clone())).unwrap(),
+        EmptyArgs {}
+    );
+    assert_eq!(UnitArgs::from_value(Value::Map(row)).unwrap(), UnitArgs);
+    assert!(UnitArgs::from_value(Value::I64(1)).is_err());
+}
@@ -1,8 +1,16 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use uorm::Param;
-use uorm::executor::session::Session;
+use uorm::driver_manager::{DriverManager, U};
+use uorm::error::DbError;
+use uorm::executor::interceptor::QueryInterceptor;
+use uorm::executor::session::{Session, SessionConfig, SessionFactory, TaskLocalTransaction};
+use uorm::udbc::connection::Connection;
 use uorm::udbc::driver::Driver;
 use uorm::udbc::sqlite::pool::SqliteDriver;
+use uorm::udbc::value::Value;
 
 #[derive(Debug, PartialEq, Param)]
 struct User {
@@ -101,3 +109,1069 @@ async fn test_transaction_rollback() {
     let rows: Vec<User> = session.query(select_sql, &()).await.unwrap();
     assert_eq!(rows.len(), 0);
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_splitting_session_routes_reads_to_replica() {
+    let mut keep_alive = Vec::new();
+    for db_name in ["split_primary", "split_replica"] {
+        let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+        let driver = SqliteDriver::new(url).name(db_name).build().unwrap();
+        let mut conn = driver.acquire().await.unwrap();
+        conn.execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &[],
+        )
+        .await
+        .unwrap();
+        keep_alive.push(conn);
+        U.register(driver).unwrap();
+    }
+
+    let session = Session::with_splitting(&U, "split_primary", "split_replica").unwrap();
+    let user = NewUser {
+        name: "Carol".to_string(),
+        age: 41,
+    };
+    let insert_sql = "INSERT INTO users (name, age) VALUES (#{name}, #{age})";
+    let select_sql = "SELECT * FROM users WHERE name = 'Carol'";
+
+    // Writes land on the primary, reads hit the (unreplicated) replica.
+    session.execute(insert_sql, &user).await.unwrap();
+    let rows: Vec<User> = session.query(select_sql, &()).await.unwrap();
+    assert!(rows.is_empty());
+    let rows: Vec<User> = session.primary().query(select_sql, &()).await.unwrap();
+    assert_eq!(rows.len(), 1);
+
+    // Inside a transaction, reads are served by the primary.
+    session.begin().await.unwrap();
+    let rows: Vec<User> = session.query(select_sql, &()).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    session.commit().await.unwrap();
+
+    assert!(Session::with_splitting(&U, "split_primary", "missing").is_none());
+    // Drivers are looked up in the given manager only.
+    let other = DriverManager::builder().build();
+    assert!(Session::with_splitting(&other, "split_primary", "split_replica").is_none());
+}
+
+#[derive(Default)]
+struct CountingInterceptor {
+    before: AtomicUsize,
+    rows: AtomicUsize,
+}
+
+#[async_trait]
+impl QueryInterceptor for CountingInterceptor {
+    async fn before_query(&self, _sql_id: &str, _args: &Value) -> uorm::Result<()> {
+        self.before.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn after_query(&self, _sql_id: &str, rows: usize, _elapsed_ms: u64) {
+        self.rows.fetch_add(rows, Ordering::SeqCst);
+    }
+}
+
+struct DenyDeletes;
+
+#[async_trait]
+impl QueryInterceptor for DenyDeletes {
+    async fn before_query(&self, _sql_id: &str, args: &Value) -> uorm::Result<()> {
+        if let Value::Map(m) = args
+            && m.contains_key("delete")
+        {
+            return Err(DbError::Custom("deletes are not allowed".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_session_interceptors() {
+    let db_name = "interceptor";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let mut conn = driver.acquire().await.unwrap();
+    conn.execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let counter = Arc::new(CountingInterceptor::default());
+    let session = Session::new(driver.clone())
+        .with_interceptor(counter.clone())
+        .with_interceptor(Arc::new(DenyDeletes));
+
+    let user = NewUser {
+        name: "Dave".to_string(),
+        age: 50,
+    };
+    session
+        .execute(
+            "INSERT INTO users (name, age) VALUES (#{name}, #{age})",
+            &user,
+        )
+        .await
+        .unwrap();
+    let rows: Vec<User> = session.query("SELECT * FROM users", &()).await.unwrap();
+    assert_eq!(rows.len(), 1);
+
+    let mut args = std::collections::HashMap::new();
+    args.insert("delete".to_string(), true);
+    let err = session
+        .execute("DELETE FROM users", &args)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::Custom(_)));
+
+    // Both successful calls and the rejected one ran the `before` hook; only successful
+    // calls reported rows (1 inserted + 1 selected).
+    assert_eq!(counter.before.load(Ordering::SeqCst), 3);
+    assert_eq!(counter.rows.load(Ordering::SeqCst), 2);
+    let rows: Vec<User> = session.query("SELECT * FROM users", &()).await.unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_owned_transaction() {
+    let db_name = "tx_owned";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = SqliteDriver::new(url).name(db_name).build().unwrap();
+    let driver = Arc::new(driver);
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let insert = "INSERT INTO users (name, age) VALUES (#{name}, #{age})";
+
+    // Committed work is kept; work after a rolled-back savepoint is discarded.
+    let mut tx = session.begin_transaction().await.unwrap();
+    tx.execute(
+        insert,
+        &NewUser {
+            name: "Alice".to_string(),
+            age: 30,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(tx.last_insert_id().await.unwrap() > 0);
+    tx.savepoint("sp1").await.unwrap();
+    tx.execute(
+        insert,
+        &NewUser {
+            name: "Bob".to_string(),
+            age: 40,
+        },
+    )
+    .await
+    .unwrap();
+    tx.rollback_to_savepoint("sp1").await.unwrap();
+    let count: i64 = tx
+        .query_scalar("SELECT count(*) FROM users", &())
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+    let alice: Option<User> = tx
+        .get_one("SELECT * FROM users WHERE name = 'Alice'", &())
+        .await
+        .unwrap();
+    assert_eq!(alice.unwrap().age, 30);
+    assert!(tx.savepoint("bad name").await.is_err());
+    tx.commit().await.unwrap();
+
+    // Dropping an unfinished transaction rolls it back.
+    let mut tx = session.begin_transaction().await.unwrap();
+    tx.execute(
+        insert,
+        &NewUser {
+            name: "Carol".to_string(),
+            age: 50,
+        },
+    )
+    .await
+    .unwrap();
+    drop(tx);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let rows: Vec<User> = session.query("SELECT * FROM users", &()).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Alice");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_one_required() {
+    let db_name = "query_one_required";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = SqliteDriver::new(url).name(db_name).build().unwrap();
+    let driver = Arc::new(driver);
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+    session
+        .execute(
+            "INSERT INTO users (name, age) VALUES ('Alice', 30), ('Bob', 40)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let alice: User = session
+        .query_one_required("SELECT * FROM users WHERE name = 'Alice'", &(), "no alice")
+        .await
+        .unwrap();
+    assert_eq!(alice.age, 30);
+
+    let err = session
+        .query_one_required::<User, _>("SELECT * FROM users WHERE age > 100", &(), "nobody")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::NotFound(msg) if msg == "nobody"));
+
+    let err = session
+        .query_one_required::<User, _>("SELECT * FROM users", &(), "nobody")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::TooManyRows(2)));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_one() {
+    let db_name = "get_one";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = SqliteDriver::new(url).name(db_name).build().unwrap();
+    let driver = Arc::new(driver);
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+    session
+        .execute(
+            "INSERT INTO users (name, age) VALUES ('Alice', 30), ('Bob', 40)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let first: Option<User> = session
+        .get_one("SELECT * FROM users ORDER BY age DESC", &())
+        .await
+        .unwrap();
+    assert_eq!(first.map(|u| u.name), Some("Bob".to_string()));
+    let none: Option<User> = session
+        .get_one("SELECT * FROM users WHERE age > 100", &())
+        .await
+        .unwrap();
+    assert!(none.is_none());
+    let pair: Option<(String, i32)> = session
+        .get_one("SELECT name, age FROM users ORDER BY age", &())
+        .await
+        .unwrap();
+    assert_eq!(pair, Some(("Alice".to_string(), 30)));
+    let max_age: Option<i64> = session
+        .get_one("SELECT max(age) FROM users", &())
+        .await
+        .unwrap();
+    assert_eq!(max_age, Some(40));
+
+    let mut tx = session.begin_transaction().await.unwrap();
+    let pair: Option<(i32, String)> = tx
+        .get_one("SELECT age, name FROM users ORDER BY age DESC", &())
+        .await
+        .unwrap();
+    assert_eq!(pair, Some((40, "Bob".to_string())));
+    let total: Option<i64> = tx.get_one("SELECT count(*) FROM users", &()).await.unwrap();
+    assert_eq!(total, Some(2));
+    tx.rollback().await.unwrap();
+
+    let mut conn = driver.acquire().await.unwrap();
+    let row = conn
+        .query_one("SELECT name FROM users ORDER BY age", &[])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.get("name"), Some(&Value::Str("Alice".to_string())));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_inline_raw_interpolation_requires_opt_in() {
+    let db_name = "inline_raw";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY)", &())
+        .await
+        .unwrap();
+    let args = HashMap::from([("table".to_string(), "items".to_string())]);
+    let sql = "SELECT * FROM ${table}";
+
+    let err = session.query_raw(sql, &args).await.unwrap_err();
+    assert!(matches!(err, DbError::TemplateEngineError(_)), "{:?}", err);
+    let mut tx = session.begin_transaction().await.unwrap();
+    assert!(tx.query_raw(sql, &args).await.is_err());
+    tx.rollback().await.unwrap();
+
+    let session = session.allow_raw(true);
+    assert!(session.query_raw(sql, &args).await.unwrap().is_empty());
+    let mut tx = session.begin_transaction().await.unwrap();
+    assert!(tx.query_raw(sql, &args).await.unwrap().is_empty());
+    tx.commit().await.unwrap();
+    let err = session.query_raw(sql, &()).await.unwrap_err();
+    assert!(matches!(err, DbError::TemplateEngineError(_)), "{:?}", err);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_last_insert_id_semantics() {
+    let db_name = "last_insert_id";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = SqliteDriver::new(url).name(db_name).build().unwrap();
+    let driver = Arc::new(driver);
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let insert = "INSERT INTO users (name, age) VALUES (#{name}, #{age})";
+    let (affected, id) = session
+        .execute_with_insert_id(
+            insert,
+            &NewUser {
+                name: "Alice".to_string(),
+                age: 30,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!((affected, id), (1, 1));
+
+    // Outside a transaction the id could come from another pooled connection, but the
+    // call still succeeds.
+    assert!(session.last_insert_id().await.is_ok());
+
+    session.begin().await.unwrap();
+    session
+        .execute(
+            insert,
+            &NewUser {
+                name: "Bob".to_string(),
+                age: 40,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(session.last_insert_id().await.unwrap(), 2);
+    session.commit().await.unwrap();
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_uorm_alias_is_the_same_manager() {
+    assert!(std::ptr::eq::<uorm::driver_manager::DriverManager>(
+        &**uorm::UORM,
+        &*uorm::U
+    ));
+    assert!(std::ptr::eq::<uorm::driver_manager::DriverManager>(
+        &*uorm::U,
+        &*U
+    ));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_colon_params() {
+    let db_name = "colon_params";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = SqliteDriver::new(url)
+        .name(db_name)
+        .parser_config(uorm::ParserConfig {
+            allow_colon_params: true,
+        })
+        .build()
+        .unwrap();
+    let driver = Arc::new(driver);
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+    session
+        .execute(
+            "INSERT INTO users (name, age) VALUES (:name, #{age})",
+            &NewUser {
+                name: "Alice".to_string(),
+                age: 30,
+            },
+        )
+        .await
+        .unwrap();
+
+    let users: Vec<User> = session
+        .query(
+            "SELECT * FROM users WHERE name = :name",
+            &NewUser {
+                name: "Alice".to_string(),
+                age: 0,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].age, 30);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_map() {
+    let db_name = "query_map";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute("CREATE TABLE config (id INTEGER, name TEXT)", &())
+        .await
+        .unwrap();
+    session
+        .execute(
+            "INSERT INTO config VALUES (1, 'a'), (2, 'b'), (1, 'c')",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let map: std::collections::HashMap<i64, String> = session
+        .query_map(
+            "SELECT id, name FROM config ORDER BY rowid",
+            &(),
+            "id",
+            "name",
+        )
+        .await
+        .unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&1], "c");
+    assert_eq!(map[&2], "b");
+
+    let err = session
+        .query_map::<i64, String, _>("SELECT id FROM config", &(), "id", "name")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::MissingField(col) if col == "name"));
+
+    let names: Vec<String> = session
+        .query_column("SELECT name FROM config ORDER BY rowid", &(), "name")
+        .await
+        .unwrap();
+    assert_eq!(names, ["a", "b", "c"]);
+    let err = session
+        .query_column::<String, _>("SELECT id FROM config", &(), "name")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::MissingField(col) if col == "name"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_tuples() {
+    let db_name = "query_tuples";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute("CREATE TABLE config (id INTEGER, name TEXT)", &())
+        .await
+        .unwrap();
+    session
+        .execute("INSERT INTO config VALUES (1, 'a'), (2, 'b')", &())
+        .await
+        .unwrap();
+
+    // Tuples take the columns in select-list order.
+    let rows: Vec<(i64, String)> = session
+        .query("SELECT id, name FROM config ORDER BY id", &())
+        .await
+        .unwrap();
+    assert_eq!(rows, [(1, "a".to_string()), (2, "b".to_string())]);
+    let rows: Vec<(String, i64)> = session
+        .query("SELECT name, id FROM config ORDER BY id", &())
+        .await
+        .unwrap();
+    assert_eq!(rows[0], ("a".to_string(), 1));
+
+    // Same inside an owned transaction.
+    let mut tx = session.begin_transaction().await.unwrap();
+    let rows: Vec<(String, i64)> = tx
+        .query("SELECT name, id FROM config ORDER BY id", &())
+        .await
+        .unwrap();
+    assert_eq!(rows, [("a".to_string(), 1), ("b".to_string(), 2)]);
+    tx.rollback().await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_insert_update_delete() {
+    let db_name = "insert_update_delete";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let bob = NewUser {
+        name: "Bob".to_string(),
+        age: 20,
+    };
+    let first = session
+        .insert(
+            "INSERT INTO users (name, age) VALUES (#{name}, #{age})",
+            &bob,
+        )
+        .await
+        .unwrap();
+    let second = session
+        .insert(
+            "INSERT INTO users (name, age) VALUES (#{name}, #{age})",
+            &bob,
+        )
+        .await
+        .unwrap();
+    assert_eq!(first.affected_rows, 1);
+    assert_eq!(second.id(), first.id() + 1);
+
+    let updated = session
+        .update("UPDATE users SET age = age + 1 WHERE name = #{name}", &bob)
+        .await
+        .unwrap();
+    assert_eq!(updated, 2);
+
+    let deleted = session
+        .delete("DELETE FROM users WHERE name = #{name}", &bob)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_task_local_transaction_is_shared_with_spawned_task() {
+    let db_name = "task_local_tx";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    TaskLocalTransaction::scope(async {
+        session.begin().await.unwrap();
+
+        let child_session = Session::new(driver.clone());
+        let in_tx = tokio::spawn(TaskLocalTransaction::scope(async move {
+            child_session
+                .execute(
+                    "INSERT INTO users (name, age) VALUES (#{name}, #{age})",
+                    &NewUser {
+                        name: "Child".to_string(),
+                        age: 1,
+                    },
+                )
+                .await
+                .unwrap();
+            child_session.is_transaction_active()
+        }))
+        .await
+        .unwrap();
+        assert!(in_tx);
+
+        // The spawned insert ran on this transaction, so rolling back discards it.
+        session.rollback().await.unwrap();
+        assert!(!session.is_transaction_active());
+    })
+    .await;
+
+    let rows = session.query_raw("SELECT * FROM users", &()).await.unwrap();
+    assert!(rows.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_scoped_begin_is_tracked_per_task() {
+    let db_name = "per_task_tx";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY)", &())
+        .await
+        .unwrap();
+
+    let (began_tx, began_rx) = tokio::sync::oneshot::channel();
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+    let task_session = session.clone();
+    let task = tokio::spawn(TaskLocalTransaction::scope(async move {
+        task_session.begin().await.unwrap();
+        began_tx.send(()).unwrap();
+        done_rx.await.unwrap();
+        // Whichever worker resumes the task, it still sees its own transaction.
+        for id in 0..10 {
+            assert!(task_session.is_transaction_active());
+            task_session
+                .execute(
+                    "INSERT INTO items (id) VALUES (#{id})",
+                    &HashMap::from([("id".to_string(), id)]),
+                )
+                .await
+                .unwrap();
+            tokio::task::yield_now().await;
+        }
+        task_session.rollback().await.unwrap();
+    }));
+
+    began_rx.await.unwrap();
+    assert!(!session.is_transaction_active());
+    done_tx.send(()).unwrap();
+    task.await.unwrap();
+
+    let rows = session.query_raw("SELECT * FROM items", &()).await.unwrap();
+    assert!(rows.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_begin_in_unscoped_task_fails() {
+    let driver = Arc::new(SqliteDriver::new("sqlite::memory:").build().unwrap());
+    let session = Session::new(driver.clone());
+
+    let result = tokio::spawn(async move { session.begin().await })
+        .await
+        .unwrap();
+    assert!(
+        matches!(result, Err(DbError::DbError(msg)) if msg.contains("TaskLocalTransaction::scope"))
+    );
+    assert_eq!(driver.pool_stats().active_connections, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_aborted_task_releases_its_transaction() {
+    let db_name = "aborted_task_tx";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY)", &())
+        .await
+        .unwrap();
+
+    let (began_tx, began_rx) = tokio::sync::oneshot::channel();
+    let task_session = session.clone();
+    let task = tokio::spawn(TaskLocalTransaction::scope(async move {
+        task_session.begin().await.unwrap();
+        task_session
+            .execute("INSERT INTO items (id) VALUES (1)", &())
+            .await
+            .unwrap();
+        began_tx.send(()).unwrap();
+        std::future::pending::<()>().await;
+    }));
+
+    began_rx.await.unwrap();
+    assert_eq!(driver.pool_stats().active_connections, 2);
+    task.abort();
+    assert!(task.await.unwrap_err().is_cancelled());
+
+    // Dropping the task's scope rolls the transaction back and closes its connection.
+    for _ in 0..100 {
+        if driver.pool_stats().active_connections == 1 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(driver.pool_stats().active_connections, 1);
+    let rows = session.query_raw("SELECT * FROM items", &()).await.unwrap();
+    assert!(rows.is_empty());
+    drop(keep_alive);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_session_factory() {
+    let db_name = "session_factory";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let keep_alive_driver = SqliteDriver::new(url.clone()).build().unwrap();
+    let _keep_alive = keep_alive_driver.acquire().await.unwrap();
+    U.register(SqliteDriver::new(url).name(db_name).build().unwrap())
+        .unwrap();
+    let session = U.session_by_name(db_name).unwrap();
+    session
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY)", &())
+        .await
+        .unwrap();
+
+    let counter = Arc::new(CountingInterceptor::default());
+    let factory = U
+        .session_factory(
+            db_name,
+            SessionConfig {
+                query_timeout_ms: Some(1000),
+                log_level: log::Level::Info,
+                interceptor: Some(counter.clone()),
+            },
+        )
+        .unwrap();
+
+    let session = factory.create();
+    session
+        .execute("INSERT INTO items (id) VALUES (1)", &())
+        .await
+        .unwrap();
+    assert_eq!(counter.before.load(Ordering::SeqCst), 0);
+
+    let rows = factory
+        .create_intercepted()
+        .query_raw("SELECT id FROM items", &())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(counter.before.load(Ordering::SeqCst), 1);
+
+    let err = U
+        .session_factory("no_such_db", SessionConfig::default())
+        .err()
+        .unwrap();
+    assert!(matches!(err, DbError::DriverError(_)));
+}
+
+/// A connection whose `execute` never finishes for the statement `"HANG"`.
+struct HangingConnection {
+    rolled_back: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Connection for HangingConnection {
+    async fn query(
+        &mut self,
+        _sql: &str,
+        _args: &[(String, Value)],
+    ) -> uorm::Result<Vec<HashMap<String, Value>>> {
+        Ok(Vec::new())
+    }
+
+    async fn execute(&mut self, sql: &str, _args: &[(String, Value)]) -> uorm::Result<u64> {
+        if sql == "HANG" {
+            std::future::pending::<()>().await;
+        }
+        Ok(1)
+    }
+
+    async fn last_insert_id(&mut self) -> uorm::Result<u64> {
+        Ok(0)
+    }
+
+    async fn begin(&mut self) -> uorm::Result<()> {
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> uorm::Result<()> {
+        Ok(())
+    }
+
+    async fn rollback(&mut self) -> uorm::Result<()> {
+        self.rolled_back.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+struct HangingDriver {
+    rolled_back: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Driver for HangingDriver {
+    fn name(&self) -> &str {
+        "hanging"
+    }
+
+    fn r#type(&self) -> &str {
+        "hanging"
+    }
+
+    fn placeholder(&self, _param_seq: usize, _param_name: &str) -> String {
+        "?".to_string()
+    }
+
+    async fn acquire(&self) -> uorm::Result<Box<dyn Connection>> {
+        Ok(Box::new(HangingConnection {
+            rolled_back: self.rolled_back.clone(),
+        }))
+    }
+
+    async fn close(&self) -> uorm::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_timeout_aborts_transaction() {
+    let rolled_back = Arc::new(AtomicBool::new(false));
+    let driver = Arc::new(HangingDriver {
+        rolled_back: rolled_back.clone(),
+    });
+    let session = SessionFactory::new(
+        driver,
+        SessionConfig {
+            query_timeout_ms: Some(10),
+            ..SessionConfig::default()
+        },
+    )
+    .create();
+
+    let err = session.execute("HANG", &()).await.unwrap_err();
+    assert!(matches!(err, DbError::Timeout(_)), "{:?}", err);
+    assert_eq!(session.execute("UPDATE t SET x = 1", &()).await.unwrap(), 1);
+
+    session.begin().await.unwrap();
+    let err = session.execute("HANG", &()).await.unwrap_err();
+    assert!(matches!(err, DbError::Timeout(_)), "{:?}", err);
+    for _ in 0..10 {
+        if rolled_back.load(Ordering::SeqCst) {
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+    assert!(rolled_back.load(Ordering::SeqCst));
+
+    // The aborted transaction can't be used or committed, only cleared.
+    assert!(session.execute("UPDATE t SET x = 1", &()).await.is_err());
+    assert!(session.commit().await.is_err());
+    session.rollback().await.unwrap();
+    assert!(!session.is_transaction_active());
+}
+
+struct UserRepo {
+    session: Session,
+}
+
+impl UserRepo {
+    fn get_session(&self) -> &Session {
+        &self.session
+    }
+
+    #[uorm::transaction(session_expr = "self.get_session()")]
+    async fn add_then_fail(&self, user: &NewUser, fail: bool) -> uorm::Result<()> {
+        self.session
+            .execute(
+                "INSERT INTO users (name, age) VALUES (#{name}, #{age})",
+                user,
+            )
+            .await?;
+        assert!(self.session.is_transaction_active());
+        if fail {
+            return Err(DbError::Custom("rejected".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_transaction_session_expr() {
+    let db_name = "tx_session_expr";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let repo = UserRepo {
+        session: Session::new(driver.clone()),
+    };
+    repo.session
+        .execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+            &(),
+        )
+        .await
+        .unwrap();
+
+    let user = NewUser {
+        name: "Erin".to_string(),
+        age: 33,
+    };
+    assert!(repo.add_then_fail(&user, true).await.is_err());
+    repo.add_then_fail(&user, false).await.unwrap();
+    assert!(!repo.session.is_transaction_active());
+
+    let rows = repo
+        .session
+        .query_raw("SELECT * FROM users", &())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
+#[tokio::test]
+async fn test_standalone_driver_manager() {
+    let db_name = "standalone_manager";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let keep_alive_driver = SqliteDriver::new(url.clone()).build().unwrap();
+    let _keep_alive = keep_alive_driver.acquire().await.unwrap();
+
+    let collected = Arc::new(AtomicUsize::new(0));
+    let manager = {
+        let collected = collected.clone();
+        DriverManager::builder()
+            .default_db(db_name)
+            .pool_stats_collector(move |name, _stats| {
+                assert_eq!(name, "standalone_manager");
+                collected.fetch_add(1, Ordering::SeqCst);
+            })
+            .build()
+    };
+    manager
+        .register(
+            SqliteDriver::new(url.clone())
+                .name(db_name)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    assert!(
+        manager
+            .register(SqliteDriver::new(url).name(db_name).build().unwrap())
+            .is_err()
+    );
+    assert!(U.session_by_name(db_name).is_none());
+    assert!(manager.mapper().is_some());
+
+    let session = manager.session().unwrap();
+    session
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY)", &())
+        .await
+        .unwrap();
+    session
+        .execute("INSERT INTO items (id) VALUES (1)", &())
+        .await
+        .unwrap();
+    let ids: Vec<i64> = session
+        .query_column("SELECT id FROM items", &(), "id")
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![1]);
+
+    assert_eq!(manager.collect_pool_stats().len(), 1);
+    assert_eq!(collected.load(Ordering::SeqCst), 1);
+
+    // A same-named driver in another manager doesn't see this manager's transaction.
+    let other = DriverManager::builder().default_db(db_name).build();
+    other
+        .register(
+            SqliteDriver::new(format!("sqlite:file:{}_other?mode=memory", db_name))
+                .name(db_name)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    let other_session = other.session().unwrap();
+    session.begin().await.unwrap();
+    assert!(session.is_transaction_active());
+    assert!(!other_session.is_transaction_active());
+    other_session.begin().await.unwrap();
+    other_session.rollback().await.unwrap();
+    session.rollback().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_session_and_mapper_clone() {
+    let db_name = "session_clone";
+    let url = format!("sqlite:file:{}?mode=memory&cache=shared", db_name);
+    let driver = Arc::new(SqliteDriver::new(url).name(db_name).build().unwrap());
+    let _keep_alive = driver.acquire().await.unwrap();
+
+    let session = Session::new(driver.clone());
+    session
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY)", &())
+        .await
+        .unwrap();
+
+    let cloned = session.clone();
+    tokio::spawn(async move {
+        cloned
+            .execute("INSERT INTO items (id) VALUES (1)", &())
+            .await
+            .unwrap();
+    })
+    .await
+    .unwrap();
+
+    // A clone has its own transaction state; a new session on the driver shares it.
+    session.begin().await.unwrap();
+    let cloned = session.clone();
+    assert!(!cloned.is_transaction_active());
+    assert!(Session::new(driver.clone()).is_transaction_active());
+    cloned.begin().await.unwrap();
+    cloned
+        .execute("INSERT INTO items (id) VALUES (2)", &())
+        .await
+        .unwrap();
+    cloned.commit().await.unwrap();
+    session
+        .execute("INSERT INTO items (id) VALUES (3)", &())
+        .await
+        .unwrap();
+    session.rollback().await.unwrap();
+
+    let mapper = uorm::executor::mapper::Mapper::new(driver.clone());
+    let mapper_copy = mapper.clone();
+    assert!(Arc::ptr_eq(&mapper.pool, &mapper_copy.pool));
+    let ids: Vec<i64> = session
+        .query_column("SELECT id FROM items ORDER BY id", &(), "id")
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![1, 2]);
+}
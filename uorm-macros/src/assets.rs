@@ -5,10 +5,64 @@ use std::collections::hash_map::DefaultHasher;
 use std::env;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
-use syn::{LitStr, parse_macro_input};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{LitStr, Token};
 
 pub fn mapper_assets_impl(input: TokenStream) -> TokenStream {
-    let dir_lit = parse_macro_input!(input as LitStr);
+    let dirs = match Punctuated::<LitStr, Token![,]>::parse_terminated.parse(input) {
+        Ok(dirs) => dirs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    if dirs.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "mapper_assets! expects at least one directory",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let mut patterns = Vec::new();
+    let mut assets = Vec::new();
+    let mut hasher = DefaultHasher::new();
+    for dir_lit in &dirs {
+        match expand_dir(dir_lit) {
+            Ok((pattern, dir_assets)) => {
+                patterns.push(LitStr::new(&pattern, proc_macro2::Span::call_site()));
+                assets.extend(dir_assets);
+            }
+            Err(e) => return e.to_compile_error().into(),
+        }
+        dir_lit.value().hash(&mut hasher);
+    }
+
+    // Generate a single registration function covering all directories
+    let fn_name = format_ident!("__uorm_auto_register_assets_{}", hasher.finish());
+
+    quote! {
+        #[uorm::ctor::ctor(crate_path = ::uorm::ctor)]
+        fn #fn_name() {
+            #[cfg(debug_assertions)]
+            {
+                #(
+                    uorm::mapper_loader::load(#patterns).expect("Failed to load mapper assets from disk");
+                )*
+            }
+            #[cfg(not(debug_assertions))]
+            {
+                let assets = vec![
+                    #(#assets),*
+                ];
+                uorm::mapper_loader::load_assets(assets).expect("Failed to load mapper assets");
+            }
+        }
+    }
+    .into()
+}
+
+/// Resolves one directory literal into its glob pattern and `include_bytes!` entries.
+fn expand_dir(dir_lit: &LitStr) -> syn::Result<(String, Vec<proc_macro2::TokenStream>)> {
     let dir = dir_lit.value();
 
     // 1. Resolve directory path relative to CARGO_MANIFEST_DIR
@@ -18,29 +72,21 @@ pub fn mapper_assets_impl(input: TokenStream) -> TokenStream {
 
     // 2. Validate directory existence
     if !dir_path.exists() || !dir_path.is_dir() {
-        return syn::Error::new(
+        return Err(syn::Error::new(
             dir_lit.span(),
             format!("Directory not found: {}", dir_path.display()),
-        )
-        .to_compile_error()
-        .into();
+        ));
     }
 
     // 3. Find all XML files recursively
     let pattern = dir_path.join("**/*.xml");
-    let pattern_str = pattern.to_string_lossy();
+    let pattern_str = pattern.to_string_lossy().to_string();
 
-    let paths = match glob(&pattern_str) {
-        Ok(paths) => paths,
-        Err(e) => {
-            return syn::Error::new(dir_lit.span(), format!("Invalid glob pattern: {}", e))
-                .to_compile_error()
-                .into();
-        }
-    };
+    let paths = glob(&pattern_str)
+        .map_err(|e| syn::Error::new(dir_lit.span(), format!("Invalid glob pattern: {}", e)))?;
 
     // 4. Generate asset loading code
-    let assets: Vec<_> = paths
+    let assets = paths
         .filter_map(Result::ok)
         .filter(|path| path.is_file())
         .filter_map(|path| {
@@ -53,28 +99,5 @@ pub fn mapper_assets_impl(input: TokenStream) -> TokenStream {
         })
         .collect();
 
-    // 5. Generate unique registration function
-    let mut hasher = DefaultHasher::new();
-    dir.hash(&mut hasher);
-    let fn_name = format_ident!("__uorm_auto_register_assets_{}", hasher.finish());
-
-    let pattern_lit = LitStr::new(&pattern_str, proc_macro2::Span::call_site());
-
-    quote! {
-        #[uorm::ctor::ctor(crate_path = ::uorm::ctor)]
-        fn #fn_name() {
-            #[cfg(debug_assertions)]
-            {
-                uorm::mapper_loader::load(#pattern_lit).expect("Failed to load mapper assets from disk");
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                let assets = vec![
-                    #(#assets),*
-                ];
-                uorm::mapper_loader::load_assets(assets).expect("Failed to load mapper assets");
-            }
-        }
-    }
-    .into()
+    Ok((pattern_str, assets))
 }
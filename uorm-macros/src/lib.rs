@@ -14,6 +14,43 @@ pub fn sql(args: TokenStream, input: TokenStream) -> TokenStream {
     sql::sql_impl(args, input)
 }
 
+/// `#[sql]` for a query returning `Result<Vec<T>>`; calls `Mapper::list`.
+#[proc_macro_attribute]
+pub fn sql_list(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql::typed_sql_impl(sql::TypedSql::List, args, input)
+}
+
+/// `#[sql]` for a single-row query returning `Result<T>` or `Result<Option<T>>`.
+#[proc_macro_attribute]
+pub fn sql_get(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql::typed_sql_impl(sql::TypedSql::Get, args, input)
+}
+
+/// `#[sql]` for an insert returning the generated key as `Result<i64>` or `Result<u64>`.
+#[proc_macro_attribute]
+pub fn sql_insert(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql::typed_sql_impl(sql::TypedSql::Insert, args, input)
+}
+
+/// `#[sql]` for an update returning the affected rows as `Result<u64>` or `Result<i64>`.
+#[proc_macro_attribute]
+pub fn sql_update(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql::typed_sql_impl(sql::TypedSql::Update, args, input)
+}
+
+/// `#[sql]` for a delete returning the affected rows as `Result<u64>` or `Result<i64>`.
+#[proc_macro_attribute]
+pub fn sql_delete(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql::typed_sql_impl(sql::TypedSql::Delete, args, input)
+}
+
+/// Sets the namespace of the `#[sql]` methods in an `impl` block without generating a
+/// `NAMESPACE` constant: `#[sql_namespace("user")] impl UserDao { ... }`.
+#[proc_macro_attribute]
+pub fn sql_namespace(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql::sql_namespace_impl(args, input)
+}
+
 #[proc_macro_attribute]
 pub fn transaction(args: TokenStream, input: TokenStream) -> TokenStream {
     transaction::transaction_impl(args, input)
@@ -6,10 +6,12 @@ use syn::{DeriveInput, ItemFn, LitStr, parse_macro_input};
 pub fn derive_param_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
+    let generics = input.generics;
 
     let fields = match input.data {
         syn::Data::Struct(data) => match data.fields {
-            syn::Fields::Named(fields) => fields.named,
+            syn::Fields::Named(fields) if !fields.named.is_empty() => fields.named,
+            syn::Fields::Named(_) | syn::Fields::Unit => return derive_empty_impl(name, generics),
             _ => {
                 return syn::Error::new_spanned(
                     name,
@@ -19,13 +21,23 @@ pub fn derive_param_impl(input: TokenStream) -> TokenStream {
                 .into();
             }
         },
+        syn::Data::Enum(data) => return derive_enum_impl(name, generics, data),
         _ => {
-            return syn::Error::new_spanned(name, "Param only supports structs")
+            return syn::Error::new_spanned(name, "Param only supports structs and enums")
                 .to_compile_error()
                 .into();
         }
     };
 
+    let field_attrs = match fields
+        .iter()
+        .map(parse_field_attrs)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
     let case_helpers = quote! {
         fn snake_to_camel(s: &str) -> String {
             let mut out = String::new();
@@ -79,41 +91,112 @@ pub fn derive_param_impl(input: TokenStream) -> TokenStream {
         }
     };
 
-    let to_inserts = fields.iter().map(|f| {
-        let field_name = f.ident.as_ref().unwrap();
-        let (key, ignore) = parse_field_attrs(f);
-        let key_lit = LitStr::new(&key, Span::call_site());
-        if ignore {
-            quote! {}
-        } else {
-            quote! {
-                {
-                    let key: &str = #key_lit;
-                    let value = uorm::udbc::value::ToValue::to_value(&self.#field_name);
-                    map.insert(key.to_string(), value.clone());
-
-                    let camel = snake_to_camel(key);
-                    if camel != key {
-                        map.entry(camel)
-                            .or_insert_with(|| value.clone());
+    // `redact` selects the `to_log_value` variant, where sensitive fields are masked and
+    // the rest delegate to their own `to_log_value` so nested params are redacted too.
+    let map_inserts =
+        |redact: bool| {
+            fields.iter().zip(&field_attrs).map(move |(f, attrs)| {
+            let field_name = f.ident.as_ref().unwrap();
+            let key_lit = LitStr::new(&attrs.name, Span::call_site());
+            // `via` converts the field first; the result is logged as-is unless sensitive.
+            let csv_value = attrs.csv_delimiter.as_ref().map(|delimiter| {
+                quote! { uorm::udbc::value::Value::Str(self.#field_name.join(#delimiter)) }
+            });
+            let via_value = csv_value.or_else(|| attrs.via.as_ref().map(|via| {
+                if via.is_ident("to_string") {
+                    quote! {
+                        uorm::udbc::value::ToValue::to_value(
+                            &std::string::ToString::to_string(&self.#field_name)
+                        )
                     }
+                } else {
+                    quote! { uorm::udbc::value::ToValue::to_value(&#via(&self.#field_name)) }
+                }
+            }));
+            let value_expr = if redact && attrs.sensitive {
+                quote! { uorm::udbc::value::Value::Str(uorm::udbc::value::REDACTED.to_string()) }
+            } else if let Some(via_value) = via_value {
+                via_value
+            } else if !redact {
+                quote! { uorm::udbc::value::ToValue::to_value(&self.#field_name) }
+            } else {
+                quote! { uorm::udbc::value::ToValue::to_log_value(&self.#field_name) }
+            };
+            if attrs.ignore {
+                quote! {}
+            } else {
+                quote! {
+                    {
+                        let key: &str = #key_lit;
+                        let value = #value_expr;
+                        map.insert(key.to_string(), value.clone());
 
-                    let snake = camel_to_snake(key);
-                    if snake != key {
-                        map.entry(snake)
-                            .or_insert_with(|| value.clone());
+                        let camel = snake_to_camel(key);
+                        if camel != key {
+                            map.entry(camel)
+                                .or_insert_with(|| value.clone());
+                        }
+
+                        let snake = camel_to_snake(key);
+                        if snake != key {
+                            map.entry(snake)
+                                .or_insert_with(|| value.clone());
+                        }
                     }
                 }
             }
-        }
-    });
+        })
+        };
+    let to_inserts = map_inserts(false);
+    let log_inserts = map_inserts(true);
 
-    let from_fields = fields.iter().map(|f| {
+    let from_fields = fields.iter().zip(&field_attrs).map(|(f, attrs)| {
         let field_name = f.ident.as_ref().unwrap();
-        let (key, ignore) = parse_field_attrs(f);
-        let key_lit = LitStr::new(&key, Span::call_site());
+        let key_lit = LitStr::new(&attrs.name, Span::call_site());
+
+        // `via_from = "parse"` maps the decoded value through `parse(..) -> Result<T, DbError>`.
+        let from_value = match (&attrs.via_from, &attrs.csv_delimiter) {
+            (Some(via_from), _) => {
+                quote! { #via_from(uorm::udbc::value::FromValue::from_value(v)?)? }
+            }
+            // `NULL` and `""` are empty lists; a value that is already a list is kept.
+            (None, Some(delimiter)) => quote! {
+                match v {
+                    uorm::udbc::value::Value::Null => Vec::new(),
+                    v @ uorm::udbc::value::Value::List(_) => {
+                        uorm::udbc::value::FromValue::from_value(v)?
+                    }
+                    v => {
+                        let s: String = uorm::udbc::value::FromValue::from_value(v)?;
+                        if s.is_empty() {
+                            Vec::new()
+                        } else {
+                            s.split(#delimiter).map(|part| part.to_string()).collect()
+                        }
+                    }
+                }
+            },
+            (None, None) => quote! { uorm::udbc::value::FromValue::from_value(v)? },
+        };
+        let convert = if let Some(default) = &attrs.default {
+            let ty = &f.ty;
+            quote! {
+                match v {
+                    Some(v) => #from_value,
+                    None => {
+                        let d: #ty = #default;
+                        d
+                    }
+                }
+            }
+        } else {
+            quote! {
+                let v = v.unwrap_or(uorm::udbc::value::Value::Null);
+                #from_value
+            }
+        };
 
-        if ignore {
+        if attrs.ignore {
             quote! { #field_name: Default::default(), }
         } else {
             quote! {
@@ -135,15 +218,45 @@ pub fn derive_param_impl(input: TokenStream) -> TokenStream {
                         }
                     }
 
-                    let v = v.unwrap_or(uorm::udbc::value::Value::Null);
-                    uorm::udbc::value::FromValue::from_value(v)?
+                    #convert
                 },
             }
         }
     });
 
+    // Every type parameter must be convertible in the direction of the impl. Ignored fields
+    // are filled with `Default::default()`, so their types additionally need `Default`.
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let mut to_generics = generics.clone();
+    for param in to_generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(uorm::udbc::value::ToValue));
+    }
+    let (to_impl_generics, _, to_where_clause) = to_generics.split_for_impl();
+
+    let mut from_generics = generics.clone();
+    for param in from_generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(uorm::udbc::value::FromValue));
+    }
+    if !from_generics.params.is_empty() {
+        let where_clause = from_generics.make_where_clause();
+        for (f, attrs) in fields.iter().zip(&field_attrs) {
+            if attrs.ignore {
+                let ty = &f.ty;
+                where_clause
+                    .predicates
+                    .push(syn::parse_quote!(#ty: Default));
+            }
+        }
+    }
+    let (from_impl_generics, _, from_where_clause) = from_generics.split_for_impl();
+
     TokenStream::from(quote! {
-        impl uorm::udbc::value::ToValue for #name {
+        impl #to_impl_generics uorm::udbc::value::ToValue for #name #ty_generics #to_where_clause {
             fn to_value(&self) -> uorm::udbc::value::Value {
                 #case_helpers
 
@@ -151,8 +264,16 @@ pub fn derive_param_impl(input: TokenStream) -> TokenStream {
                 #(#to_inserts)*
                 uorm::udbc::value::Value::Map(map)
             }
+
+            fn to_log_value(&self) -> uorm::udbc::value::Value {
+                #case_helpers
+
+                let mut map = std::collections::HashMap::new();
+                #(#log_inserts)*
+                uorm::udbc::value::Value::Map(map)
+            }
         }
-        impl uorm::udbc::value::FromValue for #name {
+        impl #from_impl_generics uorm::udbc::value::FromValue for #name #ty_generics #from_where_clause {
             fn from_value(v: uorm::udbc::value::Value) -> std::result::Result<Self, uorm::error::DbError> {
                 if let uorm::udbc::value::Value::Map(mut map) = v {
                     #case_helpers
@@ -166,30 +287,183 @@ pub fn derive_param_impl(input: TokenStream) -> TokenStream {
     })
 }
 
-fn parse_field_attrs(field: &syn::Field) -> (String, bool) {
-    let mut name = field.ident.as_ref().unwrap().to_string();
-    let mut ignore = false;
+/// `struct Empty {}` and `struct Unit;` map to an empty `Value::Map` and accept any map back.
+fn derive_empty_impl(name: syn::Ident, generics: syn::Generics) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    TokenStream::from(quote! {
+        impl #impl_generics uorm::udbc::value::ToValue for #name #ty_generics #where_clause {
+            fn to_value(&self) -> uorm::udbc::value::Value {
+                uorm::udbc::value::Value::Map(std::collections::HashMap::new())
+            }
+        }
+        impl #impl_generics uorm::udbc::value::FromValue for #name #ty_generics #where_clause {
+            fn from_value(v: uorm::udbc::value::Value) -> std::result::Result<Self, uorm::error::DbError> {
+                match v {
+                    uorm::udbc::value::Value::Map(_) => Ok(Self {}),
+                    _ => Err(uorm::error::DbError::TypeMismatch(format!("Expected Map, got {:?}", v))),
+                }
+            }
+        }
+    })
+}
+
+/// `#[derive(Param)]` on a fieldless enum: variants map to `Value::Enum(variant_name)`.
+fn derive_enum_impl(name: syn::Ident, generics: syn::Generics, data: syn::DataEnum) -> TokenStream {
+    let mut idents = Vec::new();
+    let mut names = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return syn::Error::new_spanned(
+                &variant.ident,
+                "Param only supports enums whose variants have no fields",
+            )
+            .to_compile_error()
+            .into();
+        }
+        match parse_variant_name(variant) {
+            Ok(n) => names.push(LitStr::new(&n, Span::call_site())),
+            Err(e) => return e.to_compile_error().into(),
+        }
+        idents.push(&variant.ident);
+    }
 
-    for attr in &field.attrs {
+    let enum_name = LitStr::new(&name.to_string(), Span::call_site());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    TokenStream::from(quote! {
+        impl #impl_generics uorm::udbc::value::ToValue for #name #ty_generics #where_clause {
+            fn to_value(&self) -> uorm::udbc::value::Value {
+                let variant: &str = match self {
+                    #(Self::#idents => #names,)*
+                };
+                uorm::udbc::value::Value::Enum(variant.to_string())
+            }
+        }
+        impl #impl_generics uorm::udbc::value::FromValue for #name #ty_generics #where_clause {
+            fn from_value(v: uorm::udbc::value::Value) -> std::result::Result<Self, uorm::error::DbError> {
+                let variant = match v.try_cast_to(uorm::udbc::value::ValueKind::Enum)? {
+                    uorm::udbc::value::Value::Enum(s) => s,
+                    other => {
+                        return Err(uorm::error::DbError::TypeMismatch(format!(
+                            "Expected Enum, got {:?}",
+                            other
+                        )));
+                    }
+                };
+                match variant.as_str() {
+                    #(#names => Ok(Self::#idents),)*
+                    other => Err(uorm::error::DbError::TypeMismatch(format!(
+                        "Unknown variant '{}' for {}",
+                        other, #enum_name
+                    ))),
+                }
+            }
+        }
+    })
+}
+
+/// The name of an enum variant, honoring `#[param("name")]` / `#[param(rename = "name")]`.
+fn parse_variant_name(variant: &syn::Variant) -> syn::Result<String> {
+    let mut name = variant.ident.to_string();
+    for attr in &variant.attrs {
         if attr.path().is_ident("param") {
             if let Ok(s) = attr.parse_args::<LitStr>() {
                 name = s.value();
                 continue;
             }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    name = s.value();
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(name)
+}
+
+/// Options collected from `#[param(...)]` on a single field.
+struct FieldAttrs {
+    /// Map key used for the field.
+    name: String,
+    /// Skip the field entirely (`#[param(ignore)]`).
+    ignore: bool,
+    /// Expression evaluated when the key is missing (`#[param(default = "...")]`).
+    default: Option<syn::Expr>,
+    /// Mask the value in log output (`#[param(sensitive)]`).
+    sensitive: bool,
+    /// Function applied before `to_value` (`#[param(via = "to_string")]`).
+    via: Option<syn::Path>,
+    /// Function applied after `from_value` (`#[param(via_from = "parse_fn")]`).
+    via_from: Option<syn::Path>,
+    /// Separator for storing a `Vec<String>` as one string (`#[param(vec_as_csv)]` uses `,`,
+    /// `#[param(vec_as_csv = "|")]` overrides it).
+    csv_delimiter: Option<String>,
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs {
+        name: field.ident.as_ref().unwrap().to_string(),
+        ignore: false,
+        default: None,
+        sensitive: false,
+        via: None,
+        via_from: None,
+        csv_delimiter: None,
+    };
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("param") {
+            if let Ok(s) = attr.parse_args::<LitStr>() {
+                attrs.name = s.value();
+                continue;
+            }
 
-            let _ = attr.parse_nested_meta(|meta| {
+            attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("ignore") {
-                    ignore = true;
+                    attrs.ignore = true;
+                } else if meta.path.is_ident("sensitive") {
+                    attrs.sensitive = true;
                 } else if meta.path.is_ident("rename") {
                     let value = meta.value()?;
                     let s: LitStr = value.parse()?;
-                    name = s.value();
+                    attrs.name = s.value();
+                } else if meta.path.is_ident("default") {
+                    let value = meta.value()?;
+                    let s: LitStr = value.parse()?;
+                    attrs.default = Some(s.parse()?);
+                } else if meta.path.is_ident("via") {
+                    let value = meta.value()?;
+                    let s: LitStr = value.parse()?;
+                    attrs.via = Some(s.parse()?);
+                } else if meta.path.is_ident("via_from") {
+                    let value = meta.value()?;
+                    let s: LitStr = value.parse()?;
+                    attrs.via_from = Some(s.parse()?);
+                } else if meta.path.is_ident("vec_as_csv") {
+                    let delimiter = if meta.input.peek(syn::Token![=]) {
+                        let s: LitStr = meta.value()?.parse()?;
+                        if s.value().is_empty() {
+                            return Err(meta.error("vec_as_csv delimiter must not be empty"));
+                        }
+                        s.value()
+                    } else {
+                        ",".to_string()
+                    };
+                    attrs.csv_delimiter = Some(delimiter);
                 }
                 Ok(())
-            });
+            })?;
         }
     }
-    (name, ignore)
+    if attrs.csv_delimiter.is_some() && (attrs.via.is_some() || attrs.via_from.is_some()) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "vec_as_csv cannot be combined with via or via_from",
+        ));
+    }
+    Ok(attrs)
 }
 
 pub fn param_impl(args: TokenStream, input: TokenStream) -> TokenStream {
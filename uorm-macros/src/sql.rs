@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::{
-    Expr, ItemFn, ItemStruct, Lit, LitStr, Meta, Result, Token,
+    Attribute, Expr, ImplItem, ItemFn, ItemImpl, ItemStruct, Lit, LitStr, Meta, Result, Token,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
@@ -13,16 +13,27 @@ use syn::{
 /// Supports both positional and named arguments:
 /// - Positional: `#[sql("my_id")]` or `#[sql("my_namespace")]`
 /// - Named: `#[sql(id = "my_id", database = "other_db", namespace = "my_ns")]`
+/// - Runtime database: `#[sql(id = "my_id", db_expr = "self.db_name")]`
+/// - Mapper method: `#[sql(id = "my_id", method = "get_one")]`
+/// - Optimistic locking: `#[sql(id = "update_user", optimistic_lock = "version")]`
 struct SqlArgs {
     /// The first positional string literal, which can represent either an ID (on functions)
     /// or a namespace (on structs).
     value: Option<String>,
     /// Explicitly provided SQL ID.
     id: Option<String>,
-    /// The name of the database driver to use (defaults to "default").
+    /// The name of the database driver to use (defaults to "default"). On methods with a
+    /// `self` receiver, a value starting with `self` is parsed as a runtime expression.
     database: Option<String>,
+    /// A Rust expression evaluating to `&str` that selects the database at runtime.
+    db_expr: Option<String>,
     /// The XML namespace where the SQL is defined.
     namespace: Option<String>,
+    /// The `Mapper` method to call (`execute`, `list`, `get`/`get_one`, or `insert` for
+    /// `insert_and_get_id`), inferred from the return type when omitted.
+    method: Option<String>,
+    /// Version field checked by `Mapper::update_with_version` for optimistic locking.
+    optimistic_lock: Option<String>,
 }
 
 impl Parse for SqlArgs {
@@ -30,14 +41,20 @@ impl Parse for SqlArgs {
         let mut value = None;
         let mut id = None;
         let mut database = None;
+        let mut db_expr = None;
         let mut namespace = None;
+        let mut method = None;
+        let mut optimistic_lock = None;
 
         if input.is_empty() {
             return Ok(SqlArgs {
                 value,
                 id,
                 database,
+                db_expr,
                 namespace,
+                method,
+                optimistic_lock,
             });
         }
 
@@ -51,7 +68,10 @@ impl Parse for SqlArgs {
                     value,
                     id,
                     database,
+                    db_expr,
                     namespace,
+                    method,
+                    optimistic_lock,
                 });
             }
             // If more arguments follow, they must be separated by a comma.
@@ -69,8 +89,14 @@ impl Parse for SqlArgs {
                     id = Some(lit_str.value());
                 } else if nv.path.is_ident("database") {
                     database = Some(lit_str.value());
+                } else if nv.path.is_ident("db_expr") {
+                    db_expr = Some(lit_str.value());
                 } else if nv.path.is_ident("namespace") {
                     namespace = Some(lit_str.value());
+                } else if nv.path.is_ident("method") {
+                    method = Some(lit_str.value());
+                } else if nv.path.is_ident("optimistic_lock") {
+                    optimistic_lock = Some(lit_str.value());
                 }
             }
         }
@@ -79,7 +105,10 @@ impl Parse for SqlArgs {
             value,
             id,
             database,
+            db_expr,
             namespace,
+            method,
+            optimistic_lock,
         })
     }
 }
@@ -93,7 +122,7 @@ pub fn sql_impl(args: TokenStream, input: TokenStream) -> TokenStream {
     let input_clone = input.clone();
     // Dispatch based on whether the attribute is applied to a struct or a function.
     if syn::parse::<ItemStruct>(input_clone).is_ok() {
-        return sql_namespace_impl(args, input);
+        return struct_namespace_impl(args, input);
     }
     generate_mapper_call(args, input)
 }
@@ -102,7 +131,7 @@ pub fn sql_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// It injects a `NAMESPACE` constant into the struct's implementation, which
 /// is then used by functions within the same struct.
-fn sql_namespace_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+fn struct_namespace_impl(args: TokenStream, input: TokenStream) -> TokenStream {
     let sql_args = parse_macro_input!(args as SqlArgs);
     let namespace = sql_args
         .namespace
@@ -124,6 +153,201 @@ fn sql_namespace_impl(args: TokenStream, input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// The entry point for the `#[sql_namespace("ns")]` attribute macro on an `impl` block.
+///
+/// Adds `namespace = "ns"` to every `#[sql]` method in the block that doesn't already name
+/// a namespace (explicitly or as `"ns.id"`), so no `NAMESPACE` constant is needed.
+pub fn sql_namespace_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+    let namespace = parse_macro_input!(args as LitStr);
+    let mut item_impl = match syn::parse::<ItemImpl>(input) {
+        Ok(item_impl) => item_impl,
+        Err(e) => {
+            return syn::Error::new(
+                e.span(),
+                "#[sql_namespace] must be applied to an impl block",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    for item in &mut item_impl.items {
+        if let ImplItem::Fn(method) = item {
+            for attr in method.attrs.iter_mut().filter(|attr| is_sql_attr(attr)) {
+                add_namespace(attr, &namespace);
+            }
+        }
+    }
+
+    TokenStream::from(quote! { #item_impl })
+}
+
+/// Matches `#[sql(...)]`, its typed variants such as `#[sql_list]`, and their
+/// `#[uorm::...]` paths.
+fn is_sql_attr(attr: &Attribute) -> bool {
+    const NAMES: &[&str] = &[
+        "sql",
+        "sql_list",
+        "sql_get",
+        "sql_insert",
+        "sql_update",
+        "sql_delete",
+    ];
+    let segments = &attr.path().segments;
+    let name = match segments.len() {
+        1 => &segments[0].ident,
+        2 if segments[0].ident == "uorm" => &segments[1].ident,
+        _ => return false,
+    };
+    NAMES.iter().any(|n| name == n)
+}
+
+fn add_namespace(attr: &mut Attribute, namespace: &LitStr) {
+    let path = attr.path().clone();
+    let tokens = match &attr.meta {
+        Meta::Path(_) => proc_macro2::TokenStream::new(),
+        Meta::List(list) => list.tokens.clone(),
+        // Not valid `#[sql]` syntax; leave it for `#[sql]` itself to report.
+        Meta::NameValue(_) => return,
+    };
+    let Ok(sql_args) = syn::parse2::<SqlArgs>(tokens.clone()) else {
+        return;
+    };
+    let dotted_id = sql_args
+        .id
+        .or(sql_args.value)
+        .is_some_and(|id| id.contains('.'));
+    if sql_args.namespace.is_some() || dotted_id {
+        return;
+    }
+
+    let tokens = append_arg(tokens, "namespace", namespace);
+    *attr = syn::parse_quote! { #[#path(#tokens)] };
+}
+
+/// Appends `name = value` to the arguments of an `#[sql]` attribute.
+fn append_arg(
+    tokens: proc_macro2::TokenStream,
+    name: &str,
+    value: &LitStr,
+) -> proc_macro2::TokenStream {
+    let name = syn::Ident::new(name, Span::call_site());
+    let trailing_comma = matches!(
+        tokens.clone().into_iter().last(),
+        Some(proc_macro2::TokenTree::Punct(p)) if p.as_char() == ','
+    );
+    if tokens.is_empty() || trailing_comma {
+        quote! { #tokens #name = #value }
+    } else {
+        quote! { #tokens, #name = #value }
+    }
+}
+
+/// The typed variants of `#[sql]`, each fixing the `Mapper` method and return type shape.
+#[derive(Clone, Copy)]
+pub enum TypedSql {
+    /// `#[sql_list]`: `Result<Vec<T>>`, calls `Mapper::list`.
+    List,
+    /// `#[sql_get]`: `Result<Option<T>>` calls `Mapper::get_one`; `Result<T>` calls
+    /// `Mapper::execute`, which fails with `DbError::NotFound` when there is no row.
+    Get,
+    /// `#[sql_insert]`: `Result<i64>` or `Result<u64>`, calls `Mapper::insert_and_get_id`.
+    Insert,
+    /// `#[sql_update]`: `Result<u64>` or `Result<i64>`, the affected rows from `Mapper::execute`.
+    Update,
+    /// `#[sql_delete]`: `Result<u64>` or `Result<i64>`, the affected rows from `Mapper::execute`.
+    Delete,
+}
+
+impl TypedSql {
+    fn attr_name(self) -> &'static str {
+        match self {
+            TypedSql::List => "sql_list",
+            TypedSql::Get => "sql_get",
+            TypedSql::Insert => "sql_insert",
+            TypedSql::Update => "sql_update",
+            TypedSql::Delete => "sql_delete",
+        }
+    }
+
+    /// The return types the attribute accepts, for error messages.
+    fn expected(self) -> &'static str {
+        match self {
+            TypedSql::List => "Result<Vec<T>>",
+            TypedSql::Get => "Result<T> or Result<Option<T>>",
+            TypedSql::Insert => "Result<i64> or Result<u64>",
+            TypedSql::Update | TypedSql::Delete => "Result<u64> or Result<i64>",
+        }
+    }
+
+    /// Picks the `method` for the `T` of a `Result<T>` return type, or `None` if `T` doesn't
+    /// have the required shape.
+    fn method_for(self, inner: &syn::Type) -> Option<&'static str> {
+        let is_vec = last_segment_generic(inner, "Vec").is_some();
+        match self {
+            TypedSql::List => is_vec.then_some("list"),
+            TypedSql::Get if is_vec => None,
+            TypedSql::Get if last_segment_generic(inner, "Option").is_some() => Some("get"),
+            TypedSql::Get => Some("execute"),
+            TypedSql::Insert => is_row_count(inner).then_some("insert"),
+            TypedSql::Update | TypedSql::Delete => is_row_count(inner).then_some("execute"),
+        }
+    }
+}
+
+/// `i64` or `u64`, the types of generated keys and affected-row counts.
+fn is_row_count(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(p) if p.path.is_ident("i64") || p.path.is_ident("u64"))
+}
+
+/// The entry point for `#[sql_list]`, `#[sql_get]`, `#[sql_insert]`, `#[sql_update]` and
+/// `#[sql_delete]`.
+///
+/// Checks the function's return type, then expands like `#[sql]` with the matching `method`.
+pub fn typed_sql_impl(kind: TypedSql, args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let item_fn = parse_macro_input!(input as ItemFn);
+    let attr_name = kind.attr_name();
+
+    let sql_args = match syn::parse2::<SqlArgs>(args.clone()) {
+        Ok(sql_args) => sql_args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    if sql_args.method.is_some() || sql_args.optimistic_lock.is_some() {
+        return syn::Error::new(
+            Span::call_site(),
+            format!(
+                "#[{}] sets the mapper method itself; use #[sql] for `method` or `optimistic_lock`",
+                attr_name
+            ),
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let output = &item_fn.sig.output;
+    let method = match output {
+        syn::ReturnType::Type(_, ty) => last_segment_generic(ty, "Result")
+            .and_then(|inner| kind.method_for(inner))
+            .ok_or_else(|| syn::spanned::Spanned::span(ty)),
+        syn::ReturnType::Default => Err(item_fn.sig.ident.span()),
+    };
+    let method = match method {
+        Ok(method) => method,
+        Err(span) => {
+            return syn::Error::new(
+                span,
+                format!("#[{}] functions must return {}", attr_name, kind.expected()),
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let args = append_arg(args, "method", &LitStr::new(method, Span::call_site()));
+    generate_mapper_call(args.into(), quote! { #item_fn }.into())
+}
+
 fn is_primitive_or_wrapper(ty: &syn::Type) -> bool {
     match ty {
         syn::Type::Path(type_path) => {
@@ -164,6 +388,74 @@ fn is_primitive_or_wrapper(ty: &syn::Type) -> bool {
     }
 }
 
+/// Picks the `Mapper` method from a `Result<R>` return type.
+///
+/// Plain `T` keeps `execute`: for a `Select` it maps the first row (or its only column) and
+/// fails with `DbError::NotFound` when there are none, and it also covers data-modifying
+/// statements returning affected rows or generated keys, which a purely syntactic check
+/// cannot tell apart from single-row queries. `method = "get_one"` opts a bare `Result<T>`
+/// into reading only the first row, with `DbError::NotFound` when there is none.
+fn infer_method(output: &syn::ReturnType) -> &'static str {
+    let syn::ReturnType::Type(_, ty) = output else {
+        return "execute";
+    };
+    let Some(inner) = last_segment_generic(ty, "Result") else {
+        return "execute";
+    };
+    if last_segment_generic(inner, "Vec").is_some() {
+        "list"
+    } else if last_segment_generic(inner, "Option").is_some() {
+        "get_one"
+    } else {
+        "execute"
+    }
+}
+
+/// Whether the function returns `Result<Option<T>>`.
+fn returns_option(output: &syn::ReturnType) -> bool {
+    match output {
+        syn::ReturnType::Type(_, ty) => last_segment_generic(ty, "Result")
+            .and_then(|inner| last_segment_generic(inner, "Option"))
+            .is_some(),
+        syn::ReturnType::Default => false,
+    }
+}
+
+/// If `ty` is `Name<A, ...>` (by its last path segment), returns `A`.
+fn last_segment_generic<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(t) => Some(t),
+        _ => None,
+    })
+}
+
+/// Whether a `database` value is an expression on the receiver (`self` or `self.…`) rather
+/// than a database name such as `selfservice`.
+fn is_self_expr(db: &str) -> bool {
+    db == "self" || db.starts_with("self.")
+}
+
+/// Parses a runtime database selector such as `self.db_name` into tokens.
+fn parse_db_expr(expr_str: &str) -> Result<proc_macro2::TokenStream> {
+    let expr = syn::parse_str::<Expr>(expr_str).map_err(|e| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("Invalid database expression `{}`: {}", expr_str, e),
+        )
+    })?;
+    Ok(quote! { #expr })
+}
+
 /// Handles `#[sql]` when applied to a function.
 ///
 /// It transforms the function body to:
@@ -199,8 +491,35 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
         (None, raw_id)
     };
 
-    // Determine the database name, defaulting to "default".
-    let db_name = sql_args.database.unwrap_or_else(|| "default".to_string());
+    // Determine the database name, defaulting to "default". Methods with a `self` receiver
+    // may pick it at runtime with an expression such as `database = "self.db_name()"`.
+    let has_receiver = fn_args
+        .iter()
+        .any(|arg| matches!(arg, syn::FnArg::Receiver(_)));
+    // `db_expr` is always an expression; it cannot be combined with a static `database`.
+    let db_name_tokens = match (sql_args.db_expr, sql_args.database) {
+        (Some(_), Some(_)) => {
+            return syn::Error::new(
+                Span::call_site(),
+                "`db_expr` and `database` cannot be used together",
+            )
+            .to_compile_error()
+            .into();
+        }
+        (Some(expr_str), None) => match parse_db_expr(&expr_str) {
+            Ok(tokens) => tokens,
+            Err(e) => return e.to_compile_error().into(),
+        },
+        (None, Some(db)) if has_receiver && is_self_expr(&db) => match parse_db_expr(&db) {
+            Ok(tokens) => tokens,
+            Err(e) => return e.to_compile_error().into(),
+        },
+        (None, Some(db)) => {
+            let db_name_lit = LitStr::new(&db, Span::call_site());
+            quote! { #db_name_lit }
+        }
+        (None, None) => quote! { "default" },
+    };
 
     // Prepare fields for the anonymous arguments struct that will be serialized.
     let mut struct_fields = Vec::new();
@@ -326,6 +645,7 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
         )
     } else {
         let mut to_value_inserts = Vec::new();
+        let mut to_log_value_inserts = Vec::new();
         for arg in fn_args {
             if let syn::FnArg::Typed(pat_type) = arg
                 && let syn::Pat::Ident(pat_ident) = &*pat_type.pat
@@ -335,6 +655,9 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
                 to_value_inserts.push(quote! {
                      map.insert(#ident_str.to_string(), uorm::udbc::value::ToValue::to_value(&self.#ident));
                 });
+                to_log_value_inserts.push(quote! {
+                     map.insert(#ident_str.to_string(), uorm::udbc::value::ToValue::to_log_value(&self.#ident));
+                });
             }
         }
 
@@ -349,6 +672,11 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
                         #(#to_value_inserts)*
                         uorm::udbc::value::Value::Map(map)
                     }
+                    fn to_log_value(&self) -> uorm::udbc::value::Value {
+                        let mut map = std::collections::HashMap::new();
+                        #(#to_log_value_inserts)*
+                        uorm::udbc::value::Value::Map(map)
+                    }
                 }
             },
             quote! {
@@ -359,12 +687,88 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
         )
     };
 
-    // The method to call on the mapper (usually 'execute' or 'query').
-    // Note: The macro currently hardcodes 'execute', but the actual behavior
-    // is often determined by the return type in more complex implementations.
-    let method_ident = syn::Ident::new("execute", Span::call_site());
+    // The method to call on the mapper: explicit `method = "..."`, otherwise inferred from the
+    // return type (`Vec<T>` -> `list`, `Option<T>` -> `get_one`, anything else -> `execute`).
+    let method_name = match sql_args.method.as_deref() {
+        Some("execute") => "execute",
+        Some("list") => "list",
+        Some("get") | Some("get_one") => "get_one",
+        Some("insert") => "insert_and_get_id",
+        Some(other) => {
+            return syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "Unsupported method `{}`; expected `execute`, `list`, `get_one` or `insert`",
+                    other
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+        None => infer_method(output),
+    };
+    let method_ident = syn::Ident::new(method_name, Span::call_site());
+
+    // `optimistic_lock = "version"` routes the call through `update_with_version`.
+    let mapper_call = match (&sql_args.optimistic_lock, &sql_args.method) {
+        (Some(_), Some(_)) => {
+            return syn::Error::new(
+                Span::call_site(),
+                "`optimistic_lock` cannot be combined with `method`",
+            )
+            .to_compile_error()
+            .into();
+        }
+        (Some(field), None) => {
+            let field_lit = LitStr::new(field, Span::call_site());
+            quote! { update_with_version(&__uorm_sql_id, &__uorm_args, #field_lit).await }
+        }
+        // The generated key comes back as `u64`; convert it to the declared integer type.
+        (None, _) if method_name == "insert_and_get_id" => quote! {
+            insert_and_get_id(&__uorm_sql_id, &__uorm_args)
+                .await
+                .and_then(|id| uorm::udbc::value::FromValue::from_value(uorm::udbc::value::Value::U64(id)))
+        },
+        // `get_one` into a bare `Result<T>` reads the first row and turns "no row" into an error.
+        (None, Some(_)) if method_name == "get_one" && !returns_option(output) => quote! {
+            get_one(&__uorm_sql_id, &__uorm_args).await.and_then(|row| {
+                row.ok_or_else(|| {
+                    uorm::error::DbError::NotFound(format!("No rows returned for {}", __uorm_sql_id))
+                })
+            })
+        },
+        (None, _) => quote! { #method_ident(&__uorm_sql_id, &__uorm_args).await },
+    };
     let id_lit = LitStr::new(&final_id, Span::call_site());
-    let db_name_lit = LitStr::new(&db_name, Span::call_site());
+
+    // `list!()`, `get!()`, `count!()` and `insert!()` name the `Mapper` method explicitly
+    // instead of inferring it from the return type.
+    let explicit_macros = [
+        ("list", quote! { list(&__uorm_sql_id, &__uorm_args) }),
+        ("get", quote! { get(&__uorm_sql_id, &__uorm_args) }),
+        ("count", quote! { count(&__uorm_sql_id, &__uorm_args) }),
+        (
+            "insert",
+            quote! { insert_and_get_id(&__uorm_sql_id, &__uorm_args) },
+        ),
+    ]
+    .into_iter()
+    .map(|(name, call)| {
+        let name = syn::Ident::new(name, Span::call_site());
+        quote! {
+            #[allow(unused_macros)]
+            macro_rules! #name {
+                () => {{
+                    let __uorm_sql_id = format!("{}.{}", __uorm_namespace, __uorm_id);
+                    let __uorm_mapper = uorm::driver_manager::U
+                        .mapper_by_name(__uorm_db_name)
+                        .expect("Database driver not found");
+                    __uorm_mapper.#call.await
+                }};
+            }
+        }
+    });
+    let explicit_macros = quote! { #(#explicit_macros)* };
 
     // Determine the namespace: either explicitly provided or retrieved from the struct's `NAMESPACE` constant.
     let namespace_tokens = if let Some(ns) = sql_args.namespace {
@@ -384,7 +788,7 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
             #args_struct_init
             let __uorm_namespace: &'static str = #namespace_tokens;
             let __uorm_id: &'static str = #id_lit;
-            let __uorm_db_name: &'static str = #db_name_lit;
+            let __uorm_db_name: &str = &(#db_name_tokens);
 
             // Inject a local `exec!()` macro into the function body.
             // This local macro captures the context (namespace, id, db_name) and
@@ -395,9 +799,10 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
                     let __uorm_mapper = uorm::driver_manager::U
                         .mapper_by_name(__uorm_db_name)
                         .expect("Database driver not found");
-                    __uorm_mapper.#method_ident(&__uorm_sql_id, &__uorm_args).await
+                    __uorm_mapper.#mapper_call
                 }};
             }
+            #explicit_macros
 
             #block
         }
@@ -405,3 +810,50 @@ fn generate_mapper_call(args: TokenStream, input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_sql_checks_the_result_type() {
+        let method = |kind: TypedSql, ty: syn::Type| kind.method_for(&ty);
+        assert_eq!(
+            method(TypedSql::Update, syn::parse_quote!(u64)),
+            Some("execute")
+        );
+        assert_eq!(
+            method(TypedSql::Delete, syn::parse_quote!(i64)),
+            Some("execute")
+        );
+        assert_eq!(method(TypedSql::Update, syn::parse_quote!(String)), None);
+        assert_eq!(method(TypedSql::Delete, syn::parse_quote!(Vec<u64>)), None);
+        assert_eq!(
+            method(TypedSql::Insert, syn::parse_quote!(i64)),
+            Some("insert")
+        );
+        assert_eq!(method(TypedSql::Get, syn::parse_quote!(Vec<User>)), None);
+    }
+
+    #[test]
+    fn only_self_paths_are_database_expressions() {
+        assert!(is_self_expr("self"));
+        assert!(is_self_expr("self.db_name()"));
+        assert!(!is_self_expr("selfservice"));
+        assert!(!is_self_expr("self_db"));
+    }
+
+    #[test]
+    fn sql_namespace_covers_every_sql_attribute() {
+        let attrs: Vec<Attribute> = vec![
+            syn::parse_quote!(#[sql("a")]),
+            syn::parse_quote!(#[sql_list("a")]),
+            syn::parse_quote!(#[sql_get("a")]),
+            syn::parse_quote!(#[sql_insert("a")]),
+            syn::parse_quote!(#[sql_update("a")]),
+            syn::parse_quote!(#[uorm::sql_delete("a")]),
+        ];
+        assert!(attrs.iter().all(is_sql_attr));
+        assert!(!is_sql_attr(&syn::parse_quote!(#[sql_other("a")])));
+    }
+}
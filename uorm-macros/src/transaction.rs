@@ -7,25 +7,45 @@ use syn::{
 
 struct TransactionArgs {
     database: Option<String>,
+    session_expr: Option<Expr>,
 }
 
 impl Parse for TransactionArgs {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut database = None;
+        let mut session_expr = None;
         if !input.is_empty() {
             let metas: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(input)?;
             for meta in metas {
                 if let Meta::NameValue(nv) = meta
                     && let Some(ident) = nv.path.get_ident()
-                    && ident == "database"
                     && let Expr::Lit(expr_lit) = &nv.value
                     && let Lit::Str(lit_str) = &expr_lit.lit
                 {
-                    database = Some(lit_str.value());
+                    if ident == "database" {
+                        database = Some(lit_str.value());
+                    } else if ident == "session_expr" {
+                        let expr = syn::parse_str::<Expr>(&lit_str.value()).map_err(|e| {
+                            syn::Error::new(
+                                lit_str.span(),
+                                format!("session_expr is not a valid expression: {}", e),
+                            )
+                        })?;
+                        session_expr = Some((lit_str.span(), expr));
+                    }
                 }
             }
         }
-        Ok(TransactionArgs { database })
+        if let (Some(_), Some((span, _))) = (&database, &session_expr) {
+            return Err(syn::Error::new(
+                *span,
+                "`database` and `session_expr` cannot be used together",
+            ));
+        }
+        Ok(TransactionArgs {
+            database,
+            session_expr: session_expr.map(|(_, expr)| expr),
+        })
     }
 }
 
@@ -35,35 +55,50 @@ pub fn transaction_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let block = &func.block;
 
-    let db_name = args.database.unwrap_or_else(|| "default".to_string());
-    let db_name_lit = LitStr::new(&db_name, proc_macro2::Span::call_site());
+    // `session_expr` may evaluate to a `Session` or a `&Session`; borrowing it covers both.
+    let session_init = match &args.session_expr {
+        Some(expr) => quote! { let __uorm_session = &(#expr); },
+        None => {
+            let db_name = args.database.unwrap_or_else(|| "default".to_string());
+            let db_name_lit = LitStr::new(&db_name, proc_macro2::Span::call_site());
+            quote! {
+                let __uorm_mapper = uorm::driver_manager::U
+                    .mapper_by_name(#db_name_lit)
+                    .expect("Database driver not found");
+                let __uorm_session =
+                    &uorm::executor::session::Session::new(__uorm_mapper.pool.clone());
+            }
+        }
+    };
+    // The body runs in a task-local scope so the transaction follows the task across worker
+    // threads.
     let new_block = quote! {
         {
-            let __uorm_mapper = uorm::driver_manager::U
-                .mapper_by_name(#db_name_lit)
-                .expect("Database driver not found");
-            let __uorm_session = uorm::executor::session::Session::new(__uorm_mapper.pool.clone());
+            uorm::executor::session::TaskLocalTransaction::scope(async {
+                #session_init
 
-            let __uorm_tx_started = !__uorm_session.is_transaction_active();
-            if __uorm_tx_started {
-                if let Err(e) = __uorm_session.begin().await {
-                    return uorm::TransactionResult::from_db_error(e);
+                let __uorm_tx_started = !__uorm_session.is_transaction_active();
+                if __uorm_tx_started {
+                    if let Err(e) = __uorm_session.begin().await {
+                        return uorm::TransactionResult::from_db_error(e);
+                    }
                 }
-            }
 
-            let result = (async #block).await;
+                let result = (async #block).await;
 
-            if __uorm_tx_started {
-                if uorm::TransactionResult::is_ok(&result) {
-                    if let Err(e) = __uorm_session.commit().await {
-                        return uorm::TransactionResult::from_db_error(e);
+                if __uorm_tx_started {
+                    if uorm::TransactionResult::is_ok(&result) {
+                        if let Err(e) = __uorm_session.commit().await {
+                            return uorm::TransactionResult::from_db_error(e);
+                        }
+                    } else {
+                        let _ = __uorm_session.rollback().await;
                     }
-                } else {
-                    let _ = __uorm_session.rollback().await;
                 }
-            }
 
-            result
+                result
+            })
+            .await
         }
     };
 